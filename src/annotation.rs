@@ -0,0 +1,99 @@
+//! Named, colored byte ranges that document the layout of a file.
+//!
+//! Annotations are loaded from a file where every line has the form `offset length color label`.
+//! The offset and length are decimal, or hexadecimal if preceded with "0x", the color is either a
+//! name like `red` or an RGB value like `#ff8000`, and the label is the rest of the line. Empty
+//! lines and lines starting with `#` are ignored.
+
+use std::ops::Range;
+
+use ratatui::style::Color;
+
+use crate::windows::parse_number;
+
+/// A labeled range of bytes that is highlighted in the hex editor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    pub(crate) range: Range<usize>,
+    pub(crate) label: String,
+    pub(crate) color: Color,
+}
+
+/// Parses the contents of an annotations file into annotations sorted by their starting offset.
+///
+/// # Errors
+///
+/// This errors with the offending line number when a line cannot be parsed.
+pub fn parse(text: &str) -> Result<Vec<Annotation>, String> {
+    let mut annotations = text
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| parse_line(line).map_err(|e| format!("Line {line_number}: {e}")))
+        .collect::<Result<Vec<_>, _>>()?;
+    annotations.sort_by_key(|annotation| annotation.range.start);
+    Ok(annotations)
+}
+
+fn parse_line(line: &str) -> Result<Annotation, String> {
+    let mut rest = line;
+    let mut fields = [""; 3];
+    for field in &mut fields {
+        let (next, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if next.is_empty() {
+            return Err(String::from("expected `offset length color label`"));
+        }
+        *field = next;
+        rest = remaining.trim_start();
+    }
+    let [offset, length, color] = fields;
+    let offset = parse_number(offset).map_err(|e| format!("invalid offset: {e}"))?;
+    let length = parse_number(length).map_err(|e| format!("invalid length: {e}"))?;
+    if length == 0 {
+        return Err(String::from("length must be greater than 0"));
+    }
+    let color = color.parse::<Color>().map_err(|_| format!("unknown color: {color}"))?;
+    let label = rest.to_string();
+
+    Ok(Annotation { range: offset..offset.saturating_add(length), label, color })
+}
+
+/// Finds the annotation containing `offset`, preferring the one that starts last when
+/// annotations overlap.
+pub(crate) fn annotation_at(annotations: &[Annotation], offset: usize) -> Option<&Annotation> {
+    let candidates = annotations.partition_point(|annotation| annotation.range.start <= offset);
+    annotations[..candidates].iter().rev().find(|annotation| annotation.range.contains(&offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "# header\n0x10 4 red magic number\n\n0 0x10 #00ff00 header\n";
+        let annotations = parse(text).unwrap();
+        assert_eq!(
+            annotations,
+            vec![
+                Annotation {
+                    range: 0..16,
+                    label: String::from("header"),
+                    color: Color::Rgb(0, 255, 0)
+                },
+                Annotation {
+                    range: 16..20,
+                    label: String::from("magic number"),
+                    color: Color::Red
+                },
+            ]
+        );
+        assert_eq!(annotation_at(&annotations, 17).map(|a| a.label.as_str()), Some("magic number"));
+        assert_eq!(annotation_at(&annotations, 20), None);
+
+        assert!(parse("0 0 red empty").is_err());
+        assert!(parse("0 4 notacolor label").is_err());
+        assert!(parse("0x10").is_err());
+    }
+}
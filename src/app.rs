@@ -6,26 +6,70 @@
 //! [`ScreenHandler`]: crate::screen::Handler
 //! [`LabelHandler`]: crate::label::Handler
 
-use std::{error::Error, fs::File, process};
+use std::{
+    cmp,
+    error::Error,
+    fmt,
+    fs::{self, File},
+    iter, mem,
+    ops::Range,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
-use arboard::Clipboard;
-use ratatui::crossterm::event::{self, Event, KeyEventKind};
+use crossbeam::channel::TryRecvError;
+use ratatui::backend::Backend;
+use ratatui::crossterm::event::{self, Event, KeyEvent, KeyEventKind};
 use ratatui::layout::Rect;
+use ratatui::style::Color;
 use ratatui::Frame;
+use regex::bytes::Regex;
 
+use crate::annotation::{self, Annotation};
+use crate::backup::{self, Autosave};
 use crate::buffer::AsyncBuffer;
+use crate::character::RichChar;
+use crate::clipboard::Clipboard;
 use crate::decoder::Encoding;
-use crate::windows::search::Search;
+#[cfg(feature = "disasm")]
+use crate::disasm::{Arch, Disassembler, DISASM_TITLE};
+use crate::keymap::{self, Keymap};
+use crate::pattern::Pattern;
+use crate::search_index::{Matcher, SearchIndex};
+use crate::symbol::Symbol;
+use crate::theme::{self, Theme};
+use crate::windows::search::{jump_to_next_match, Search, SearchDirection};
 use crate::{
     input,
-    label::Handler as LabelHandler,
+    label::{self, Handler as LabelHandler},
     screen::Handler as ScreenHandler,
     windows::{
-        editor::Editor, jump_to_byte::JumpToByte, unsaved_changes::UnsavedChanges, KeyHandler,
+        self, align::AlignCursor, append::Append, checksum::Checksum, editor::Editor, fill::Fill,
+        hash::Hash, histogram::Histogram, info::Info, insert_bytes::InsertBytes,
+        jump_to_byte::JumpToByte, notifications::Notifications, protected_edit::ProtectedEdit,
+        replace::Replace, restore_backup::RestoreBackup, split::Split, strings::Strings,
+        symbols::Symbols, unsaved_changes::UnsavedChanges, write_value::WriteValue, KeyHandler,
         Window,
     },
 };
 
+/// The prefix of a search term that is interpreted as a regular expression.
+pub(crate) const REGEX_PREFIX: &str = "re:";
+
+/// The prefix of a search term that is interpreted as a pattern of bits, where `?` matches either
+/// bit.
+pub(crate) const BITS_PREFIX: &str = "bits:";
+
+/// The prefix of a search term that is interpreted as hexadecimal, where `?` matches any nibble.
+pub(crate) const HEX_PREFIX: &str = "hex:";
+
+/// The prefix of a search term that is interpreted as ASCII, which unprefixed search terms are as
+/// well. It lets text that starts with another prefix be searched for.
+pub(crate) const ASCII_PREFIX: &str = "ascii:";
+
+/// How often the screen is refreshed with the matches of a search while they are being found.
+const SEARCH_REFRESH_INTERVAL: Duration = Duration::from_millis(50);
+
 /// Enum that represent grouping of 4 bits in a byte.
 ///
 /// For example, the first nibble in 0XF4 is 1111, or the F in hexadecimal. This is specified by
@@ -45,6 +89,139 @@ impl Nibble {
     }
 }
 
+/// Whether typing into an editor changes the bytes under the cursor or inserts new ones.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub(crate) enum EditMode {
+    #[default]
+    Overwrite,
+    Insert,
+}
+
+impl EditMode {
+    pub(crate) fn toggle(&mut self) {
+        match self {
+            Self::Overwrite => *self = Self::Insert,
+            Self::Insert => *self = Self::Overwrite,
+        }
+    }
+}
+
+impl fmt::Display for EditMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overwrite => write!(f, "Overwrite"),
+            Self::Insert => write!(f, "Insert"),
+        }
+    }
+}
+
+/// What the colors of the bytes in the editors are based on.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub(crate) enum ColorMode {
+    /// The category of their character, like ASCII or whitespace.
+    #[default]
+    Character,
+    /// Their value, from dark for 0x00 to bright for 0xFF like a heat map.
+    HeatMap,
+}
+
+impl ColorMode {
+    pub(crate) fn toggle(&mut self) {
+        match self {
+            Self::Character => *self = Self::HeatMap,
+            Self::HeatMap => *self = Self::Character,
+        }
+    }
+}
+
+/// What moving left or right past an end of the file does.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub(crate) enum FileEdges {
+    /// The cursor stops at the first or last byte.
+    #[default]
+    Stop,
+    /// The cursor continues from the other end of the file.
+    Wrap,
+}
+
+/// The fewest digits that addresses are padded to in the address column.
+pub(crate) const ADDRESS_DIGITS: usize = 8;
+
+/// The base that addresses are displayed in.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum AddressRadix {
+    #[default]
+    Hex,
+    Dec,
+    Oct,
+}
+
+impl AddressRadix {
+    pub(crate) fn cycle(&mut self) {
+        *self = match self {
+            Self::Hex => Self::Dec,
+            Self::Dec => Self::Oct,
+            Self::Oct => Self::Hex,
+        }
+    }
+
+    /// Returns the number of digits that the address column needs to fit the largest address,
+    /// which is at least [`ADDRESS_DIGITS`].
+    pub(crate) fn row_digits(self, largest: usize) -> usize {
+        self.format_row(largest, ADDRESS_DIGITS).len()
+    }
+
+    /// Formats an address for the address column, padded to the number of digits so that every
+    /// row has the same width.
+    pub(crate) fn format_row(self, address: usize, digits: usize) -> String {
+        match self {
+            Self::Hex => format!("{address:0digits$X}"),
+            Self::Dec => format!("{address:0digits$}"),
+            Self::Oct => format!("{address:0digits$o}"),
+        }
+    }
+
+    /// Formats an address with a prefix that identifies the radix, like the `Offset` label.
+    pub(crate) fn format_offset(self, address: usize) -> String {
+        match self {
+            Self::Hex => format!("{address:#X}"),
+            Self::Dec => format!("{address}"),
+            Self::Oct => format!("{address:#o}"),
+        }
+    }
+}
+
+impl fmt::Display for AddressRadix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Hex => write!(f, "Hexadecimal"),
+            Self::Dec => write!(f, "Decimal"),
+            Self::Oct => write!(f, "Octal"),
+        }
+    }
+}
+
+/// What typing over part of a multibyte UTF-8 character in the text editor does.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum MultibyteEdit {
+    /// The whole character is replaced by the typed one.
+    #[default]
+    Replace,
+    /// The edit is refused with a warning, since it would corrupt the character.
+    Warn,
+}
+
+/// How bytes are typed in the hex editor.
+#[derive(PartialEq, Eq, Copy, Clone, Debug, Default)]
+pub enum HexEntry {
+    /// Every digit is written as soon as it is typed, editing one nibble at a time.
+    #[default]
+    Nibble,
+    /// The first digit is held until the second one is typed or enter is pressed, and then the
+    /// whole byte is written. Escape discards it.
+    Byte,
+}
+
 /// An instance of a user action, used to implement the undo feature.
 ///
 /// These actions record the previous state - deleting the first byte (x00) correlates to
@@ -55,12 +232,40 @@ pub(crate) enum Action {
 
     /// Tracks when a user deletes a byte..
     Delete(usize, u8),
+
+    /// Tracks when a command overwrites a series of bytes at once.
+    Overwrite(usize, Vec<u8>),
+
+    /// Tracks when a command overwrites several series of bytes at once, which don't overlap.
+    OverwriteMany(Vec<(usize, Vec<u8>)>),
+
+    /// Tracks when a user inserts a new byte.
+    Insert(usize),
+
+    /// Tracks when bytes are appended to the end of the contents, which had the given length.
+    Append(usize),
+
+    /// Tracks when a series of bytes is replaced by the given number of new bytes.
+    Replace(usize, Vec<u8>, usize),
+}
+
+/// An edit that targeted a protected byte and is waiting for the user to confirm it.
+#[derive(Clone, Copy)]
+pub(crate) enum PendingEdit {
+    Char(char),
+    /// A byte typed with an escape like `\x1b` in the ASCII editor.
+    Byte(u8),
+    Backspace,
+    Delete,
 }
 
 /// State Information needed by the [`ScreenHandler`] and [`KeyHandler`].
 pub struct Data {
-    /// The file under editing.
-    pub file: File,
+    /// The file under editing, or None when editing bytes in memory.
+    pub file: Option<File>,
+
+    /// The path of the file under editing, if it is known.
+    pub(crate) path: Option<PathBuf>,
 
     /// The file content.
     pub(crate) contents: AsyncBuffer,
@@ -74,12 +279,31 @@ pub struct Data {
     /// Offset of the first content byte that is visible on the screen.
     pub(crate) start_address: usize,
 
+    /// The address of the first byte of the file, which is added to the displayed addresses.
+    pub(crate) base: usize,
+
+    /// The base that the address column and `Offset` label are displayed in.
+    pub(crate) address_radix: AddressRadix,
+
     /// Offset of the content byte under cursor.
     pub(crate) offset: usize,
 
     /// The nibble that is currently selected in the Hex viewport.
     pub(crate) nibble: Nibble,
 
+    /// Whether typing inserts new bytes or overwrites existing ones.
+    pub(crate) edit_mode: EditMode,
+
+    /// What typing over part of a multibyte UTF-8 character does.
+    pub(crate) multibyte_edit: MultibyteEdit,
+
+    /// Whether hex digits are written one at a time or a byte at a time.
+    pub(crate) hex_entry: HexEntry,
+
+    /// The first digit of a byte typed in the hex editor, held until the byte is finished when
+    /// typing whole bytes.
+    pub(crate) pending_nibble: Option<u8>,
+
     /// The last clicked (key down AND key up) label/window.
     pub(crate) last_click: Window,
 
@@ -92,8 +316,8 @@ pub struct Data {
     /// The nibble that was last hovered from the drag.
     pub(crate) drag_nibble: Option<Nibble>,
 
-    /// Copies label data to your clipboard.
-    pub(crate) clipboard: Option<Clipboard>,
+    /// Copies label data to your clipboard, or keeps it within the session if there is none.
+    pub(crate) clipboard: Clipboard,
 
     /// The editor that is currently selected. This editor will be refocused upon a popup closing.
     pub(crate) editor: Editor,
@@ -106,26 +330,663 @@ pub struct Data {
 
     /// List of all offsets that the search term was found at.
     pub(crate) search_offsets: Vec<usize>,
+
+    /// The length of each match in `search_offsets`.
+    pub(crate) search_lengths: Vec<usize>,
+
+    /// The reindex that is still finding matches of the search term, if there is one.
+    pub(crate) search_index: Option<SearchIndex>,
+
+    /// The number of actions when the search matches were last brought up to date with the
+    /// contents, or None if an undo outdated them.
+    pub(crate) search_actions: Option<usize>,
+
+    /// The direction of a search that is waiting for the reindex to find its next match.
+    pub(crate) pending_search: Option<SearchDirection>,
+
+    /// Byte ranges that require confirmation before they can be edited.
+    pub(crate) protected: Vec<Range<usize>>,
+
+    /// An edit to a protected byte that is waiting on the user's confirmation.
+    pub(crate) pending_edit: Option<PendingEdit>,
+
+    /// Set while a confirmed edit is replayed so that it is not intercepted again.
+    pub(crate) edit_confirmed: bool,
+
+    /// Labeled byte ranges loaded by the user, sorted by their starting offset.
+    pub(crate) annotations: Vec<Annotation>,
+
+    /// Named offsets loaded by the user, sorted by their offset.
+    pub(crate) symbols: Vec<Symbol>,
+
+    /// The contents of the file opened with `--diff`, which are displayed next to the hex editor
+    /// and compared with the contents byte by byte.
+    pub(crate) diff: Option<AsyncBuffer>,
+
+    /// Offsets the user bookmarked to quickly jump between, in ascending order.
+    pub(crate) bookmarks: Vec<usize>,
+
+    /// The number of bytes in the pointers that are followed, from 1 to 8.
+    pub(crate) pointer_size: usize,
+
+    /// The offsets that pointers were followed from, most recent last, which going back returns to.
+    pub(crate) pointer_history: Vec<usize>,
+
+    /// The number of lines that the mouse wheel scrolls by.
+    pub(crate) scroll_lines: usize,
+
+    /// The number of rows kept above and below the cursor when the viewport scrolls to it.
+    pub(crate) scrolloff: usize,
+
+    /// The number of times to repeat the next movement, while it is being typed after `#`.
+    pub(crate) count: Option<usize>,
+
+    /// The characters typed after a backslash in the ASCII editor, until they make up an escape
+    /// like `\x1b`.
+    pub(crate) escape: Option<String>,
+
+    /// The size of the records whose starts tab moves the cursor to and the hex editor highlights.
+    /// Tab moves by the number of bytes per line if unset.
+    pub(crate) alignment: Option<usize>,
+
+    /// The number of bytes that the left and right keys move the cursor by, like the size of the
+    /// integers in an array. This is 1, 2, 4, or 8.
+    pub(crate) step: usize,
+
+    /// Whether left and right wrap around from one end of the file to the other or stop at it.
+    pub(crate) edges: FileEdges,
+
+    /// Whether bytes are colored by their character or by their value.
+    pub(crate) color_mode: ColorMode,
+
+    /// The colors used to display the contents.
+    pub(crate) theme: Theme,
+
+    /// Writes backups of the contents while they're edited, if enabled with `--autosave`.
+    pub(crate) autosave: Option<Autosave>,
+
+    /// Generates the bytes of random fills.
+    #[cfg(feature = "random")]
+    pub(crate) rng: fastrand::Rng,
 }
 
 impl Data {
-    /// Reindexes contents to find locations of the user's search term.
-    pub(crate) fn reindex_search(&mut self) {
-        self.search_offsets = self
-            .contents
-            .windows(self.search_term.len())
-            .enumerate()
-            .filter_map(|(idx, w)| (w == self.search_term.as_bytes()).then_some(idx))
+    /// Creates the state of a file whose cursor starts at the offset, scrolled so that the screen
+    /// starts at the start address.
+    ///
+    /// # Errors
+    ///
+    /// This errors when the file given with `--diff` can't be opened.
+    fn new(
+        file: Option<File>,
+        contents: AsyncBuffer,
+        encoding: Encoding,
+        (offset, start_address): (usize, usize),
+        options: &Options,
+        theme: Theme,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            file,
+            path: options.path.clone(),
+            contents,
+            encoding,
+            dirty: false,
+            start_address,
+            base: options.base,
+            address_radix: options.address_radix,
+            offset,
+            nibble: Nibble::Beginning,
+            edit_mode: EditMode::Overwrite,
+            multibyte_edit: options.multibyte_edit,
+            hex_entry: options.hex_entry,
+            pending_nibble: None,
+            last_click: Window::Unhandled,
+            drag_enabled: false,
+            last_drag: None,
+            drag_nibble: None,
+            clipboard: Clipboard::new(),
+            editor: Editor::Hex,
+            actions: vec![],
+            search_term: String::new(),
+            search_offsets: Vec::new(),
+            search_lengths: Vec::new(),
+            search_index: None,
+            search_actions: None,
+            pending_search: None,
+            protected: Vec::new(),
+            pending_edit: None,
+            edit_confirmed: false,
+            annotations: options.annotations.clone(),
+            symbols: options.symbols.clone(),
+            diff: options.diff.as_deref().map(Self::open_diff).transpose()?,
+            bookmarks: Vec::new(),
+            pointer_size: options.pointer_size,
+            pointer_history: Vec::new(),
+            scroll_lines: options.scroll_lines,
+            scrolloff: options.scrolloff,
+            count: None,
+            escape: None,
+            step: 1,
+            edges: if options.wrap { FileEdges::Wrap } else { FileEdges::Stop },
+            color_mode: ColorMode::default(),
+            alignment: options.alignment,
+            theme,
+            autosave: options
+                .autosave
+                .zip(options.path.as_deref())
+                .map(|(interval, path)| Autosave::new(path, interval)),
+            #[cfg(feature = "random")]
+            rng: options.seed.map_or_else(fastrand::Rng::new, fastrand::Rng::with_seed),
+        })
+    }
+
+    /// Returns the name of the file, or `[no file]` when editing bytes in memory.
+    pub(crate) fn file_name(&self) -> String {
+        self.path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map_or_else(|| String::from("[no file]"), |name| name.to_string_lossy().into_owned())
+    }
+
+    /// Opens the file that is compared with the contents when `--diff` is given.
+    fn open_diff(path: &Path) -> Result<AsyncBuffer, String> {
+        File::open(path)
+            .map_err(|e| e.to_string())
+            .and_then(|file| AsyncBuffer::new(&file).map_err(|e| e.to_string()))
+            .map_err(|e| format!("Can't open {} to compare with: {e}", path.display()))
+    }
+
+    /// Starts reindexing contents on a background thread to find locations of the user's search
+    /// term, cancelling the previous reindex. The matches are added to `search_offsets` as they
+    /// are found by [`receive_search_matches`](Self::receive_search_matches).
+    ///
+    /// # Errors
+    ///
+    /// This errors when the search term is a regular expression that fails to compile.
+    pub(crate) fn reindex_search(&mut self) -> Result<(), String> {
+        let matcher = self.search_matcher()?;
+        self.search_offsets.clear();
+        self.search_lengths.clear();
+        self.search_actions = Some(self.actions.len());
+        self.search_index = Some(SearchIndex::new(self.contents.snapshot(), matcher));
+        Ok(())
+    }
+
+    /// Brings the search matches up to date with the edits made since they were found. Bytes
+    /// that were typed over only need the matches around them updated, but any other edit needs a
+    /// reindex.
+    ///
+    /// # Errors
+    ///
+    /// This errors when the search term is a regular expression that fails to compile.
+    pub(crate) fn update_search(&mut self) -> Result<(), String> {
+        let Some(edits) = self.search_actions.and_then(|n| self.actions.get(n..)) else {
+            return self.reindex_search();
+        };
+        let typed: Option<Vec<usize>> = edits
+            .iter()
+            .map(|action| match action {
+                Action::CharacterInput(offset, ..) => Some(*offset),
+                _ => None,
+            })
             .collect();
+        let typed = match typed {
+            Some(typed) if typed.is_empty() => return Ok(()),
+            // A running reindex is searching the contents from before the edits
+            Some(typed) if self.search_index.is_none() => typed,
+            _ => return self.reindex_search(),
+        };
+
+        let matcher = self.search_matcher()?;
+        self.contents.block();
+        for offset in typed {
+            let Some((starts, found)) = matcher.find_around(&self.contents, offset) else {
+                return self.reindex_search();
+            };
+            let first = self.search_offsets.partition_point(|&o| o < starts.start);
+            let last = self.search_offsets.partition_point(|&o| o < starts.end);
+            let (offsets, lengths): (Vec<_>, Vec<_>) = found.into_iter().unzip();
+            self.search_offsets.splice(first..last, offsets);
+            self.search_lengths.splice(first..last, lengths);
+        }
+        self.search_actions = Some(self.actions.len());
+        Ok(())
+    }
+
+    /// Parses the search term into what the contents are searched for.
+    fn search_matcher(&self) -> Result<Matcher, String> {
+        let matcher = if let Some(pattern) = self.search_term.strip_prefix(REGEX_PREFIX) {
+            // Syntax errors span several lines to point at the problem, but only the last
+            // line, which describes it, fits in a notification.
+            let regex = Regex::new(pattern).map_err(|e| {
+                format!("Invalid regex: {}", e.to_string().lines().last().unwrap_or_default())
+            })?;
+            Matcher::Regex(regex)
+        } else if let Some(bits) = self.search_term.strip_prefix(BITS_PREFIX) {
+            let pattern =
+                Pattern::from_bits(bits).map_err(|e| format!("Invalid bit pattern: {e}"))?;
+            Matcher::Patterns(vec![pattern])
+        } else if let Some(hex) = self.search_term.strip_prefix(HEX_PREFIX) {
+            let pattern = Pattern::from_hex(hex).map_err(|e| format!("Invalid hex: {e}"))?;
+            Matcher::Patterns(vec![pattern])
+        } else {
+            let text = self.search_term.strip_prefix(ASCII_PREFIX).unwrap_or(&self.search_term);
+            if text.is_empty() {
+                return Err(String::from("Empty search query"));
+            }
+            Matcher::Patterns(vec![Pattern::exact(text.as_bytes())])
+        };
+        Ok(matcher)
+    }
+
+    /// Adds the matches that the reindex found since this was last called, waiting for all of
+    /// them if `wait` is set. Returns whether the reindex is still running.
+    pub(crate) fn receive_search_matches(&mut self, wait: bool) -> bool {
+        let Some(index) = &mut self.search_index else {
+            return false;
+        };
+        loop {
+            match index.receive(wait) {
+                Ok(found) => {
+                    let (offsets, lengths): (Vec<_>, Vec<_>) = found.into_iter().unzip();
+                    self.search_offsets.extend(offsets);
+                    self.search_lengths.extend(lengths);
+                }
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => {
+                    self.search_index = None;
+                    return false;
+                }
+            }
+        }
+    }
+
+    /// Returns the ranges of the search matches that overlap with the given range.
+    pub(crate) fn search_matches_in(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let longest = self.search_lengths.iter().max().copied().unwrap_or_default();
+        let first = self.search_offsets.partition_point(|&o| o + longest <= range.start);
+        self.search_offsets[first..]
+            .iter()
+            .zip(&self.search_lengths[first..])
+            .take_while(|(&o, _)| o < range.end)
+            .map(|(&o, &len)| o..o + len)
+            .filter(|found| found.end > range.start)
+            .collect()
+    }
+
+    /// Returns the range of bytes covered by the current drag selection, if there is one.
+    pub(crate) fn selection(&self) -> Option<Range<usize>> {
+        self.last_drag.map(|drag| cmp::min(drag, self.offset)..cmp::max(drag, self.offset) + 1)
+    }
+
+    /// Discards the first digit of a byte typed in the hex editor, moving the cursor back to the
+    /// first nibble. This is done whenever the cursor moves, so the digit isn't finished elsewhere.
+    pub(crate) fn discard_pending_nibble(&mut self) {
+        if self.pending_nibble.take().is_some() {
+            self.nibble = Nibble::Beginning;
+        }
+    }
+
+    /// Returns the value the byte at the given offset had before it was first edited, or None if
+    /// it has not been edited or was added by an edit. The offset is followed back through the
+    /// edits that moved the byte.
+    pub(crate) fn original_byte(&self, mut offset: usize) -> Option<u8> {
+        let mut original = None;
+        // The oldest edit of the byte is found last
+        for action in self.actions.iter().rev() {
+            match action {
+                Action::CharacterInput(edited, byte, _) => {
+                    if *edited == offset {
+                        original = Some(*byte);
+                    }
+                }
+                Action::Overwrite(start, bytes) => {
+                    if let Some(&byte) = offset.checked_sub(*start).and_then(|i| bytes.get(i)) {
+                        original = Some(byte);
+                    }
+                }
+                Action::OverwriteMany(overwritten) => {
+                    for (start, bytes) in overwritten {
+                        if let Some(&byte) = offset.checked_sub(*start).and_then(|i| bytes.get(i)) {
+                            original = Some(byte);
+                        }
+                    }
+                }
+                Action::Replace(start, bytes, len) => {
+                    if offset >= start + len {
+                        offset = offset - len + bytes.len();
+                    } else if let Some(i) = offset.checked_sub(*start) {
+                        original = Some(*bytes.get(i)?);
+                    }
+                }
+                Action::Insert(inserted) => match offset.cmp(inserted) {
+                    cmp::Ordering::Less => {}
+                    cmp::Ordering::Equal => return None,
+                    cmp::Ordering::Greater => offset -= 1,
+                },
+                Action::Delete(deleted, _) => {
+                    if offset >= *deleted {
+                        offset += 1;
+                    }
+                }
+                Action::Append(len) => {
+                    if offset >= *len {
+                        return None;
+                    }
+                }
+            }
+        }
+        original
+    }
+
+    /// Overwrites the bytes in the range with `byte` and records it as a single action.
+    pub(crate) fn fill(&mut self, range: Range<usize>, byte: u8) {
+        self.actions.push(Action::Overwrite(range.start, self.contents[range.clone()].to_vec()));
+        self.contents[range].fill(byte);
+        self.dirty = true;
+    }
+
+    /// Overwrites the bytes starting at the offset and records it as a single action.
+    pub(crate) fn overwrite(&mut self, offset: usize, bytes: &[u8]) {
+        let range = offset..offset + bytes.len();
+        self.actions.push(Action::Overwrite(offset, self.contents[range.clone()].to_vec()));
+        self.contents[range].copy_from_slice(bytes);
+        self.dirty = true;
+    }
+
+    /// Overwrites the bytes in the range with random ones and records it as a single action.
+    #[cfg(feature = "random")]
+    pub(crate) fn fill_random(&mut self, range: Range<usize>) {
+        self.actions.push(Action::Overwrite(range.start, self.contents[range.clone()].to_vec()));
+        self.rng.fill(&mut self.contents[range]);
+        self.dirty = true;
+    }
+
+    /// Replaces the bytes in the range with `bytes`, which can be of a different length, and
+    /// records it as a single action.
+    pub(crate) fn replace_bytes(&mut self, range: Range<usize>, bytes: &[u8]) {
+        let replaced = self.contents[range.clone()].to_vec();
+        self.contents.splice(range.clone(), bytes);
+        self.shift_marks(&range, bytes.len());
+        self.actions.push(Action::Replace(range.start, replaced, bytes.len()));
+    }
+
+    /// Moves the protected ranges, bookmarks, and annotations along with their bytes after the
+    /// bytes in `range` were replaced by `len` bytes. The ones left without any bytes are dropped.
+    pub(crate) fn shift_marks(&mut self, range: &Range<usize>, len: usize) {
+        self.protected.retain_mut(|protected| {
+            shift_range(protected, range, len).map(|shifted| *protected = shifted).is_some()
+        });
+        self.annotations.retain_mut(|annotation| {
+            shift_range(&annotation.range, range, len)
+                .map(|shifted| annotation.range = shifted)
+                .is_some()
+        });
+        self.bookmarks =
+            self.bookmarks.iter().filter_map(|&offset| shift_offset(offset, range, len)).collect();
+    }
+
+    /// Finds the annotation that the byte at the given offset belongs to.
+    pub(crate) fn annotation_at(&self, offset: usize) -> Option<&Annotation> {
+        annotation::annotation_at(&self.annotations, offset)
+    }
+
+    /// Converts an offset in the file to the address displayed for it.
+    pub(crate) fn address(&self, offset: usize) -> usize {
+        self.base.wrapping_add(offset)
+    }
+
+    /// Returns the number of digits that the address column needs for the address of the last
+    /// byte, or for the largest address if the addresses wrap around.
+    pub(crate) fn address_digits(&self) -> usize {
+        let largest = self.base.saturating_add(self.contents.len() - 1);
+        self.address_radix.row_digits(largest)
+    }
+
+    /// Bookmarks the offset, or removes its bookmark if it has one. Returns whether the offset is
+    /// bookmarked afterwards.
+    pub(crate) fn toggle_bookmark(&mut self, offset: usize) -> bool {
+        match self.bookmarks.binary_search(&offset) {
+            Ok(index) => {
+                self.bookmarks.remove(index);
+                false
+            }
+            Err(index) => {
+                self.bookmarks.insert(index, offset);
+                true
+            }
+        }
+    }
+
+    /// Finds the first bookmark after the offset, wrapping around to the first bookmark.
+    pub(crate) fn next_bookmark(&self, offset: usize) -> Option<usize> {
+        let bookmarks = self.bookmarks.iter().copied().filter(|&b| b < self.contents.len());
+        bookmarks.clone().find(|&bookmark| bookmark > offset).or_else(|| bookmarks.min())
+    }
+
+    /// Returns the offset of the nearest byte after or before the cursor that matches the
+    /// predicate.
+    pub(crate) fn find_byte(
+        &self,
+        direction: &SearchDirection,
+        predicate: impl Fn(u8) -> bool,
+    ) -> Option<usize> {
+        // Edits may still be moving bytes around outside of the window.
+        self.contents.block();
+        let offset = self.offset;
+        match direction {
+            SearchDirection::Forward => self.contents[offset + 1..]
+                .iter()
+                .position(|&byte| predicate(byte))
+                .map(|found| offset + 1 + found),
+            SearchDirection::Backward => {
+                self.contents[..offset].iter().rposition(|&byte| predicate(byte))
+            }
+        }
+    }
+
+    /// Returns the offset of the next byte after the cursor that matches the predicate, wrapping
+    /// around to the start of the file like search does.
+    pub(crate) fn find_byte_wrapping(&self, predicate: impl Fn(u8) -> bool) -> Option<usize> {
+        self.find_byte(&SearchDirection::Forward, &predicate)
+            .or_else(|| self.contents[..self.offset].iter().position(|&byte| predicate(byte)))
+    }
+
+    /// Checks if the byte at the given offset differs from the byte at the same offset in the
+    /// file opened with `--diff`, including when only one of the files is that long.
+    pub(crate) fn differs(&self, offset: usize) -> bool {
+        self.diff.as_ref().is_some_and(|diff| diff.get(offset) != self.contents.get(offset))
+    }
+
+    /// Returns the color that a byte is displayed in, based on its character or its value
+    /// depending on the color mode.
+    pub(crate) fn byte_color(&self, byte: u8, character: &RichChar) -> Color {
+        match self.color_mode {
+            ColorMode::Character => character.color(&self.theme),
+            ColorMode::HeatMap => Theme::heat_color(byte),
+        }
+    }
+
+    /// Checks if the byte at the given offset starts a record, which is every number of bytes set
+    /// with `--record-size` from address 0.
+    pub(crate) fn is_record_start(&self, offset: usize) -> bool {
+        self.alignment.is_some_and(|size| self.address(offset).is_multiple_of(size))
+    }
+
+    /// Checks if the byte at the given offset lies in a protected range.
+    pub(crate) fn is_protected(&self, offset: usize) -> bool {
+        self.protected.iter().any(|range| range.contains(&offset))
+    }
+
+    /// Protects the given range, or unprotects every range overlapping it if there are any.
+    /// Returns whether the range is protected afterwards.
+    pub(crate) fn toggle_protection(&mut self, range: Range<usize>) -> bool {
+        let protected_count = self.protected.len();
+        self.protected
+            .retain(|protected| protected.end <= range.start || range.end <= protected.start);
+        if self.protected.len() != protected_count {
+            return false;
+        }
+        self.protected.push(range);
+        true
+    }
+
+    /// Stores the edit as pending if it would modify a protected byte in the range that the user
+    /// has not yet confirmed editing. Returns true if the edit was intercepted and should not be
+    /// performed.
+    pub(crate) fn intercept_protected_edit(
+        &mut self,
+        mut range: Range<usize>,
+        edit: PendingEdit,
+    ) -> bool {
+        if self.edit_confirmed || !range.any(|offset| self.is_protected(offset)) {
+            return false;
+        }
+        self.pending_edit = Some(edit);
+        true
+    }
+}
+
+/// Returns where the byte at the offset is after the bytes in `range` were replaced by `len` bytes,
+/// or None if it was replaced by nothing.
+fn shift_offset(offset: usize, range: &Range<usize>, len: usize) -> Option<usize> {
+    if offset < range.start {
+        Some(offset)
+    } else if offset >= range.end {
+        Some(offset - range.len() + len)
+    } else {
+        (offset - range.start < len).then_some(offset)
+    }
+}
+
+/// Returns where the bytes in `marked` are after the bytes in `range` were replaced by `len` bytes,
+/// or None if they were all replaced by nothing. Bytes inserted inside of `marked` become part of
+/// it, but bytes inserted at its start or end don't.
+fn shift_range(marked: &Range<usize>, range: &Range<usize>, len: usize) -> Option<Range<usize>> {
+    let shift = |bound: usize| {
+        if bound >= range.end {
+            bound - range.len() + len
+        } else {
+            range.start + cmp::min(bound - range.start, len)
+        }
+    };
+    let start = if marked.start < range.start { marked.start } else { shift(marked.start) };
+    let end = if marked.end <= range.start { marked.end } else { shift(marked.end) };
+    (start < end).then_some(start..end)
+}
+
+/// Settings that change how the application behaves. These are usually set from the command line.
+// The switches mirror the flags of the command line
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// The initial number of bits used by the binary, octal, and hexadecimal labels.
+    pub stream_length: usize,
+    /// The most bits that the binary, octal, and hexadecimal labels can be lengthened to.
+    pub max_stream_length: usize,
+    /// The number of bytes to display per line, if it fits in the terminal.
+    pub bytes_per_line: Option<usize>,
+    /// The number of bytes after which an extra space is displayed in the hex editor, or 0 to
+    /// leave the bytes ungrouped.
+    pub group_size: usize,
+    /// Labeled byte ranges to highlight in the hex editor.
+    pub annotations: Vec<Annotation>,
+    /// Named offsets that can be jumped to, sorted by their offset.
+    pub symbols: Vec<Symbol>,
+    /// A file to compare with, which is displayed next to the hex editor.
+    pub diff: Option<PathBuf>,
+    /// The path of the opened file, used to avoid writing over it.
+    pub path: Option<PathBuf>,
+    /// What typing over part of a multibyte UTF-8 character does.
+    pub multibyte_edit: MultibyteEdit,
+    /// Whether hex digits are written one at a time or a byte at a time.
+    pub hex_entry: HexEntry,
+    /// The address of the first byte of the file, like a load address, used for display.
+    pub base: usize,
+    /// The base that addresses are displayed in.
+    pub address_radix: AddressRadix,
+    /// The names of the labels to display, like `signed16` or `offset`. All labels are displayed
+    /// if this is empty.
+    pub labels: Vec<String>,
+    /// The number of lines that the mouse wheel scrolls by.
+    pub scroll_lines: usize,
+    /// The number of rows kept above and below the cursor when the viewport scrolls, like vim's
+    /// `scrolloff`.
+    pub scrolloff: usize,
+    /// The number of bytes from the cursor that the `Entropy` label is computed over.
+    pub entropy_window: usize,
+    /// The size of the pages that the `Page` label counts and that alt and page up or down move
+    /// between.
+    pub page_size: usize,
+    /// The number of bytes in the pointers that are followed, from 1 to 8.
+    pub pointer_size: usize,
+    /// The size of the records whose starts tab moves the cursor to and the hex editor highlights.
+    /// Tab moves by the number of bytes per line if unset.
+    pub alignment: Option<usize>,
+    /// A keymap file to load the keys of commands from. The default keys are used if unset.
+    pub keymap: Option<PathBuf>,
+    /// A theme file to load the colors from. The default colors are used if it can't be loaded.
+    pub theme: Option<PathBuf>,
+    /// How often to back up the contents next to the file while they're edited. Backups aren't
+    /// written if unset.
+    pub autosave: Option<Duration>,
+    /// How long notifications stay on the screen through the keys and clicks that follow them.
+    /// Notifications are cleared by the next key or click if unset.
+    pub notification_timeout: Option<Duration>,
+    /// Whether quitting with unsaved changes asks to discard them first.
+    pub confirm_quit: bool,
+    /// Whether keys are also bound like in vim, like a bare `q` in the hex editor to quit.
+    pub vim: bool,
+    /// Whether left and right wrap around the ends of the file instead of stopping at them.
+    pub wrap: bool,
+    /// Whether the indices of the columns are displayed above the editors.
+    pub ruler: bool,
+    /// Whether the labels are left out at the start, which gives their rows to the editors.
+    pub hide_labels: bool,
+    /// The seed for random fills, which makes them reproducible. A random seed is used if unset.
+    #[cfg(feature = "random")]
+    pub seed: Option<u64>,
+    /// The architecture that the instruction at the cursor is disassembled as, which adds the
+    /// `Disasm` label. Nothing is disassembled if unset.
+    #[cfg(feature = "disasm")]
+    pub arch: Option<Arch>,
+}
 
-        if let Ok(hex_search_term) = hex::decode(self.search_term.replace(' ', "")) {
-            self.search_offsets.extend(
-                self.contents
-                    .windows(hex_search_term.len())
-                    .enumerate()
-                    .filter_map(|(idx, w)| (w == hex_search_term).then_some(idx))
-                    .collect::<Vec<usize>>(),
-            );
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            stream_length: 8,
+            max_stream_length: label::DEFAULT_MAX_STREAM_LENGTH,
+            bytes_per_line: None,
+            group_size: 8,
+            annotations: Vec::new(),
+            symbols: Vec::new(),
+            diff: None,
+            path: None,
+            multibyte_edit: MultibyteEdit::default(),
+            hex_entry: HexEntry::default(),
+            base: 0,
+            address_radix: AddressRadix::default(),
+            labels: Vec::new(),
+            scroll_lines: 1,
+            scrolloff: 0,
+            entropy_window: label::DEFAULT_ENTROPY_WINDOW,
+            page_size: label::DEFAULT_PAGE_SIZE,
+            pointer_size: 4,
+            alignment: None,
+            keymap: None,
+            theme: None,
+            autosave: None,
+            notification_timeout: None,
+            confirm_quit: true,
+            wrap: false,
+            ruler: false,
+            hide_labels: false,
+            vim: false,
+            #[cfg(feature = "random")]
+            seed: None,
+            #[cfg(feature = "disasm")]
+            arch: None,
         }
     }
 }
@@ -146,6 +1007,21 @@ pub struct Application {
     /// The window that handles keyboard input. This is usually in the form of the Hex/ASCII editor
     /// or popups.
     pub key_handler: Box<dyn KeyHandler>,
+
+    /// The commands that keys are bound to.
+    pub(crate) keymap: Keymap,
+
+    /// The other open files, in the order that they are switched to after the one being edited.
+    pub(crate) files: Vec<Data>,
+
+    /// Whether quitting with unsaved changes asks to discard them first.
+    pub(crate) confirm_quit: bool,
+
+    /// The keys of the macro that was recorded last, which are played back with `F6`.
+    pub(crate) macro_keys: Vec<KeyEvent>,
+
+    /// Whether the keys that are pressed are recorded into the macro.
+    pub(crate) recording: bool,
 }
 
 impl Application {
@@ -154,52 +1030,176 @@ impl Application {
     ///
     /// # Errors
     ///
-    /// This errors out if the file specified is empty.
+    /// This errors out if the file specified is empty, isn't a regular file, or can't be memory
+    /// mapped, or if the offset is past the end of the file.
     pub fn new(file: File, encoding: Encoding, offset: usize) -> Result<Self, Box<dyn Error>> {
+        Self::with_options(file, encoding, offset, &Options::default())
+    }
+
+    /// Creates a new application like [`new`](Self::new), but with the behavior customized by
+    /// `options`.
+    ///
+    /// # Errors
+    ///
+    /// This errors out if the file specified is empty, isn't a regular file, or can't be memory
+    /// mapped, or if the offset is past the end of the file.
+    pub fn with_options(
+        file: File,
+        encoding: Encoding,
+        offset: usize,
+        options: &Options,
+    ) -> Result<Self, Box<dyn Error>> {
         let contents = AsyncBuffer::new(&file)?;
+        Self::from_parts(Some(file), contents, encoding, offset, options, None)
+    }
+
+    /// Creates an application that edits `contents` in memory, laid out for the size of
+    /// `backend`. It doesn't need a terminal and is drawn with [`render_frame`](Self::render_frame),
+    /// for example on a [`TestBackend`](ratatui::backend::TestBackend) to test what is displayed.
+    /// Saving and refreshing are unavailable since there is no file.
+    ///
+    /// # Errors
+    ///
+    /// This errors out if `contents` is empty or the size of `backend` can't be retrieved, or if
+    /// the offset is past the end of the contents.
+    pub fn with_backend<B: Backend>(
+        backend: &B,
+        contents: &[u8],
+        encoding: Encoding,
+        offset: usize,
+        options: &Options,
+    ) -> Result<Self, Box<dyn Error>> {
+        let size = backend.size()?;
+        let contents = AsyncBuffer::from_bytes(contents)?;
+        let area = Rect::new(0, 0, size.width, size.height);
+        Self::from_parts(None, contents, encoding, offset, options, Some(area))
+    }
+
+    /// Reads the colors from the theme file given with `--theme`.
+    fn read_theme(path: &Path) -> Result<Theme, String> {
+        fs::read_to_string(path).map_err(|e| e.to_string()).and_then(|text| theme::parse(&text))
+    }
+
+    /// Loads the key bindings from the keymap file given with `--keymap`, or the default ones,
+    /// along with the vim keys if `--vim` is given.
+    fn load_keymap(options: &Options) -> Result<Keymap, String> {
+        let mut keymap = match &options.keymap {
+            Some(path) => fs::read_to_string(path)
+                .map_err(|e| e.to_string())
+                .and_then(|text| keymap::parse(&text))
+                .map_err(|e| format!("Invalid keymap {}: {e}", path.display()))?,
+            None => Keymap::default(),
+        };
+        if options.vim {
+            keymap.add_vim_keys();
+        }
+        Ok(keymap)
+    }
+
+    /// Creates the screen handler, laid out for `area`, or for the terminal if there is none.
+    fn open_display(
+        area: Option<Rect>,
+        options: &Options,
+        label_count: usize,
+        diff: bool,
+    ) -> Result<ScreenHandler, Box<dyn Error>> {
+        let mut display = match area {
+            Some(area) => ScreenHandler::with_size(
+                area,
+                options.bytes_per_line,
+                options.group_size,
+                label_count,
+                diff,
+            ),
+            None => {
+                ScreenHandler::new(options.bytes_per_line, options.group_size, label_count, diff)?
+            }
+        };
+        if options.ruler || options.hide_labels {
+            display.ruler = options.ruler;
+            display.hide_labels = options.hide_labels;
+            display.comp_layouts = display.layout(display.terminal_size, &Editor::Hex);
+        }
+        Ok(display)
+    }
+
+    /// Creates the application around the contents. The display is laid out for `area`, or for
+    /// the terminal if there is none.
+    fn from_parts(
+        file: Option<File>,
+        contents: AsyncBuffer,
+        encoding: Encoding,
+        offset: usize,
+        options: &Options,
+        area: Option<Rect>,
+    ) -> Result<Self, Box<dyn Error>> {
         if contents.is_empty() {
-            eprintln!("heh does not support editing empty files");
-            process::exit(1);
+            return Err("heh does not support editing empty files".into());
         } else if offset >= contents.len() {
-            eprintln!(
+            return Err(format!(
                 "The specified offset ({offset}) is too large! (must be less than {})",
                 contents.len()
-            );
-            process::exit(1);
-        }
-
-        let mut labels = LabelHandler::new(&contents, offset);
-        let clipboard = Clipboard::new().ok();
-        if clipboard.is_none() {
-            labels.notification = String::from("Can't find clipboard!");
-        }
-
-        let display = ScreenHandler::new()?;
-
-        let app = Self {
-            data: Data {
-                file,
-                contents,
-                encoding,
-                dirty: false,
-                start_address: (offset / display.comp_layouts.bytes_per_line)
-                    * display.comp_layouts.bytes_per_line,
-                offset,
-                nibble: Nibble::Beginning,
-                last_click: Window::Unhandled,
-                drag_enabled: false,
-                last_drag: None,
-                drag_nibble: None,
-                clipboard,
-                editor: Editor::Hex,
-                actions: vec![],
-                search_term: String::new(),
-                search_offsets: Vec::new(),
-            },
+            )
+            .into());
+        }
+
+        let mut labels = LabelHandler::new(&contents, offset, options.stream_length, encoding);
+        labels.titles = label::select_titles(&options.labels)?;
+        labels.set_entropy_window(options.entropy_window, &contents[offset..]);
+        labels.page_size = options.page_size;
+        labels.max_stream_length = options.max_stream_length;
+        labels.notification_timeout = options.notification_timeout;
+        #[cfg(feature = "disasm")]
+        if let Some(arch) = options.arch {
+            labels.disassembler = Some(Disassembler::new(arch)?);
+            labels.titles.push(DISASM_TITLE);
+        }
+        labels.update_offset(options.base.wrapping_add(offset), options.address_radix);
+        let theme = options.theme.as_deref().map_or_else(|| Ok(Theme::default()), Self::read_theme);
+        let keymap = Self::load_keymap(options)?;
+
+        let display =
+            Self::open_display(area, options, labels.titles.len(), options.diff.is_some())?;
+        labels.update_position(offset, display.comp_layouts.bytes_per_line);
+        let start_address = windows::centered_start_address(
+            offset,
+            contents.len(),
+            display.comp_layouts.bytes_per_line,
+            display.comp_layouts.lines_per_screen,
+        );
+
+        let theme_error = theme.as_ref().err().cloned();
+        let data = Data::new(
+            file,
+            contents,
+            encoding,
+            (offset, start_address),
+            options,
+            theme.unwrap_or_default(),
+        )?;
+        if !data.clipboard.has_system() {
+            labels.notification = String::from("Can't find clipboard, copying within heh");
+        }
+        if let Some(e) = theme_error {
+            labels.notification = format!("Invalid theme: {e}");
+        }
+        let restorable = options.path.as_deref().is_some_and(backup::is_newer);
+
+        let mut app = Self {
+            data,
             display,
             labels,
             key_handler: Box::from(Editor::Hex),
+            keymap,
+            files: Vec::new(),
+            confirm_quit: options.confirm_quit,
+            macro_keys: Vec::new(),
+            recording: false,
         };
+        app.labels.record_notification();
+        if restorable {
+            app.set_focused_window(Window::RestoreBackup);
+        }
 
         Ok(app)
     }
@@ -213,20 +1213,81 @@ impl Application {
     pub fn run(&mut self) -> Result<(), Box<dyn Error>> {
         ScreenHandler::setup()?;
         loop {
+            let working = self.key_handler.work(&self.data);
+            self.labels.clear_expired_notification();
+            self.back_up();
+            let searching = self.receive_search();
             self.render_display()?;
+            // Keep working until there is input to handle
+            if working && !event::poll(Duration::ZERO)? {
+                continue;
+            }
+            // Show the matches of the search as they are found
+            if searching && !event::poll(SEARCH_REFRESH_INTERVAL)? {
+                continue;
+            }
+            // Wake up for the next backup or to clear the notification if nothing happens before
+            if let Some(wait) = self
+                .all_files()
+                .filter_map(|data| data.autosave.as_ref().and_then(Autosave::time_left))
+                .chain(self.labels.notification_time_left())
+                .min()
+            {
+                if !event::poll(wait)? {
+                    continue;
+                }
+            }
             let event = event::read()?;
             if !self.handle_input(&event)? {
                 break;
             }
         }
+        for data in self.all_files_mut() {
+            if let Some(autosave) = data.autosave.take() {
+                autosave.finish();
+            }
+        }
         self.display.teardown()?;
         Ok(())
     }
 
+    /// Backs up the contents of each file if they changed and a backup is due, and shows the error
+    /// of a backup that couldn't be written.
+    fn back_up(&mut self) {
+        let mut error = None;
+        for data in self.all_files_mut() {
+            let Some(autosave) = &mut data.autosave else {
+                continue;
+            };
+            if autosave.is_due() {
+                data.contents.block();
+                autosave.write(&data.contents);
+            }
+            error = autosave.error().or(error);
+        }
+        if let Some(e) = error {
+            self.labels.notification = e;
+        }
+    }
+
+    /// Adds the matches that the search reindex found, and moves to the match that a search was
+    /// waiting for once it is found. Returns whether the reindex is still running.
+    fn receive_search(&mut self) -> bool {
+        let searching = self.data.receive_search_matches(false);
+        if let Some(direction) = self.data.pending_search.take() {
+            jump_to_next_match(&mut self.data, &mut self.display, &mut self.labels, &direction);
+            // The search progress isn't kept, only where the search ended up
+            if self.data.pending_search.is_none() {
+                self.labels.record_notification();
+            }
+        }
+        searching
+    }
+
     /// Renders the display. This is a wrapper around [`ScreenHandler`'s
     /// render](ScreenHandler::render) method.
     fn render_display(&mut self) -> Result<(), Box<dyn Error>> {
-        self.display.render(&mut self.data, &self.labels, self.key_handler.as_ref())
+        self.display.render(&mut self.data, &mut self.labels, self.key_handler.as_ref())
     }
 
     /// Renders a single frame for the given area.
@@ -235,16 +1296,18 @@ impl Application {
         // We check if we need to recompute the terminal size in the case that the saved off
         // variable differs from the current frame, which can occur when a terminal is resized
         // between an event handling and a rendering.
-        if area != self.display.terminal_size {
+        let address_digits = self.data.address_digits();
+        if area != self.display.terminal_size || address_digits != self.display.address_digits {
             self.display.terminal_size = area;
-            self.display.comp_layouts =
-                ScreenHandler::calculate_dimensions(area, self.key_handler.as_ref());
+            self.display.address_digits = address_digits;
+            self.display.comp_layouts = self.display.layout(area, self.key_handler.as_ref());
             // We change the start_address here to ensure that 0 is ALWAYS the first start
             // address. We round to preventing constant resizing always moving to 0.
             self.data.start_address = (self.data.start_address
                 + (self.display.comp_layouts.bytes_per_line / 2))
                 / self.display.comp_layouts.bytes_per_line
                 * self.display.comp_layouts.bytes_per_line;
+            self.labels.update_position(self.data.offset, self.display.comp_layouts.bytes_per_line);
         }
         ScreenHandler::render_frame(
             frame,
@@ -264,62 +1327,197 @@ impl Application {
     ///
     /// This errors when handling the key event fails.
     pub fn handle_input(&mut self, event: &Event) -> Result<bool, Box<dyn Error>> {
-        match event {
-            Event::Key(key) => {
-                if key.kind == KeyEventKind::Press {
-                    self.labels.notification.clear();
-                    return input::handle_key_input(self, *key);
-                }
+        let keep_running = match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                self.labels.expire_notification();
+                let keep_running = input::handle_key_input(self, *key)?;
+                self.labels.record_notification();
+                keep_running
             }
             Event::Mouse(mouse) => {
-                self.labels.notification.clear();
+                self.labels.expire_notification();
                 input::handle_mouse_input(self, *mouse);
+                self.labels.record_notification();
+                true
             }
-            Event::Resize(_, _) | Event::FocusGained | Event::FocusLost | Event::Paste(_) => {}
+            Event::Key(_)
+            | Event::Resize(_, _)
+            | Event::FocusGained
+            | Event::FocusLost
+            | Event::Paste(_) => true,
+        };
+        self.clamp_offsets();
+        if let Some(autosave) = self.data.autosave.as_mut().filter(|_| self.data.dirty) {
+            autosave.changed();
         }
-        Ok(true)
+        Ok(keep_running)
     }
 
-    /// Sets the current [`KeyHandler`]. This should be used when trying to focus another window.
-    /// Setting the [`KeyHandler`] directly could cause errors.
+    /// Keeps the cursor, selection, and viewport inside the content, which they can end up past
+    /// after bytes are removed.
+    fn clamp_offsets(&mut self) {
+        let (offset, start_address) = windows::clamp_offsets(
+            self.data.offset,
+            self.data.start_address,
+            self.data.contents.len(),
+            self.display.comp_layouts.bytes_per_line,
+        );
+        let last_offset = self.data.contents.len() - 1;
+        self.data.last_drag = self.data.last_drag.map(|drag| cmp::min(drag, last_offset));
+        self.data.start_address = start_address;
+        if offset != self.data.offset {
+            self.data.offset = offset;
+            self.labels.update_all(&self.data.contents[offset..]);
+            windows::adjust_offset(&mut self.data, &mut self.display, &mut self.labels);
+        }
+    }
+
+    /// Moves the cursor to the offset, clearing the selection and scrolling the offset into view.
+    ///
+    /// # Errors
+    ///
+    /// This errors out if the offset is past the end of the file.
+    pub fn goto(&mut self, offset: usize) -> Result<(), Box<dyn Error>> {
+        if offset >= self.data.contents.len() {
+            return Err(format!(
+                "The offset ({offset}) is too large! (must be less than {})",
+                self.data.contents.len()
+            )
+            .into());
+        }
+        self.data.discard_pending_nibble();
+        self.data.offset = offset;
+        self.data.last_drag = None;
+        self.data.drag_nibble = None;
+        self.labels.update_all(&self.data.contents[offset..]);
+        windows::adjust_offset(&mut self.data, &mut self.display, &mut self.labels);
+        Ok(())
+    }
+
+    /// Returns the offset of the cursor.
+    #[must_use]
+    pub fn current_offset(&self) -> usize {
+        self.data.offset
+    }
+
+    /// Returns the number of bytes displayed on each line, as laid out for the last rendered area.
+    #[must_use]
+    pub fn bytes_per_line(&self) -> usize {
+        self.display.comp_layouts.bytes_per_line()
+    }
+
+    /// Returns the number of lines of bytes displayed, as laid out for the last rendered area.
+    #[must_use]
+    pub fn lines_per_screen(&self) -> usize {
+        self.display.comp_layouts.lines_per_screen()
+    }
+
+    /// Returns the offsets of the bytes that are displayed.
+    #[must_use]
+    pub fn visible_range(&self) -> Range<usize> {
+        let start = self.data.start_address;
+        let end = start + self.bytes_per_line() * self.lines_per_screen();
+        start..cmp::min(end, self.data.contents.len())
+    }
+
+    /// Returns the selected bytes, or the byte at the cursor if nothing is selected. This waits
+    /// for edits that are still being applied in the background.
+    pub fn selected_bytes(&mut self) -> &[u8] {
+        self.data.contents.block();
+        let range = self.data.selection().unwrap_or(self.data.offset..self.data.offset + 1);
+        &self.data.contents[range]
+    }
+
+    /// Overwrites the byte at the offset like typing over it would, so that it can be undone.
+    ///
+    /// # Errors
+    ///
+    /// This errors out if the offset is past the end of the file or the byte is protected.
+    pub fn replace_byte(&mut self, offset: usize, value: u8) -> Result<(), Box<dyn Error>> {
+        if offset >= self.data.contents.len() {
+            return Err(format!(
+                "The offset ({offset}) is too large! (must be less than {})",
+                self.data.contents.len()
+            )
+            .into());
+        } else if self.data.is_protected(offset) {
+            return Err(format!("The byte at {offset:#X} is protected").into());
+        }
+        self.data.contents.block();
+        self.data.actions.push(Action::CharacterInput(offset, self.data.contents[offset], None));
+        self.data.contents[offset] = value;
+        self.data.dirty = true;
+        self.labels.update_all(&self.data.contents[self.data.offset..]);
+        Ok(())
+    }
+
+    /// Sets the current [`KeyHandler`]. This should be used when trying to focus another window.
+    /// Setting the [`KeyHandler`] directly could cause errors.
     ///
     /// Popup dimensions are also changed here and are safe to do so because there are currently
     /// no popups that have dimensions based off of the size of the terminal frame.
     pub(crate) fn set_focused_window(&mut self, window: Window) {
-        match window {
+        // A popup can move the cursor, so a half typed byte is discarded rather than finished
+        // somewhere else
+        self.data.discard_pending_nibble();
+        let popup: Box<dyn KeyHandler> = match window {
             Window::Hex => {
                 self.key_handler = Box::from(Editor::Hex);
                 self.data.editor = Editor::Hex;
+                return;
             }
             Window::Ascii => {
                 self.key_handler = Box::from(Editor::Ascii);
                 self.data.editor = Editor::Ascii;
+                return;
             }
-            Window::JumpToByte => {
-                self.key_handler = Box::from(JumpToByte::new());
-                self.display.comp_layouts.popup = ScreenHandler::calculate_popup_dimensions(
-                    self.display.terminal_size,
-                    self.key_handler.as_ref(),
-                );
+            Window::JumpToByte => Box::from(JumpToByte::new()),
+            Window::AlignCursor => Box::from(AlignCursor::new()),
+            Window::Search => Box::from(Search::new()),
+            Window::UnsavedChanges => Box::from(UnsavedChanges::new()),
+            Window::ProtectedEdit => Box::from(ProtectedEdit::new()),
+            Window::Checksum => Box::from(Checksum::new()),
+            Window::Append => Box::from(Append::new()),
+            Window::Strings => Box::from(Strings::new()),
+            Window::Info => Box::from(Info::new(&self.data, &self.display)),
+            Window::Replace => {
+                self.data.contents.block();
+                Box::from(Replace::new(&self.data))
             }
-            Window::Search => {
-                self.key_handler = Box::from(Search::new());
-                self.display.comp_layouts.popup = ScreenHandler::calculate_popup_dimensions(
-                    self.display.terminal_size,
-                    self.key_handler.as_ref(),
-                );
+            Window::Fill => Box::from(Fill::new()),
+            Window::InsertBytes => Box::from(InsertBytes::new()),
+            Window::AppendBytes => Box::from(InsertBytes::appending()),
+            Window::Symbols => Box::from(Symbols::new(&self.data)),
+            Window::Hash => {
+                self.data.contents.block();
+                Box::from(Hash::new(&self.data))
             }
-            Window::UnsavedChanges => {
-                self.key_handler = Box::from(UnsavedChanges::new());
-                self.display.comp_layouts.popup = ScreenHandler::calculate_popup_dimensions(
-                    self.display.terminal_size,
-                    self.key_handler.as_ref(),
-                );
+            Window::Split => Box::from(Split::new()),
+            Window::RestoreBackup => Box::from(RestoreBackup::new()),
+            Window::WriteValue => Box::from(WriteValue::new()),
+            Window::Notifications => Box::from(Notifications::new(&self.labels)),
+            Window::Histogram => {
+                self.data.contents.block();
+                Box::from(Histogram::new(&self.data))
             }
             // We should never try and focus these windows to accept input.
-            Window::Unhandled | Window::Label(_) => {
+            Window::Unhandled | Window::Overview | Window::Label(_) => {
                 panic!()
             }
+        };
+        self.key_handler = popup;
+        self.display.comp_layouts.popup = ScreenHandler::calculate_popup_dimensions(
+            self.display.terminal_size,
+            self.key_handler.as_ref(),
+        );
+    }
+
+    /// Closes the window if it's focused, or focuses it otherwise.
+    pub(crate) fn toggle_window(&mut self, window: Window) {
+        if self.key_handler.is_focusing(window) {
+            self.focus_editor();
+        } else {
+            self.set_focused_window(window);
         }
     }
 
@@ -327,4 +1525,1523 @@ impl Application {
     pub(crate) fn focus_editor(&mut self) {
         self.key_handler = Box::from(self.data.editor);
     }
+
+    /// Returns the file being edited followed by the other open files.
+    pub(crate) fn all_files(&self) -> impl Iterator<Item = &Data> {
+        iter::once(&self.data).chain(&self.files)
+    }
+
+    /// Returns the file being edited followed by the other open files, mutably.
+    pub(crate) fn all_files_mut(&mut self) -> impl Iterator<Item = &mut Data> {
+        iter::once(&mut self.data).chain(&mut self.files)
+    }
+
+    /// Opens another file to switch to, with its cursor at the start. It uses the same options as
+    /// the first file, except for the annotations and symbols, which only belong to the first.
+    ///
+    /// # Errors
+    ///
+    /// This errors out if the file is empty, isn't a regular file, or can't be memory mapped, or
+    /// if the file given with `--diff` can't be opened.
+    pub fn add_file(&mut self, file: File, options: &Options) -> Result<(), Box<dyn Error>> {
+        let contents = AsyncBuffer::new(&file)?;
+        if contents.is_empty() {
+            return Err("heh does not support editing empty files".into());
+        }
+        let mut data = Data::new(
+            Some(file),
+            contents,
+            self.data.encoding,
+            (0, 0),
+            options,
+            self.data.theme.clone(),
+        )?;
+        data.annotations.clear();
+        data.symbols.clear();
+        self.files.push(data);
+        Ok(())
+    }
+
+    /// Switches to editing the next open file, which the labels and editors are updated for. The
+    /// file that was being edited becomes the last one to switch to.
+    pub(crate) fn next_file(&mut self) {
+        if self.files.is_empty() {
+            self.labels.notification = String::from("Only one file is open");
+            return;
+        }
+        let mut next = self.files.remove(0);
+        // Copies are kept within heh when there is no system clipboard, so they move along
+        mem::swap(&mut next.clipboard, &mut self.data.clipboard);
+        let previous = mem::replace(&mut self.data, next);
+        self.files.push(previous);
+
+        let data = &mut self.data;
+        let layouts = &self.display.comp_layouts;
+        data.start_address = windows::centered_start_address(
+            data.offset,
+            data.contents.len(),
+            layouts.bytes_per_line,
+            layouts.lines_per_screen,
+        );
+        self.labels.pin = None;
+        self.labels.encoding = data.encoding;
+        self.labels.update_all(&data.contents[data.offset..]);
+        self.labels.update_offset(data.address(data.offset), data.address_radix);
+        self.labels.update_position(data.offset, layouts.bytes_per_line);
+        self.labels.update_edit_mode(data.edit_mode);
+        self.focus_editor();
+
+        self.labels.notification = format!("Editing {}", self.data.file_name());
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use ratatui::{
+        backend::TestBackend,
+        crossterm::event::{
+            KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+        },
+        Terminal,
+    };
+
+    use super::*;
+
+    /// Opens the contents in memory, laid out for a 100 by 40 terminal, along with the terminal to
+    /// draw them on. This is shared with the tests of the windows.
+    pub(crate) fn open(
+        contents: &[u8],
+        offset: usize,
+        options: &Options,
+    ) -> (Application, Terminal<TestBackend>) {
+        let backend = TestBackend::new(100, 40);
+        let app = Application::with_backend(&backend, contents, Encoding::Ascii, offset, options)
+            .unwrap();
+        (app, Terminal::new(backend).unwrap())
+    }
+
+    /// Presses the key, returning whether the application keeps running.
+    fn press(app: &mut Application, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        app.handle_input(&Event::Key(KeyEvent::new(code, modifiers))).unwrap()
+    }
+
+    /// Presses the character along with ctrl.
+    fn ctrl(app: &mut Application, c: char) -> bool {
+        press(app, KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    /// Presses the character along with alt.
+    fn alt(app: &mut Application, c: char) -> bool {
+        press(app, KeyCode::Char(c), KeyModifiers::ALT)
+    }
+
+    /// Types each character of the text without any modifiers.
+    fn type_text(app: &mut Application, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+    }
+
+    /// Draws the application and returns the lines on the screen.
+    fn render(app: &mut Application, terminal: &mut Terminal<TestBackend>) -> Vec<String> {
+        terminal.draw(|frame| app.render_frame(frame, frame.area())).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| (0..buffer.area.width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    /// Returns the text drawn in the hex editor on the given row, from its first byte.
+    fn hex_row(app: &Application, lines: &[String], row: usize) -> String {
+        lines[row].chars().skip(usize::from(app.display.comp_layouts.hex.x) + 1).collect()
+    }
+
+    #[test]
+    fn test_protected_edit() {
+        let (mut app, _) = open(b"abc", 1, &Options::default());
+        alt(&mut app, 'p');
+        assert_eq!(app.labels.notification, "Protected 0x1-0x1");
+
+        // The edit waits for a confirmation, and is dropped if it's declined
+        type_text(&mut app, "4");
+        assert!(app.key_handler.is_focusing(Window::ProtectedEdit));
+        assert_eq!(&app.data.contents[..], b"abc");
+        type_text(&mut app, "n");
+        assert!(app.key_handler.is_focusing(Window::Hex));
+        assert_eq!(&app.data.contents[..], b"abc");
+        assert_eq!(app.data.pending_edit.map(|_| ()), None);
+
+        // Confirming it performs the edit that was held back
+        type_text(&mut app, "4");
+        type_text(&mut app, "y");
+        assert!(app.key_handler.is_focusing(Window::Hex));
+        assert_eq!(&app.data.contents[..], b"aBc");
+        assert_eq!((app.current_offset(), app.data.nibble), (1, Nibble::End));
+
+        // Bytes outside of the range are edited right away
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        type_text(&mut app, "4");
+        assert!(app.key_handler.is_focusing(Window::Hex));
+        assert_eq!(&app.data.contents[..], b"aBC");
+    }
+
+    #[test]
+    fn test_shift_range() {
+        // Replacing bytes moves the ones after them, and cuts off the replaced ones
+        assert_eq!(shift_offset(3, &(4..6), 0), Some(3));
+        assert_eq!(shift_offset(6, &(4..6), 3), Some(7));
+        assert_eq!(shift_offset(5, &(4..6), 1), None);
+        assert_eq!(shift_range(&(4..8), &(0..2), 0), Some(2..6));
+        assert_eq!(shift_range(&(4..8), &(6..10), 0), Some(4..6));
+        assert_eq!(shift_range(&(4..8), &(2..10), 0), None);
+
+        // Bytes inserted inside of a range join it, but not ones inserted next to it
+        assert_eq!(shift_range(&(4..8), &(6..6), 2), Some(4..10));
+        assert_eq!(shift_range(&(4..8), &(4..4), 2), Some(6..10));
+        assert_eq!(shift_range(&(4..8), &(8..8), 2), Some(4..8));
+    }
+
+    #[test]
+    fn test_shift_marks() {
+        let (mut app, _) = open(b"hello world", 6, &Options::default());
+        app.data.edit_mode = EditMode::Insert;
+        app.data.toggle_protection(6..11);
+        app.data.toggle_bookmark(8);
+
+        // Typing and deleting before the marks moves them along with their bytes
+        app.goto(0).unwrap();
+        app.set_focused_window(Window::Ascii);
+        type_text(&mut app, ">>");
+        assert_eq!(app.data.protected.first(), Some(&(8..13)));
+        assert_eq!(app.data.bookmarks, [10]);
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(app.data.protected.first(), Some(&(7..12)));
+        for _ in 0..3 {
+            ctrl(&mut app, 'z');
+        }
+        assert_eq!(app.data.protected.first(), Some(&(6..11)));
+
+        // Truncating the file cuts off the marks past its new end
+        app.data.toggle_protection(6..11);
+        let label = String::from("world");
+        app.data.annotations = vec![Annotation { range: 4..9, label, color: Color::Red }];
+        app.goto(6).unwrap();
+        ctrl(&mut app, 'k');
+        assert_eq!(app.data.annotations[0].range, 4..6);
+        assert!(app.data.bookmarks.is_empty());
+    }
+
+    #[test]
+    fn test_regex_search() {
+        let (mut app, _) = open(b"id 12, id 345", 0, &Options::default());
+        ctrl(&mut app, 'f');
+        type_text(&mut app, "re:[0-9]+");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        // The jump waits for the reindex when it hasn't found the first match yet
+        app.data.receive_search_matches(true);
+        app.receive_search();
+        assert_eq!(app.current_offset(), 3);
+        assert_eq!(app.data.search_matches_in(0..13), [3..5, 10..13]);
+        ctrl(&mut app, 'n');
+        assert_eq!(app.current_offset(), 10);
+
+        // A pattern that doesn't compile clears the matches and says why
+        ctrl(&mut app, 'f');
+        type_text(&mut app, "re:(");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.labels.notification, "Invalid regex: error: unclosed group");
+        assert!(app.data.search_offsets.is_empty());
+    }
+
+    #[test]
+    fn test_stream_length() {
+        let options = Options { stream_length: 12, ..Options::default() };
+        let (mut app, _) = open(&[0xAB, 0xCD], 0, &options);
+        assert_eq!(app.labels["Stream Length"], "12");
+        assert_eq!(app.labels["Binary"], "101010111100");
+
+        // The stream length still changes from the one it started with
+        alt(&mut app, '=');
+        assert_eq!(app.labels["Stream Length"], "13");
+        assert_eq!(app.labels["Binary"], "1010101111001");
+    }
+
+    #[test]
+    fn test_search_highlight() {
+        let (mut app, mut terminal) = open(b"xAB.AB", 0, &Options::default());
+        ctrl(&mut app, 'f');
+        type_text(&mut app, "AB");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        app.data.receive_search_matches(true);
+        app.goto(0).unwrap();
+        render(&mut app, &mut terminal);
+
+        // Every match on screen is highlighted, along with the spaces between its bytes
+        let buffer = terminal.backend().buffer();
+        let (hex, ascii) = (app.display.comp_layouts.hex, app.display.comp_layouts.ascii);
+        let search_match = Theme::default().search_match;
+        for x in [hex.x + 4, hex.x + 6, hex.x + 8, hex.x + 13, ascii.x + 2, ascii.x + 5] {
+            assert_eq!(buffer[(x, 1)].bg, search_match);
+        }
+        for x in [hex.x + 9, hex.x + 10, hex.x + 12, ascii.x + 4] {
+            assert_ne!(buffer[(x, 1)].bg, search_match);
+        }
+    }
+
+    #[test]
+    fn test_confirm_with_keys() {
+        let (mut app, _) = open(b"hi", 0, &Options::default());
+        app.replace_byte(0, b'H').unwrap();
+
+        // Other keys are ignored, n goes back to the editor, and y in either case quits
+        assert!(ctrl(&mut app, 'q'));
+        assert!(press(&mut app, KeyCode::Char('x'), KeyModifiers::NONE));
+        assert!(app.key_handler.is_focusing(Window::UnsavedChanges));
+        assert!(press(&mut app, KeyCode::Char('n'), KeyModifiers::NONE));
+        assert!(app.key_handler.is_focusing(Window::Hex));
+        assert_eq!(&app.data.contents[..], b"Hi");
+        assert!(ctrl(&mut app, 'q'));
+        assert!(!press(&mut app, KeyCode::Char('Y'), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_patch_line() {
+        let (mut app, _) = open(b"abcd", 1, &Options::default());
+        app.data.clipboard = Clipboard::session();
+        type_text(&mut app, "58");
+        assert_eq!(app.data.original_byte(1), Some(b'b'));
+        assert_eq!(app.data.original_byte(2), None);
+
+        // The byte keeps its original value when the bytes before it move
+        app.data.edit_mode = EditMode::Insert;
+        app.goto(0).unwrap();
+        type_text(&mut app, "41");
+        app.data.contents.block();
+        assert_eq!(&app.data.contents[..], b"AaXcd");
+        assert_eq!(app.data.original_byte(2), Some(b'b'));
+        assert_eq!(app.data.original_byte(0), None);
+        app.goto(0).unwrap();
+        press(&mut app, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(app.data.original_byte(1), Some(b'b'));
+
+        // Without the system clipboard, the patch line is shown instead
+        app.goto(1).unwrap();
+        alt(&mut app, 'y');
+        assert_eq!(app.labels.notification, "0x1: 0x62 -> 0x58");
+        app.goto(2).unwrap();
+        alt(&mut app, 'y');
+        assert_eq!(app.labels.notification, "0x2: 0x63");
+
+        // The address is written like in the address column
+        app.data.base = 0x100;
+        app.data.address_radix = AddressRadix::Dec;
+        alt(&mut app, 'y');
+        assert_eq!(app.labels.notification, "258: 0x63");
+        alt(&mut app, 'p');
+        assert_eq!(app.labels.notification, "Protected 258-258");
+    }
+
+    #[test]
+    fn test_multibyte_edit() {
+        let (mut app, _) = open("aé!".as_bytes(), 2, &Options::default());
+        app.data.encoding = Encoding::Utf8;
+        app.set_focused_window(Window::Ascii);
+
+        // Typing over a character with a protected byte asks first, even if it isn't the one
+        // under the cursor
+        app.data.toggle_protection(1..2);
+        type_text(&mut app, "ü");
+        assert!(app.key_handler.is_focusing(Window::ProtectedEdit));
+        type_text(&mut app, "n");
+        app.data.toggle_protection(1..2);
+
+        // A character of the same length is written over the old one, and one of another length
+        // replaces it
+        app.goto(2).unwrap();
+        type_text(&mut app, "ü");
+        assert_eq!(&app.data.contents[..], "aü!".as_bytes());
+        assert!(matches!(app.data.actions.last(), Some(Action::Overwrite(1, _))));
+        app.goto(1).unwrap();
+        type_text(&mut app, "€");
+        assert_eq!(&app.data.contents[..], "a€!".as_bytes());
+        assert!(matches!(app.data.actions.last(), Some(Action::Replace(1, _, 3))));
+    }
+
+    #[cfg(feature = "random")]
+    #[test]
+    fn test_fill_random() {
+        let options = Options { seed: Some(7), ..Options::default() };
+        let fill = |options: &Options| {
+            let (mut app, _) = open(&[0; 8], 2, options);
+            press(&mut app, KeyCode::Right, KeyModifiers::SHIFT);
+            press(&mut app, KeyCode::Right, KeyModifiers::SHIFT);
+            alt(&mut app, 'r');
+            assert_eq!(app.labels.notification, "Filled 3 random bytes");
+            app
+        };
+
+        // The same seed fills in the same bytes, and only the selected ones
+        let mut app = fill(&options);
+        assert_eq!(app.data.contents[..], fill(&options).data.contents[..]);
+        assert_ne!(app.data.contents[2..5], [0; 3]);
+        assert_eq!(app.data.contents[..2], [0; 2]);
+        assert_eq!(app.data.contents[5..], [0; 3]);
+        ctrl(&mut app, 'z');
+        assert_eq!(app.data.contents[..], [0; 8]);
+
+        app.data.toggle_protection(4..5);
+        app.goto(4).unwrap();
+        alt(&mut app, 'r');
+        assert_eq!(app.labels.notification, "Can't fill protected bytes");
+        assert_eq!(app.data.contents[..], [0; 8]);
+    }
+
+    #[test]
+    fn test_base() {
+        let options = Options { base: 0x4000, ..Options::default() };
+        let (mut app, mut terminal) = open(&[0; 0x40], 0x11, &options);
+        assert_eq!(app.labels["Offset"], "0x4011");
+        assert!(render(&mut app, &mut terminal)[1].starts_with("│00004000│"));
+
+        // Addresses jumped to are relative to the base
+        ctrl(&mut app, 'j');
+        type_text(&mut app, "0x4020");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 0x20);
+        assert_eq!(app.labels["Offset"], "0x4020");
+        ctrl(&mut app, 'j');
+        type_text(&mut app, "0x20");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.labels.notification, "Address is before the base!");
+    }
+
+    #[test]
+    fn test_alt_drag() {
+        let (mut app, _) = open(&[0; 20], 0, &Options::default());
+        let ascii = app.display.comp_layouts.ascii;
+        let mouse = |app: &mut Application, kind, byte: u16, modifiers| {
+            let event = MouseEvent { kind, column: ascii.x + 1 + byte, row: 1, modifiers };
+            app.handle_input(&Event::Mouse(event)).unwrap();
+        };
+        let drag = MouseEventKind::Drag(MouseButton::Left);
+        mouse(&mut app, MouseEventKind::Down(MouseButton::Left), 2, KeyModifiers::NONE);
+        mouse(&mut app, drag, 5, KeyModifiers::NONE);
+        assert_eq!(app.data.selection(), Some(2..6));
+
+        // Holding alt starts the selection over from the cursor, and it stays anchored there
+        mouse(&mut app, drag, 7, KeyModifiers::ALT);
+        assert_eq!(app.data.selection(), Some(5..8));
+        mouse(&mut app, drag, 9, KeyModifiers::NONE);
+        assert_eq!(app.data.selection(), Some(5..10));
+    }
+
+    #[test]
+    fn test_position_label() {
+        let (mut app, _) = open(&[0; 200], 0, &Options::default());
+        let bytes_per_line = app.display.comp_layouts.bytes_per_line;
+        assert_eq!(app.labels["Position"], "0, 0");
+        app.goto(3 * bytes_per_line + 2).unwrap();
+        assert_eq!(app.labels["Position"], "3, 2");
+        press(&mut app, KeyCode::Up, KeyModifiers::NONE);
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(app.labels["Position"], "2, 1");
+    }
+
+    #[test]
+    fn test_address_radix() {
+        let mut radix = AddressRadix::default();
+        let mut formatted = Vec::new();
+        for _ in 0..3 {
+            formatted.push((radix.format_row(64, 8), radix.format_offset(64), radix.to_string()));
+            radix.cycle();
+        }
+        assert_eq!(radix, AddressRadix::Hex);
+        assert_eq!(
+            formatted,
+            [
+                (String::from("00000040"), String::from("0x40"), String::from("Hexadecimal")),
+                (String::from("00000064"), String::from("64"), String::from("Decimal")),
+                (String::from("00000100"), String::from("0o100"), String::from("Octal")),
+            ]
+        );
+
+        // The address column widens for addresses that need more than 8 digits
+        assert_eq!(AddressRadix::Oct.row_digits(0o77_777_777), 8);
+        assert_eq!(AddressRadix::Oct.row_digits(0o100_000_000), 9);
+        assert_eq!(AddressRadix::Dec.row_digits(100_000_000), 9);
+        assert_eq!(AddressRadix::Hex.row_digits(100_000_000), 8);
+
+        let options = Options {
+            base: 99_999_990,
+            address_radix: AddressRadix::Dec,
+            bytes_per_line: Some(16),
+            ..Options::default()
+        };
+        let (mut app, mut terminal) = open(&[0; 32], 0, &options);
+        let lines = render(&mut app, &mut terminal);
+        assert_eq!(app.display.comp_layouts.hex.x, 11);
+        assert!(lines[1].starts_with("│099999990│"));
+        assert!(lines[2].starts_with("│100000006│"));
+    }
+
+    #[test]
+    fn test_bookmarks() {
+        let (mut app, _) = open(&[0; 0x40], 0x30, &Options::default());
+        alt(&mut app, 'n');
+        assert_eq!(app.labels.notification, "No bookmarks");
+        alt(&mut app, 'b');
+        assert_eq!(app.labels.notification, "Bookmarked 0x30");
+        app.goto(0x10).unwrap();
+        alt(&mut app, 'b');
+        app.goto(0x20).unwrap();
+
+        // Jumping goes through the bookmarks in order, wrapping around after the last one
+        alt(&mut app, 'n');
+        assert_eq!(app.current_offset(), 0x30);
+        alt(&mut app, 'n');
+        assert_eq!(app.current_offset(), 0x10);
+        alt(&mut app, 'b');
+        assert_eq!(app.labels.notification, "Removed bookmark");
+        alt(&mut app, 'n');
+        alt(&mut app, 'n');
+        assert_eq!(app.current_offset(), 0x30);
+        assert_eq!(app.data.bookmarks, [0x30]);
+    }
+
+    #[test]
+    fn test_scroll_lines() {
+        let options = Options { scroll_lines: 3, ..Options::default() };
+        let (mut app, _) = open(&[0; 4999], 0, &options);
+        let bytes_per_line = app.display.comp_layouts.bytes_per_line;
+        let lines_per_screen = app.display.comp_layouts.lines_per_screen;
+        let hex = app.display.comp_layouts.hex;
+        let scroll = |app: &mut Application, kind| {
+            let event =
+                MouseEvent { kind, column: hex.x + 1, row: 1, modifiers: KeyModifiers::NONE };
+            app.handle_input(&Event::Mouse(event)).unwrap();
+            app.data.start_address
+        };
+
+        // The wheel scrolls by the number of lines, without moving the cursor
+        assert_eq!(scroll(&mut app, MouseEventKind::ScrollDown), 3 * bytes_per_line);
+        assert_eq!(scroll(&mut app, MouseEventKind::ScrollUp), 0);
+        assert_eq!(scroll(&mut app, MouseEventKind::ScrollUp), 0);
+        assert_eq!(app.current_offset(), 0);
+
+        // It doesn't scroll past the last line
+        let last_start = (4999usize.div_ceil(bytes_per_line) - lines_per_screen) * bytes_per_line;
+        app.data.start_address = last_start - bytes_per_line;
+        assert_eq!(scroll(&mut app, MouseEventKind::ScrollDown), last_start);
+    }
+
+    #[test]
+    fn test_insert_bytes() {
+        let (mut app, _) = open(b"abc", 1, &Options::default());
+        alt(&mut app, 'i');
+        type_text(&mut app, "0x2 AB");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+
+        // The bytes go in before the cursor, which stays on the first of them, and the insertion
+        // is undone at once
+        assert_eq!(&app.data.contents[..], b"a\xAB\xABbc");
+        assert_eq!(app.current_offset(), 1);
+        assert_eq!(app.labels.notification, "Inserted 2 bytes");
+        ctrl(&mut app, 'z');
+        assert_eq!(&app.data.contents[..], b"abc");
+
+        // The byte defaults to 0
+        alt(&mut app, 'i');
+        type_text(&mut app, "1");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(&app.data.contents[..], b"a\0bc");
+        alt(&mut app, 'i');
+        type_text(&mut app, "0");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.labels.notification, "Can insert between 1 and 0x10000000 bytes");
+    }
+
+    #[test]
+    fn test_edit_from_code() {
+        let (mut app, _) = open(b"hello", 0, &Options::default());
+        app.data.last_drag = Some(4);
+        app.goto(1).unwrap();
+        assert_eq!(app.current_offset(), 1);
+        assert_eq!(app.data.selection(), None);
+        assert!(app.goto(5).is_err());
+
+        // Replaced bytes can be undone like typed ones, except for protected ones
+        app.replace_byte(4, b'!').unwrap();
+        assert_eq!(app.selected_bytes(), b"e");
+        assert!(app.data.dirty);
+        app.data.toggle_protection(0..1);
+        assert_eq!(
+            app.replace_byte(0, b'H').unwrap_err().to_string(),
+            "The byte at 0x0 is protected"
+        );
+        assert!(app.replace_byte(5, b'!').is_err());
+        app.data.last_drag = Some(4);
+        assert_eq!(app.selected_bytes(), b"ell!");
+        ctrl(&mut app, 'z');
+        assert_eq!(&app.data.contents[..], b"hello");
+    }
+
+    #[test]
+    fn test_with_backend() {
+        let (mut app, mut terminal) = open(b"hello world", 0, &Options::default());
+
+        let screen = render(&mut app, &mut terminal);
+        assert!(screen[1].contains("│00000000││68 65 6C 6C 6F 20 77 6F  72 6C 64 "));
+        assert!(screen[1].contains("│hello world "));
+        assert!(screen[39].starts_with(
+            " [no file] | 11 bytes | ASCII | Little Endian | Overwrite | Offset 0x0 "
+        ));
+
+        // Typing in the hex editor edits the contents
+        type_text(&mut app, "48");
+        assert_eq!(app.current_offset(), 1);
+        assert!(render(&mut app, &mut terminal)[1].contains("│Hello world "));
+
+        app.goto(6).unwrap();
+        app.replace_byte(6, b'W').unwrap();
+        assert_eq!(app.selected_bytes(), b"W");
+        assert!(app.goto(11).is_err());
+        let screen = render(&mut app, &mut terminal);
+        assert!(screen[1].contains("│Hello World "));
+        assert!(
+            screen[39].starts_with(" [no file] [modified] | 11 bytes | ASCII | Little Endian | ")
+        );
+
+        // There is no file to save the contents to
+        ctrl(&mut app, 's');
+        assert_eq!(app.labels.notification, "There is no file to save to!");
+        assert!(app.data.dirty);
+    }
+
+    #[test]
+    fn test_jump_to_bytes() {
+        let (mut app, _) = open(&[0, 0, 0, 7, 7, 0], 0, &Options::default());
+
+        alt(&mut app, '.');
+        assert_eq!(app.current_offset(), 3);
+        alt(&mut app, '.');
+        assert_eq!(app.current_offset(), 5);
+        alt(&mut app, '.');
+        assert_eq!(app.current_offset(), 5);
+        assert_eq!(app.labels.notification, "No change found");
+        alt(&mut app, ',');
+        assert_eq!(app.current_offset(), 4);
+        app.goto(2).unwrap();
+        alt(&mut app, ',');
+        assert_eq!(app.current_offset(), 2);
+    }
+
+    #[test]
+    fn test_jump_to_zero() {
+        let (mut app, _) = open(&[0, 0, 0, 7, 7, 0], 2, &Options::default());
+
+        // Jumping to zero and non-zero bytes wraps around to the start
+        alt(&mut app, '1');
+        assert_eq!(app.current_offset(), 3);
+        alt(&mut app, '1');
+        assert_eq!(app.current_offset(), 4);
+        alt(&mut app, '0');
+        assert_eq!(app.current_offset(), 5);
+        alt(&mut app, '0');
+        assert_eq!(app.current_offset(), 0);
+        alt(&mut app, '1');
+        assert_eq!(app.current_offset(), 3);
+
+        app.replace_byte(3, 0).unwrap();
+        app.replace_byte(4, 0).unwrap();
+        alt(&mut app, '1');
+        assert_eq!(app.current_offset(), 3);
+        assert_eq!(app.labels.notification, "No non-zero byte found");
+        alt(&mut app, '0');
+        assert_eq!(app.current_offset(), 4);
+    }
+
+    #[test]
+    fn test_status_line() {
+        let options =
+            Options { path: Some(PathBuf::from("dumps/firmware.bin")), ..Options::default() };
+        let (mut app, mut terminal) = open(&[0; 0x20], 0x10, &options);
+        assert!(render(&mut app, &mut terminal)[39].starts_with(
+            " firmware.bin | 32 bytes | ASCII | Little Endian | Overwrite | Offset 0x10 "
+        ));
+
+        // It follows the cursor, the edit mode, and edits that change the size
+        press(&mut app, KeyCode::Insert, KeyModifiers::NONE);
+        type_text(&mut app, "1");
+        app.data.contents.block();
+        assert!(render(&mut app, &mut terminal)[39].starts_with(
+            " firmware.bin [modified] | 33 bytes | ASCII | Little Endian | Insert | Offset 0x10 "
+        ));
+        app.goto(0x1F).unwrap();
+        assert!(render(&mut app, &mut terminal)[39].contains(" | Offset 0x1F "));
+    }
+
+    #[test]
+    fn test_file_start_end() {
+        let (mut app, _) = open(&[0; 10000], 20, &Options::default());
+        app.data.last_drag = Some(10);
+
+        // Either end of the file is scrolled into view, and the selection is cleared
+        press(&mut app, KeyCode::End, KeyModifiers::CONTROL);
+        assert_eq!((app.current_offset(), app.data.nibble), (9999, Nibble::End));
+        assert_eq!(app.data.selection(), None);
+        assert!(app.visible_range().contains(&9999));
+        type_text(&mut app, "g");
+        assert_eq!((app.current_offset(), app.data.nibble), (0, Nibble::Beginning));
+        assert_eq!(app.data.start_address, 0);
+
+        // In the ASCII editor, there are no nibbles to move to
+        app.set_focused_window(Window::Ascii);
+        press(&mut app, KeyCode::End, KeyModifiers::CONTROL);
+        assert_eq!((app.current_offset(), app.data.nibble), (9999, Nibble::Beginning));
+    }
+
+    #[test]
+    fn test_step() {
+        let (mut app, mut terminal) = open(&[0; 6], 0, &Options::default());
+
+        // Left and right can move by several bytes, landing on the first nibble
+        alt(&mut app, 'w');
+        alt(&mut app, 'w');
+        assert_eq!(app.data.step, 4);
+        assert!(render(&mut app, &mut terminal)[39].contains(" | Overwrite | Step 4 | "));
+        type_text(&mut app, "1");
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!((app.current_offset(), app.data.nibble), (4, Nibble::Beginning));
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 5);
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 1);
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!((app.current_offset(), app.data.nibble), (0, Nibble::Beginning));
+
+        // The step cycles through 1, 2, 4, and 8 bytes
+        alt(&mut app, 'w');
+        assert_eq!(app.data.step, 8);
+        alt(&mut app, 'w');
+        assert_eq!(app.data.step, 1);
+        assert!(!render(&mut app, &mut terminal)[39].contains("Step"));
+    }
+
+    #[test]
+    fn test_endianness() {
+        let (mut app, mut terminal) = open(&[1, 2], 0, &Options::default());
+        assert!(render(&mut app, &mut terminal)[39].contains(" | Little Endian | "));
+        assert_eq!(app.labels["Unsigned 16 bit"], "513");
+
+        // The endianness stays on the status line after it's switched, unlike the notification
+        ctrl(&mut app, 'e');
+        assert_eq!(app.labels.notification, "Big Endian");
+        assert_eq!(app.labels["Unsigned 16 bit"], "258");
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert!(app.labels.notification.is_empty());
+        assert!(render(&mut app, &mut terminal)[39].contains(" | Big Endian | "));
+        ctrl(&mut app, 'e');
+        assert!(render(&mut app, &mut terminal)[39].contains(" | Little Endian | "));
+    }
+
+    #[test]
+    fn test_diff() {
+        let path = std::env::temp_dir().join(format!("heh-diff-test-{}", std::process::id()));
+        fs::write(&path, b"hello there").unwrap();
+        let options = Options { diff: Some(path.clone()), ..Options::default() };
+        let (mut app, mut terminal) = open(b"hello world", 0, &options);
+        fs::remove_file(&path).unwrap();
+
+        // The other file is displayed in a second hex editor, on the same rows
+        let screen = render(&mut app, &mut terminal);
+        assert!(
+            screen[1].contains("│68 65 6C 6C 6F 20 77 6F  72 6C ││68 65 6C 6C 6F 20 74 68  65 72 ")
+        );
+        assert!(screen[2].contains("│64 "));
+        assert!(screen[2].contains("││65 "));
+        assert!(!app.data.differs(5));
+        assert!(app.data.differs(6));
+        assert!(app.data.differs(10));
+
+        // The file to compare with must exist
+        let options = Options { diff: Some(path), ..Options::default() };
+        let backend = terminal.backend();
+        assert!(Application::with_backend(backend, b"hi", Encoding::Ascii, 0, &options).is_err());
+    }
+
+    #[test]
+    fn test_overview() {
+        let mut contents = vec![0; 10000];
+        contents[9990] = 0xFF;
+        let (mut app, mut terminal) = open(&contents, 0, &Options::default());
+        app.data.search_term = String::from("hex:FF");
+        app.data.reindex_search().unwrap();
+        app.data.receive_search_matches(true);
+
+        // The bar on the right marks the search match near the end of the file
+        let overview = app.display.comp_layouts.overview;
+        let screen = render(&mut app, &mut terminal);
+        let bar: Vec<char> = (overview.top()..overview.bottom())
+            .filter_map(|y| screen[usize::from(y)].chars().nth(usize::from(overview.x)))
+            .collect();
+        assert_eq!(bar.iter().filter(|&&symbol| symbol == '█').count(), 1);
+        assert_eq!(bar.last(), Some(&'█'));
+
+        // Clicking the bar jumps to that part of the file
+        app.handle_input(&Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: overview.x,
+            row: overview.bottom() - 1,
+            modifiers: KeyModifiers::NONE,
+        }))
+        .unwrap();
+        let last_row = crate::screen::overview_offset(overview.height - 1, overview.height, 10000);
+        assert_eq!(app.current_offset(), last_row);
+        assert!(app.data.start_address > 0);
+    }
+
+    #[test]
+    fn test_delete_selection() {
+        let (mut app, _) = open(&[0, 1, 2, 3, 4, 5], 4, &Options::default());
+
+        // The whole selection is deleted at once, and can be undone at once
+        app.data.last_drag = Some(1);
+        press(&mut app, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(&*app.data.contents, &[0, 5]);
+        assert_eq!((app.current_offset(), app.data.selection()), (1, None));
+        ctrl(&mut app, 'z');
+        assert_eq!(&*app.data.contents, &[0, 1, 2, 3, 4, 5]);
+
+        // At least one byte is left
+        app.goto(5).unwrap();
+        app.data.last_drag = Some(0);
+        press(&mut app, KeyCode::Delete, KeyModifiers::NONE);
+        assert_eq!(app.data.contents.len(), 6);
+        assert_eq!(app.labels.notification, "Can't delete the whole file");
+    }
+
+    #[test]
+    fn test_count() {
+        let (mut app, _) = open(&[0; 1000], 0, &Options::default());
+        let bytes_per_line = app.display.comp_layouts.bytes_per_line;
+
+        // A count typed after # repeats the next movement
+        type_text(&mut app, "#12j");
+        assert_eq!(app.current_offset(), 12 * bytes_per_line);
+        type_text(&mut app, "j");
+        assert_eq!(app.current_offset(), 13 * bytes_per_line);
+        type_text(&mut app, "#4l");
+        assert_eq!(app.current_offset(), 13 * bytes_per_line + 2);
+
+        // Other keys drop the count
+        type_text(&mut app, "#3");
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        type_text(&mut app, "k");
+        assert_eq!(app.current_offset(), 12 * bytes_per_line + 2);
+        assert!(app.data.count.is_none());
+    }
+
+    #[test]
+    fn test_update_search() {
+        let (mut app, _) = open(b"ABAB.AB..A", 0, &Options::default());
+        let reindexed = |app: &mut Application| {
+            app.data.reindex_search().unwrap();
+            app.data.receive_search_matches(true);
+            (app.data.search_offsets.clone(), app.data.search_lengths.clone())
+        };
+
+        for term in ["AB", "hex:41 42", "hex:4? 42", "ABA"] {
+            app.data.search_term = String::from(term);
+            reindexed(&mut app);
+            for (offset, value) in [(0, b'.'), (4, b'A'), (5, b'B'), (9, b'B'), (1, b'A')] {
+                app.replace_byte(offset, value).unwrap();
+                app.data.update_search().unwrap();
+                // Updating around the typed byte doesn't start a reindex
+                assert!(app.data.search_index.is_none());
+                let updated = (app.data.search_offsets.clone(), app.data.search_lengths.clone());
+                assert_eq!(updated, reindexed(&mut app), "{term} after typing at {offset}");
+            }
+        }
+
+        // Undoing needs a reindex, which finds the match that came back
+        assert_eq!(&*app.data.contents, b".AABABB..B");
+        app.data.search_term = String::from("AB");
+        assert_eq!(reindexed(&mut app).0, [2, 4]);
+        app.replace_byte(2, b'.').unwrap();
+        app.data.update_search().unwrap();
+        assert_eq!(app.data.search_offsets, [4]);
+        ctrl(&mut app, 'z');
+        app.data.update_search().unwrap();
+        assert!(app.data.search_index.is_some());
+        app.data.receive_search_matches(true);
+        assert_eq!(app.data.search_offsets, [2, 4]);
+    }
+
+    #[test]
+    fn test_hex_search() {
+        let (mut app, _) = open(b"hex:41 AB 0x41", 0, &Options::default());
+        let search = |app: &mut Application, term: &str| {
+            app.data.search_term = String::from(term);
+            app.data.reindex_search().map(|()| {
+                app.data.receive_search_matches(true);
+                app.data.search_offsets.clone()
+            })
+        };
+
+        // Terms are only searched for as hexadecimal with the prefix, which can be searched for
+        // as text with another one
+        assert_eq!(search(&mut app, "41"), Ok(vec![4, 12]));
+        assert_eq!(search(&mut app, "0x41"), Ok(vec![10]));
+        assert_eq!(search(&mut app, "hex:41 42"), Ok(vec![7]));
+        assert_eq!(search(&mut app, "ascii:hex:41"), Ok(vec![0]));
+        assert_eq!(
+            search(&mut app, "hex:4"),
+            Err(String::from("Invalid hex: expected a multiple of 2 digits"))
+        );
+        assert_eq!(search(&mut app, "hex:0x41"), Err(String::from("Invalid hex: invalid digit x")));
+    }
+
+    #[test]
+    fn test_quit() {
+        let (mut app, _) = open(b"hi", 0, &Options::default());
+        app.replace_byte(0, b'H').unwrap();
+        assert!(ctrl(&mut app, 'q'));
+        assert!(app.key_handler.is_focusing(Window::UnsavedChanges));
+
+        // There is no file to save the changes to, so they are kept open
+        app.focus_editor();
+        assert!(ctrl(&mut app, 'x'));
+        assert_eq!(app.labels.notification, "There is no file to save to!");
+
+        let options = Options { confirm_quit: false, ..Options::default() };
+        let (mut app, _) = open(b"hi", 0, &options);
+        app.replace_byte(0, b'H').unwrap();
+        assert!(!ctrl(&mut app, 'q'));
+
+        // A bare q only quits with --vim, and is typed into the hex editor otherwise
+        let (mut app, _) = open(b"hi", 0, &options);
+        assert!(press(&mut app, KeyCode::Char('q'), KeyModifiers::NONE));
+        assert_eq!(app.labels.notification, "Invalid Hex: q");
+        let (mut app, _) = open(b"hi", 0, &Options { vim: true, ..options });
+        assert!(!press(&mut app, KeyCode::Char('q'), KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_copy_offset() {
+        let options = Options { base: 0x1000, ..Options::default() };
+        let (mut app, _) = open(&[0; 0x100], 0x2B, &options);
+        app.data.clipboard = Clipboard::session();
+        alt(&mut app, 'l');
+        assert_eq!(app.labels.notification, "Offset 0x102B copied within heh");
+        assert_eq!(app.data.clipboard.get_text().as_deref(), Some("0x102B"));
+        app.data.address_radix = AddressRadix::Dec;
+        alt(&mut app, 'l');
+        assert_eq!(app.labels.notification, "Offset 4139 copied within heh");
+    }
+
+    #[test]
+    fn test_shift_select() {
+        let (mut app, _) = open(&[0; 100], 20, &Options::default());
+        let bytes_per_line = app.display.comp_layouts.bytes_per_line;
+        press(&mut app, KeyCode::Right, KeyModifiers::SHIFT);
+        press(&mut app, KeyCode::Right, KeyModifiers::SHIFT);
+        assert_eq!(app.data.selection(), Some(20..23));
+
+        // The selection stays anchored where it started
+        press(&mut app, KeyCode::Up, KeyModifiers::SHIFT);
+        assert_eq!(app.data.selection(), Some(22 - bytes_per_line..21));
+        press(&mut app, KeyCode::Down, KeyModifiers::SHIFT);
+        press(&mut app, KeyCode::Left, KeyModifiers::SHIFT);
+        assert_eq!(app.data.selection(), Some(20..22));
+
+        // Moving without shift clears it
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(app.data.selection(), None);
+    }
+
+    #[test]
+    fn test_follow_pointer() {
+        let mut contents = vec![0; 0x40];
+        contents[..4].copy_from_slice(&[0x20, 0x10, 0, 0]);
+        contents[0x20..0x24].copy_from_slice(&[0x30, 0x10, 0, 0]);
+        contents[0x30..0x34].copy_from_slice(&[0x00, 0x20, 0, 0]);
+        let options = Options { base: 0x1000, ..Options::default() };
+        let (mut app, _) = open(&contents, 0, &options);
+
+        // Pointers hold addresses, which start at the base
+        alt(&mut app, 'j');
+        assert_eq!(app.current_offset(), 0x20);
+        alt(&mut app, 'j');
+        assert_eq!(app.current_offset(), 0x30);
+
+        // A pointer outside of the file isn't followed
+        alt(&mut app, 'j');
+        assert_eq!(app.current_offset(), 0x30);
+        assert_eq!(app.labels.notification, "Pointer 0x2000 is outside of the file");
+
+        // In big endian, the same bytes point elsewhere
+        app.goto(0x20).unwrap();
+        app.labels.switch_endianness();
+        alt(&mut app, 'j');
+        assert_eq!(app.labels.notification, "Pointer 0x30100000 is outside of the file");
+
+        // Going back returns to where each pointer was followed from, in reverse
+        alt(&mut app, 'k');
+        assert_eq!(app.current_offset(), 0x20);
+        alt(&mut app, 'k');
+        assert_eq!(app.current_offset(), 0);
+        alt(&mut app, 'k');
+        assert_eq!(app.labels.notification, "No pointer to go back from");
+    }
+
+    #[test]
+    fn test_layout_accessors() {
+        let (mut app, _) = open(&[0; 10010], 5000, &Options::default());
+
+        // 20 bytes and the spaces between them fit in the hex editor, and the screen around the
+        // cursor is full
+        assert_eq!(app.bytes_per_line(), 20);
+        let visible = app.visible_range();
+        assert!(visible.contains(&5000));
+        assert_eq!(visible.len(), 20 * app.lines_per_screen());
+
+        // Only the bytes in the file are visible at its end, which is partway through a line
+        app.goto(10009).unwrap();
+        assert_eq!(app.visible_range().end, 10010);
+        assert!(app.visible_range().len() < 20 * app.lines_per_screen());
+    }
+
+    #[test]
+    fn test_cursor_style() {
+        let (mut app, mut terminal) = open(b"hello", 0, &Options::default());
+        press(&mut app, KeyCode::Right, KeyModifiers::SHIFT);
+        press(&mut app, KeyCode::Right, KeyModifiers::SHIFT);
+        render(&mut app, &mut terminal);
+
+        // The cursor stands out from the rest of the selection in both editors. It's on the last
+        // nibble of the third byte in the hex editor.
+        let buffer = terminal.backend().buffer();
+        let (hex, ascii) = (app.display.comp_layouts.hex, app.display.comp_layouts.ascii);
+        let theme = Theme::default();
+        for (x, cursor_x) in [(hex.x + 1, hex.x + 8), (ascii.x + 1, ascii.x + 3)] {
+            assert_eq!(buffer[(x, 1)].bg, theme.selection);
+            assert_eq!(buffer[(cursor_x, 1)].bg, theme.cursor);
+            assert_eq!(buffer[(cursor_x, 1)].fg, theme.cursor_text);
+        }
+        assert_ne!(theme.cursor, theme.selection);
+    }
+
+    #[test]
+    fn test_ascii_escape() {
+        let (mut app, _) = open(b"hello", 0, &Options::default());
+        app.set_focused_window(Window::Ascii);
+
+        // Escapes type bytes that can't be typed otherwise, and a backslash is typed twice
+        type_text(&mut app, "\\x1");
+        assert_eq!(app.labels.notification, "\\x1");
+        type_text(&mut app, "B\\\\\\0");
+        assert_eq!(&app.data.contents[..], b"\x1b\\\0lo");
+        assert_eq!(app.current_offset(), 3);
+
+        // Invalid escapes are dropped without writing anything
+        type_text(&mut app, "\\q");
+        assert_eq!(app.labels.notification, "Invalid escape: \\q");
+        type_text(&mut app, "\\xg");
+        assert_eq!(app.labels.notification, "Invalid escape: \\xg");
+        assert_eq!(&app.data.contents[..], b"\x1b\\\0lo");
+
+        // Each escaped byte is undone on its own
+        ctrl(&mut app, 'z');
+        assert_eq!(&app.data.contents[..], b"\x1b\\llo");
+    }
+
+    #[test]
+    fn test_jump_to_boundary() {
+        let options = Options { alignment: Some(16), base: 0x1004, ..Options::default() };
+        let (mut app, _) = open(&[0; 40], 0, &options);
+
+        // Boundaries are aligned addresses, which start at the base
+        for offset in [12, 28, 39] {
+            press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+            assert_eq!(app.current_offset(), offset);
+        }
+        for offset in [28, 12, 0] {
+            press(&mut app, KeyCode::BackTab, KeyModifiers::SHIFT);
+            assert_eq!(app.current_offset(), offset);
+        }
+
+        // Without an alignment, tab moves to the start of the next line
+        app.data.alignment = None;
+        app.data.base = 0;
+        app.goto(3).unwrap();
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), app.bytes_per_line());
+    }
+
+    #[test]
+    fn test_pin_distance() {
+        let options = Options { base: 0x1000, ..Options::default() };
+        let (mut app, _) = open(&[0; 0x40], 0, &options);
+        assert_eq!(app.labels["Δ from pin"], "");
+
+        app.goto(0x10).unwrap();
+        alt(&mut app, 'z');
+        assert_eq!(app.labels.notification, "Pinned 0x1010");
+        assert_eq!(app.labels["Δ from pin"], "0x0");
+        app.goto(0x18).unwrap();
+        assert_eq!(app.labels["Δ from pin"], "+0x8");
+        app.goto(0x4).unwrap();
+        assert_eq!(app.labels["Δ from pin"], "-0xC");
+
+        // The distance is shown in the radix of the addresses
+        alt(&mut app, 'x');
+        assert_eq!(app.labels["Δ from pin"], "-12");
+
+        // Pinning the same address again unpins it
+        app.goto(0x10).unwrap();
+        alt(&mut app, 'z');
+        assert_eq!(app.labels.notification, "Unpinned");
+        assert_eq!(app.labels["Δ from pin"], "");
+    }
+
+    #[cfg(feature = "disasm")]
+    #[test]
+    fn test_disasm_label() {
+        use crate::disasm::Arch;
+
+        let contents = [0x90, 0xEB, 0x00, 0xB8, 0x01, 0x00];
+        let options = Options { base: 0x1000, arch: Some(Arch::X86_64), ..Options::default() };
+        let (mut app, _) = open(&contents, 0, &options);
+        assert_eq!(app.labels.titles.last(), Some(&"Disasm"));
+        assert_eq!(app.labels["Disasm"], "nop (1)");
+        app.goto(1).unwrap();
+        assert_eq!(app.labels["Disasm"], "jmp 0x1003 (2)");
+
+        // The mov continues past the end of the file
+        app.goto(3).unwrap();
+        assert_eq!(app.labels["Disasm"], "(n/a)");
+
+        // Without an architecture, there is no label
+        let (app, _) = open(&contents, 0, &Options::default());
+        assert!(!app.labels.titles.contains(&"Disasm"));
+    }
+
+    #[test]
+    fn test_notification_history() {
+        let (mut app, _) = open(b"hi", 0, &Options::default());
+        let startup = app.labels.history.len();
+
+        // Notifications are kept after the next key clears them
+        type_text(&mut app, "z");
+        alt(&mut app, 'k');
+        type_text(&mut app, "h");
+        assert!(app.labels.notification.is_empty());
+        let history: Vec<&str> =
+            app.labels.history.iter().skip(startup).map(|(_, text)| text.as_str()).collect();
+        assert_eq!(history, ["Invalid Hex: z", "No pointer to go back from"]);
+
+        ctrl(&mut app, 'l');
+        assert!(app.key_handler.is_focusing(Window::Notifications));
+
+        for _ in 0..100 {
+            alt(&mut app, 'k');
+        }
+        assert_eq!(app.labels.history.len(), 50);
+    }
+
+    #[test]
+    fn test_multiple_files() {
+        let path = std::env::temp_dir().join(format!("heh-files-test-{}", std::process::id()));
+        fs::write(&path, b"other").unwrap();
+        let file = fs::OpenOptions::new().read(true).write(true).open(&path).unwrap();
+        let (mut app, _) = open(b"hello", 0, &Options::default());
+        let options = Options { path: Some(path.clone()), ..Options::default() };
+        app.add_file(file, &options).unwrap();
+
+        // Each file keeps its own cursor and unsaved changes
+        app.goto(3).unwrap();
+        app.replace_byte(3, b'L').unwrap();
+        ctrl(&mut app, 'o');
+        assert_eq!(app.labels.notification, format!("Editing {}", app.data.file_name()));
+        assert_eq!(app.current_offset(), 0);
+        assert_eq!(&app.data.contents[..], b"other");
+        assert!(!app.data.dirty);
+        app.replace_byte(0, b'O').unwrap();
+        ctrl(&mut app, 'o');
+        assert_eq!(app.current_offset(), 3);
+        assert_eq!(&app.data.contents[..], b"helLo");
+
+        // Quitting asks first while any file has unsaved changes
+        assert!(ctrl(&mut app, 'q'));
+        assert!(app.key_handler.is_focusing(Window::UnsavedChanges));
+
+        // Saving every file writes the other one even though this one has nowhere to go
+        app.focus_editor();
+        assert!(ctrl(&mut app, 'x'));
+        assert_eq!(app.labels.notification, "There is no file to save to!");
+        assert_eq!(fs::read(&path).unwrap(), b"Other");
+        assert!(!app.files[0].dirty);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_record_highlight() {
+        let options = Options { alignment: Some(4), ..Options::default() };
+        let (mut app, mut terminal) = open(&[0; 16], 1, &options);
+        render(&mut app, &mut terminal);
+
+        // Only the nibbles of the first byte of each record are highlighted
+        let buffer = terminal.backend().buffer();
+        let hex = app.display.comp_layouts.hex;
+        let record = Theme::default().record;
+        for x in [hex.x + 1, hex.x + 2, hex.x + 13, hex.x + 14] {
+            assert_eq!(buffer[(x, 1)].bg, record);
+        }
+        for x in [hex.x + 3, hex.x + 7, hex.x + 15] {
+            assert_ne!(buffer[(x, 1)].bg, record);
+        }
+    }
+
+    #[test]
+    fn test_heat_map() {
+        let (mut app, mut terminal) = open(b"\x00A\xFF", 2, &Options::default());
+        let (hex, ascii) = (app.display.comp_layouts.hex, app.display.comp_layouts.ascii);
+        let colors = |app: &mut Application, terminal: &mut Terminal<TestBackend>| {
+            render(app, terminal);
+            let buffer = terminal.backend().buffer();
+            [buffer[(hex.x + 1, 1)].fg, buffer[(hex.x + 4, 1)].fg, buffer[(ascii.x + 2, 1)].fg]
+        };
+        let theme = Theme::default();
+        assert_eq!(colors(&mut app, &mut terminal), [theme.null, theme.ascii, theme.ascii]);
+
+        alt(&mut app, 'g');
+        let a = Theme::heat_color(b'A');
+        assert_eq!(colors(&mut app, &mut terminal), [Theme::heat_color(0), a, a]);
+    }
+
+    #[test]
+    fn test_page_navigation() {
+        let options = Options { page_size: 0x100, base: 0x1080, ..Options::default() };
+        let (mut app, _) = open(&[0; 0x300], 0, &options);
+        assert_eq!(app.labels["Page"], "16, +0x80");
+
+        // Pages are aligned addresses, which start at the base
+        press(&mut app, KeyCode::PageDown, KeyModifiers::ALT);
+        assert_eq!(app.current_offset(), 0x80);
+        assert_eq!(app.labels["Page"], "17, +0x0");
+        press(&mut app, KeyCode::PageDown, KeyModifiers::ALT);
+        assert_eq!(app.current_offset(), 0x180);
+        app.goto(0x1C0).unwrap();
+        assert_eq!(app.labels["Page"], "18, +0x40");
+        press(&mut app, KeyCode::PageUp, KeyModifiers::ALT);
+        assert_eq!(app.current_offset(), 0x180);
+        press(&mut app, KeyCode::PageUp, KeyModifiers::ALT);
+        assert_eq!(app.current_offset(), 0x80);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let options = Options { wrap: true, ..Options::default() };
+        let (mut app, _) = open(b"abcdef", 0, &options);
+
+        // Left from the first nibble goes to the last nibble of the file, and back
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!((app.current_offset(), app.data.nibble), (5, Nibble::End));
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!((app.current_offset(), app.data.nibble), (0, Nibble::Beginning));
+
+        // The ASCII editor wraps by whole bytes
+        app.set_focused_window(Window::Ascii);
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 5);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 0);
+
+        // Steps of several bytes carry over the end
+        app.data.step = 4;
+        press(&mut app, KeyCode::Left, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 2);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 4);
+
+        // Without --wrap, the cursor stops at the ends
+        let (mut app, _) = open(b"abcdef", 5, &Options::default());
+        app.set_focused_window(Window::Ascii);
+        press(&mut app, KeyCode::Right, KeyModifiers::NONE);
+        assert_eq!(app.current_offset(), 5);
+    }
+
+    #[test]
+    fn test_histogram() {
+        let contents = [b"\x00\x00\x00AAB".as_slice(), &[0xFF; 4]].concat();
+        let (mut app, mut terminal) = open(&contents, 0, &Options::default());
+        alt(&mut app, 'q');
+        assert!(app.key_handler.is_focusing(Window::Histogram));
+        assert!(!app.key_handler.work(&app.data));
+        let lines = render(&mut app, &mut terminal);
+
+        // The most common bytes are labeled below their bars, with their share of the bytes
+        let popup = app.display.comp_layouts.popup;
+        let row =
+            |y: u16| lines[usize::from(y)].chars().skip(usize::from(popup.x)).collect::<String>();
+        assert!(row(popup.y).contains("Histogram of 0x0-0x9"));
+        assert!(row(popup.bottom() - 2).starts_with("│FF  00  41  42"));
+        assert!(row(popup.bottom() - 3).starts_with("│40% 30% 20% 10%"));
+    }
+
+    #[test]
+    fn test_hex_byte_entry() {
+        let options = Options { hex_entry: HexEntry::Byte, ..Options::default() };
+        let (mut app, mut terminal) = open(b"\x00\x11\x22", 0, &options);
+
+        // The first digit is only shown until the second one finishes the byte
+        type_text(&mut app, "a");
+        assert_eq!(app.data.contents[0], 0x00);
+        assert_eq!((app.current_offset(), app.data.nibble), (0, Nibble::End));
+        let lines = render(&mut app, &mut terminal);
+        assert!(hex_row(&app, &lines, 1).starts_with("A0 11"));
+        type_text(&mut app, "B");
+        assert_eq!(app.data.contents[0], 0xAB);
+        assert_eq!((app.current_offset(), app.data.nibble), (1, Nibble::Beginning));
+
+        // Escape discards the first digit, and enter writes it with the second nibble kept
+        type_text(&mut app, "5");
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(app.data.pending_nibble, None);
+        assert_eq!(app.data.contents[1], 0x11);
+        type_text(&mut app, "5");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.data.contents[1], 0x51);
+        assert_eq!(app.current_offset(), 2);
+
+        // Other characters don't discard the first digit
+        type_text(&mut app, "fx");
+        assert_eq!(app.labels.notification, "Invalid Hex: x");
+        type_text(&mut app, "f");
+        assert_eq!(app.data.contents[2], 0xFF);
+
+        // A whole byte is undone at once
+        ctrl(&mut app, 'z');
+        assert_eq!(app.data.contents[2], 0x22);
+    }
+
+    #[test]
+    fn test_hex_byte_entry_moved() {
+        let options = Options { hex_entry: HexEntry::Byte, ..Options::default() };
+        let (mut app, _) = open(&[0; 100], 0, &options);
+        let bytes_per_line = app.bytes_per_line();
+
+        // Moving the cursor discards the first digit instead of finishing the byte elsewhere
+        type_text(&mut app, "a");
+        press(&mut app, KeyCode::Down, KeyModifiers::NONE);
+        assert_eq!((app.data.pending_nibble, app.data.nibble), (None, Nibble::Beginning));
+        type_text(&mut app, "b");
+        assert_eq!(app.data.contents[bytes_per_line], 0);
+        type_text(&mut app, "c");
+        assert_eq!(app.data.contents[bytes_per_line], 0xBC);
+        assert!(app.data.contents[..bytes_per_line].iter().all(|&byte| byte == 0));
+
+        for code in [KeyCode::Left, KeyCode::Right, KeyCode::Up, KeyCode::PageDown, KeyCode::End] {
+            type_text(&mut app, "d");
+            press(&mut app, code, KeyModifiers::NONE);
+            assert_eq!(app.data.pending_nibble, None, "{code:?}");
+        }
+
+        // Clicking a byte doesn't finish the digit there either
+        type_text(&mut app, "e");
+        let hex = app.display.comp_layouts.hex;
+        app.handle_input(&Event::Mouse(MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: hex.x + 1,
+            row: hex.y + 1,
+            modifiers: KeyModifiers::NONE,
+        }))
+        .unwrap();
+        assert_eq!((app.current_offset(), app.data.nibble), (0, Nibble::Beginning));
+        type_text(&mut app, "f");
+        assert_eq!(app.data.contents[0], 0);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let (mut app, _) = open(b"hello world", 5, &Options::default());
+
+        // The cursor moves to the new last byte
+        ctrl(&mut app, 'k');
+        assert_eq!(&app.data.contents[..], b"hello");
+        assert_eq!(app.current_offset(), 4);
+        assert_eq!(app.labels.notification, "Truncated 6 bytes");
+        assert!(app.data.dirty);
+
+        // It is undone at once
+        ctrl(&mut app, 'z');
+        assert_eq!(&app.data.contents[..], b"hello world");
+        assert_eq!(app.current_offset(), 5);
+
+        // The file can't be emptied
+        app.goto(0).unwrap();
+        ctrl(&mut app, 'k');
+        assert_eq!(app.labels.notification, "Can't delete the whole file");
+        assert_eq!(app.data.contents.len(), 11);
+    }
+
+    #[test]
+    fn test_ruler() {
+        let options = Options { bytes_per_line: Some(16), group_size: 4, ..Options::default() };
+        let (mut app, mut terminal) = open(&[0; 64], 0, &options);
+        let lines_per_screen = app.display.comp_layouts.lines_per_screen;
+        render(&mut app, &mut terminal);
+
+        // The editors move down a row, with the indices of the columns above their bytes
+        ctrl(&mut app, 'y');
+        assert_eq!(app.display.comp_layouts.lines_per_screen, lines_per_screen - 1);
+        let lines = render(&mut app, &mut terminal);
+        let (hex, ascii) = (app.display.comp_layouts.hex, app.display.comp_layouts.ascii);
+        assert_eq!(hex.y, 1);
+        let at =
+            |x: u16, len| lines[0].chars().skip(usize::from(x) + 1).take(len).collect::<String>();
+        assert_eq!(at(hex.x, 15), "00 01 02 03  04");
+        assert_eq!(at(ascii.x, 16), "0123456789ABCDEF");
+        assert!(hex_row(&app, &lines, 2).starts_with("00 00"));
+
+        ctrl(&mut app, 'y');
+        assert_eq!(app.display.comp_layouts.lines_per_screen, lines_per_screen);
+        assert_eq!(app.display.comp_layouts.hex.y, 0);
+    }
+
+    #[test]
+    fn test_ruler_drag() {
+        let options = Options { bytes_per_line: Some(16), ruler: true, ..Options::default() };
+        let (mut app, _) = open(&[0; 1600], 0, &options);
+        let hex = app.display.comp_layouts.hex;
+        assert_eq!(hex.y, 1);
+        app.goto(800).unwrap();
+        app.data.start_address = 800;
+        let mouse = |app: &mut Application, kind, row| {
+            let event = MouseEvent { kind, column: hex.x + 1, row, modifiers: KeyModifiers::NONE };
+            app.handle_input(&Event::Mouse(event)).unwrap();
+        };
+
+        // Dragging onto the border or the ruler above the editor scrolls up a row at a time
+        mouse(&mut app, MouseEventKind::Down(MouseButton::Left), hex.y + 1);
+        for row in [hex.y, 0] {
+            mouse(&mut app, MouseEventKind::Drag(MouseButton::Left), row);
+        }
+        assert_eq!((app.current_offset(), app.data.start_address), (768, 768));
+        assert_eq!(app.data.selection(), Some(768..801));
+    }
+
+    #[test]
+    fn test_hide_labels() {
+        let (mut app, mut terminal) = open(&[0; 64], 0, &Options::default());
+        let lines_per_screen = app.display.comp_layouts.lines_per_screen;
+
+        // The editors take the rows of the labels, and the status line stays at the bottom
+        ctrl(&mut app, 'b');
+        assert_eq!(app.display.comp_layouts.hex.height, 39);
+        assert_eq!(app.display.comp_layouts.lines_per_screen, 37);
+        let lines = render(&mut app, &mut terminal);
+        assert!(lines[39].contains("Offset"));
+        assert!(lines.iter().all(|line| !line.contains("Notifications")));
+
+        ctrl(&mut app, 'b');
+        assert_eq!(app.display.comp_layouts.lines_per_screen, lines_per_screen);
+
+        // The labels can be hidden from the start
+        let (app, _) = open(&[0; 64], 0, &Options { hide_labels: true, ..Options::default() });
+        assert_eq!(app.display.comp_layouts.lines_per_screen, 37);
+    }
+
+    #[test]
+    fn test_macro() {
+        let (mut app, _) = open(&[0; 8], 0, &Options::default());
+        press(&mut app, KeyCode::F(6), KeyModifiers::NONE);
+        assert_eq!(app.labels.notification, "No macro was recorded (F5 to record one)");
+
+        // The keys between F5 and F5 are recorded while they run
+        press(&mut app, KeyCode::F(5), KeyModifiers::NONE);
+        type_text(&mut app, "01ll");
+        press(&mut app, KeyCode::F(5), KeyModifiers::NONE);
+        assert_eq!(app.labels.notification, "Recorded a macro of 4 keys");
+        assert_eq!(&app.data.contents[..], [1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(app.current_offset(), 2);
+
+        press(&mut app, KeyCode::F(6), KeyModifiers::NONE);
+        assert_eq!(&app.data.contents[..], [1, 0, 1, 0, 0, 0, 0, 0]);
+        assert_eq!(app.current_offset(), 4);
+
+        // A count plays it several times, stopping once the cursor can't move past the end
+        type_text(&mut app, "#9");
+        press(&mut app, KeyCode::F(6), KeyModifiers::NONE);
+        assert_eq!(&app.data.contents[..], [1, 0, 1, 0, 1, 0, 1, 0]);
+        assert_eq!(app.current_offset(), 7);
+        assert_eq!(app.labels.notification, "Stopped the macro at the edge of the file");
+    }
+
+    #[test]
+    fn test_align_cursor() {
+        let (mut app, _) = open(&[0; 40], 5, &Options::default());
+        let align = |app: &mut Application, input: &str| {
+            ctrl(app, 'a');
+            type_text(app, input);
+            press(app, KeyCode::Enter, KeyModifiers::NONE);
+            app.current_offset()
+        };
+
+        assert_eq!(align(&mut app, "16"), 16);
+        assert_eq!(app.labels.notification, "Aligned to 0x10");
+        // An aligned cursor stays, and "-" moves back to the previous multiple
+        assert_eq!(align(&mut app, "0x8"), 16);
+        assert_eq!(align(&mut app, "-12"), 12);
+        assert_eq!(align(&mut app, "32"), 32);
+
+        assert_eq!(align(&mut app, "0x30"), 32);
+        assert_eq!(app.labels.notification, "There is no multiple of 48 after the cursor!");
+        assert_eq!(align(&mut app, "0"), 32);
+        assert_eq!(app.labels.notification, "The alignment must be at least 1!");
+    }
+
+    #[test]
+    fn test_compare_clipboard() {
+        let (mut app, _) = open(b"\x7FELF\x02\x01", 0, &Options::default());
+        app.data.clipboard = Clipboard::session();
+        let compare = |app: &mut Application, text: &str| {
+            app.data.clipboard.set_text(String::from(text));
+            ctrl(app, 'v');
+            app.current_offset()
+        };
+
+        // Hexadecimal can leave out nibbles, and other text is compared as is
+        assert_eq!(compare(&mut app, "7F 45 4C 46 0? 01"), 0);
+        assert_eq!(app.labels.notification, "The 6 bytes at the cursor match the clipboard");
+        assert_eq!(compare(&mut app, "7F 45 4C 46 01"), 4);
+        assert_eq!(
+            app.labels.notification,
+            "First difference at 0x4: 02 in the file, 01 in the clipboard"
+        );
+        app.goto(1).unwrap();
+        assert_eq!(compare(&mut app, "ELF!"), 4);
+        assert_eq!(compare(&mut app, "?? 01 00"), 4);
+        assert_eq!(
+            app.labels.notification,
+            "The file ends after 2 matching bytes of the 3 in the clipboard"
+        );
+    }
+
+    #[test]
+    fn test_append_bytes() {
+        let (mut app, _) = open(b"abc", 1, &Options::default());
+        press(&mut app, KeyCode::Char('A'), KeyModifiers::ALT | KeyModifiers::SHIFT);
+        type_text(&mut app, "3 FF");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+
+        // The cursor moves to the first appended byte, and appending is undone at once
+        assert_eq!(&app.data.contents[..], b"abc\xFF\xFF\xFF");
+        assert_eq!(app.current_offset(), 3);
+        assert_eq!(app.labels.notification, "Appended 3 bytes");
+        assert!(app.data.dirty);
+        ctrl(&mut app, 'z');
+        assert_eq!(&app.data.contents[..], b"abc");
+    }
 }
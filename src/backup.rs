@@ -0,0 +1,151 @@
+//! Backups of the contents that are written periodically with `--autosave`, so that edits aren't
+//! lost if heh is killed or crashes.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crossbeam::channel::{Receiver, Sender};
+
+/// Returns the path of the backup of the file at `path`, which is next to it with `.heh.bak`
+/// appended to its name.
+pub(crate) fn path_for(path: &Path) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".heh.bak");
+    PathBuf::from(name)
+}
+
+/// Returns whether the file at `path` has a backup that was written after the file was last
+/// modified.
+pub(crate) fn is_newer(path: &Path) -> bool {
+    let modified = |path: &Path| fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(&path_for(path)), modified(path)) {
+        (Some(backup), Some(file)) => backup > file,
+        _ => false,
+    }
+}
+
+/// Writes backups of the contents on a background thread, at most once per interval and only
+/// after the contents changed.
+///
+/// The backup is removed by [`finish`](Self::finish) when heh exits normally. It is deliberately
+/// not removed on drop so that it survives a panic.
+pub(crate) struct Autosave {
+    /// The path of the backup.
+    path: PathBuf,
+    /// The time to wait between backups.
+    interval: Duration,
+    /// When the last backup was handed to the background thread.
+    last: Instant,
+    /// Whether the contents may have changed since the last backup.
+    pending: bool,
+    /// Sends copies of the contents to the background thread to write.
+    tx: Option<Sender<Vec<u8>>>,
+    /// Receives the errors of backups that couldn't be written.
+    errors: Receiver<String>,
+    /// The thread writing the backups.
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Autosave {
+    /// Starts backing up the file at `path` every `interval`.
+    pub(crate) fn new(path: &Path, interval: Duration) -> Self {
+        let path = path_for(path);
+        let (tx, rx) = crossbeam::channel::unbounded::<Vec<u8>>();
+        let (error_tx, errors) = crossbeam::channel::unbounded();
+        let backup = path.clone();
+        let worker = thread::spawn(move || {
+            for contents in &rx {
+                if let Err(e) = fs::write(&backup, contents) {
+                    let _ = error_tx.send(format!("Can't write {}: {e}", backup.display()));
+                }
+            }
+        });
+        Self {
+            path,
+            interval,
+            last: Instant::now(),
+            pending: false,
+            tx: Some(tx),
+            errors,
+            worker: Some(worker),
+        }
+    }
+
+    /// Notes that the contents may have changed and need to be backed up.
+    pub(crate) fn changed(&mut self) {
+        self.pending = true;
+    }
+
+    /// Returns how long until the next backup is due, or None if nothing changed since the last
+    /// one.
+    pub(crate) fn time_left(&self) -> Option<Duration> {
+        self.pending.then(|| self.interval.saturating_sub(self.last.elapsed()))
+    }
+
+    /// Returns whether a backup should be written now.
+    pub(crate) fn is_due(&self) -> bool {
+        self.time_left() == Some(Duration::ZERO)
+    }
+
+    /// Hands a copy of the contents to the background thread to write.
+    pub(crate) fn write(&mut self, contents: &[u8]) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(contents.to_vec());
+        }
+        self.pending = false;
+        self.last = Instant::now();
+    }
+
+    /// Returns the error of a backup that couldn't be written, if there is one.
+    pub(crate) fn error(&self) -> Option<String> {
+        self.errors.try_recv().ok()
+    }
+
+    /// Waits for the backups that are being written and removes the backup.
+    pub(crate) fn finish(mut self) {
+        drop(self.tx.take());
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use super::*;
+
+    #[test]
+    fn test_autosave() {
+        let path = env::temp_dir().join(format!("heh-autosave-test-{}", process::id()));
+        let backup = path_for(&path);
+        assert_eq!(
+            backup.file_name().unwrap(),
+            &*format!("heh-autosave-test-{}.heh.bak", process::id())
+        );
+
+        fs::write(&path, [0, 1, 2]).unwrap();
+        let mut autosave = Autosave::new(&path, Duration::ZERO);
+        assert_eq!(autosave.time_left(), None);
+        autosave.changed();
+        assert!(autosave.is_due());
+        autosave.write(&[3, 4]);
+        assert_eq!(autosave.time_left(), None);
+
+        // Wait for the background thread without removing the backup
+        drop(autosave.tx.take());
+        autosave.worker.take().unwrap().join().unwrap();
+        assert_eq!(fs::read(&backup).unwrap(), [3, 4]);
+        assert!(autosave.error().is_none());
+
+        autosave.finish();
+        assert!(!backup.exists());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -1,10 +1,14 @@
 use std::{
+    cmp,
     error::Error,
-    ops::{Deref, DerefMut},
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    ops::{Deref, DerefMut, Range},
     sync::{
-        atomic::{AtomicBool, AtomicUsize, Ordering},
+        atomic::{AtomicUsize, Ordering},
         Arc,
     },
+    thread::{self, JoinHandle},
 };
 
 use memmap2::{MmapMut, MmapOptions};
@@ -24,18 +28,29 @@ enum EditMessage {
 /// can be locked. This struct also implements deref to much more easily control
 /// the content the rest of the application can see without massively restructuring.
 pub(crate) struct AsyncBuffer {
-    /// The mmap backed by the file that is being edited
-    content_buf: MmapMut,
+    /// The mmap backed by the file that is being edited, or an anonymous mmap once the
+    /// content outgrows the file. It is shared with the [`Snapshot`]s that are still being read,
+    /// and copied before it is edited while they are.
+    content_buf: Arc<MmapMut>,
     /// The length of the content. Used for when elements are deleted
     len: usize,
+    /// The length of the file when it was last read or written, used to tell how much it changed
+    /// on a [`refresh`](Self::refresh)
+    file_len: usize,
     /// A mpsc channel that allows sending messages to a thread that finishes
     /// updating the buffer if it is very large. Makes it much more responsive
     tx: crossbeam::channel::Sender<EditMessage>,
-    /// An atomic that denotes whether the background buffer is actively engaged in work
-    has_work: Arc<AtomicBool>,
+    /// The number of messages that were sent but that the background thread hasn't finished
+    /// processing yet
+    pending: Arc<AtomicUsize>,
     /// An offset shared between the processing thread and the main thread. This is to safely
     /// work on the ultimately same buffer by splitting it into 2 independent slices
     window_end: Arc<AtomicUsize>,
+    /// The thread processing the messages sent through `tx`
+    worker: Option<JoinHandle<()>>,
+    /// Whether an insertion was sent since the last time the background thread was waited on.
+    /// Insertions rely on the window staying in place until they are processed.
+    inserted: bool,
 }
 
 impl Deref for AsyncBuffer {
@@ -48,7 +63,34 @@ impl Deref for AsyncBuffer {
 
 impl DerefMut for AsyncBuffer {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.content_buf[..self.len]
+        let len = self.len;
+        &mut self.buf_mut()[..len]
+    }
+}
+
+impl Drop for AsyncBuffer {
+    fn drop(&mut self) {
+        // The background thread writes into the mmap, so it has to finish before the mmap is
+        // unmapped. Replacing the sender disconnects it like in `reallocate`.
+        self.tx = crossbeam::channel::unbounded().0;
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// The content of an [`AsyncBuffer`] at the time it was taken, which other threads can read
+/// while the buffer is edited.
+pub(crate) struct Snapshot {
+    content_buf: Arc<MmapMut>,
+    len: usize,
+}
+
+impl Deref for Snapshot {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.content_buf[..self.len]
     }
 }
 
@@ -56,30 +98,61 @@ impl AsyncBuffer {
     /// Create 2 copy-on-write memmaps of the same file. Since they are shared,
     /// they edit the same underlying buffer. Store one of the buffers for use
     /// for background processing by [`AsyncBuffer::process_messages`]
+    ///
+    /// # Errors
+    ///
+    /// This errors when the file isn't a regular file, is too large to be addressed on this
+    /// platform, or can't be memory mapped.
     pub fn new(file: &std::fs::File) -> Result<Self, Box<dyn Error>> {
-        let mut content_buf = unsafe { MmapOptions::new().map_copy(file)? };
-        let internal_buf = content_buf.as_mut_ptr();
+        let metadata = file.metadata()?;
+        if !metadata.is_file() {
+            return Err("heh can only open regular files".into());
+        }
+        let len = usize::try_from(metadata.len())
+            .map_err(|_| format!("The file is too large to open ({} bytes)", metadata.len()))?;
 
-        let has_work = Arc::new(AtomicBool::new(false));
+        let content_buf = unsafe { MmapOptions::new().map_copy(file) }
+            .map_err(|e| format!("Can't map the file into memory: {e}"))?;
+        Ok(Self::from_mmap(content_buf, len))
+    }
 
-        // This is ok, because it is the len of a memmap buffer, it is limited
-        // by the size of the addressing space anyways.
-        #[allow(clippy::cast_possible_truncation)]
-        let window_end =
-            Arc::new(AtomicUsize::new(SYNC_BUFF_LEN.min(file.metadata()?.len() as usize)));
+    /// Creates a buffer of the bytes in an anonymous mmap, which isn't backed by any file.
+    ///
+    /// # Errors
+    ///
+    /// This errors when the memory for the mmap can't be allocated.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Box<dyn Error>> {
+        let mut content_buf = MmapMut::map_anon(bytes.len())?;
+        content_buf.copy_from_slice(bytes);
+        Ok(Self::from_mmap(content_buf, bytes.len()))
+    }
+
+    /// Starts the background thread on the first `len` bytes of the mmap.
+    fn from_mmap(mut content_buf: MmapMut, len: usize) -> Self {
+        let internal_buf = content_buf.as_mut_ptr();
+
+        let pending = Arc::new(AtomicUsize::new(0));
+        let window_end = Arc::new(AtomicUsize::new(SYNC_BUFF_LEN.min(len)));
 
         let (tx, rx) = crossbeam::channel::unbounded();
 
-        AsyncBuffer::process_messages(
-            #[allow(clippy::cast_possible_truncation)]
-            (internal_buf, file.metadata()?.len() as usize),
+        let worker = AsyncBuffer::process_messages(
+            (internal_buf, len),
             rx,
-            has_work.clone(),
+            pending.clone(),
             window_end.clone(),
         );
 
-        #[allow(clippy::cast_possible_truncation)]
-        Ok(Self { content_buf, len: file.metadata()?.len() as usize, tx, has_work, window_end })
+        Self {
+            content_buf: Arc::new(content_buf),
+            len,
+            file_len: len,
+            tx,
+            pending,
+            window_end,
+            worker: Some(worker),
+            inserted: false,
+        }
     }
 
     /// Receives messages of type [`EditMessage`], and processes the buffer in the
@@ -88,21 +161,19 @@ impl AsyncBuffer {
     /// Once received, it does the copy to insert / remove where the main thread stopped.
     /// This *vastly* improves snappiness and feel and does not overlap in read / write
     /// with the main thread, thus preventing any UB in writing to the same section of
-    /// the buffer
+    /// the buffer. The thread exits once every sender of the channel is dropped.
     fn process_messages(
         internal_buf: (*mut u8, usize),
         rx: crossbeam::channel::Receiver<EditMessage>,
-        has_work: Arc<AtomicBool>,
+        pending: Arc<AtomicUsize>,
         window_offset: Arc<AtomicUsize>,
-    ) {
+    ) -> JoinHandle<()> {
         let internal_buf =
             unsafe { std::slice::from_raw_parts_mut(internal_buf.0, internal_buf.1) };
         let mut internal_start = window_offset.load(Ordering::SeqCst);
 
-        std::thread::spawn(move || loop {
+        thread::spawn(move || {
             for rcv in &rx {
-                has_work.store(true, Ordering::SeqCst);
-
                 let start = window_offset.load(Ordering::SeqCst);
                 let internal_buf = &mut internal_buf[start..];
 
@@ -135,9 +206,9 @@ impl AsyncBuffer {
                     }
                 }
 
-                has_work.store(rx.is_full(), Ordering::SeqCst);
+                pending.fetch_sub(1, Ordering::SeqCst);
             }
-        });
+        })
     }
 
     /// Returns the length accounting for deletes
@@ -149,40 +220,177 @@ impl AsyncBuffer {
     /// up to the offset of the internal window. After this, it has the background
     /// thread process the rest.
     pub fn remove(&mut self, offset: usize) -> u8 {
+        if self.inserted {
+            self.block();
+            self.inserted = false;
+        }
         let val = self.content_buf[offset];
+        self.unshare();
+        let count = self.window_end.fetch_sub(1, Ordering::SeqCst).saturating_sub(offset + 1);
+        let content_buf = self.buf_mut();
 
         unsafe {
             std::ptr::copy(
-                self.content_buf.as_ptr().add(offset + 1),
-                self.content_buf.as_mut_ptr().add(offset),
-                self.window_end.fetch_sub(1, Ordering::SeqCst) - offset,
+                content_buf.as_ptr().add(offset + 1),
+                content_buf.as_mut_ptr().add(offset),
+                count,
             );
         }
 
-        self.tx.send(EditMessage::Remove).unwrap();
+        self.send(EditMessage::Remove);
         self.len -= 1;
 
         val
     }
 
-    /// At the moment, only used for undoing deletions. With that in mind,
-    /// no need to worry about increasing the size of the buffer. Copies
+    /// Inserts the byte at the offset, growing the buffer first if it is full. Copies
     /// up to the window so a single byte will be cut off at the end. Sends
     /// this byte so the background thread can re-insert it once it is safe.
     pub fn insert(&mut self, offset: usize, byte: u8) {
+        if self.len == self.content_buf.len() {
+            self.grow(1);
+        }
+        self.unshare();
+
         let window_end = self.window_end.load(Ordering::SeqCst);
-        self.tx.send(EditMessage::Add(self.content_buf[window_end - 1])).unwrap();
+        self.send(EditMessage::Add(self.content_buf[window_end - 1]));
+        self.inserted = true;
         self.len += 1;
+        let content_buf = self.buf_mut();
 
         unsafe {
             std::ptr::copy(
-                self.content_buf.as_ptr().add(offset),
-                self.content_buf.as_mut_ptr().add(offset + 1),
+                content_buf.as_ptr().add(offset),
+                content_buf.as_mut_ptr().add(offset + 1),
                 window_end.saturating_sub(offset).saturating_sub(1),
             );
         }
 
-        self.content_buf[offset] = byte;
+        content_buf[offset] = byte;
+    }
+
+    /// Appends the bytes to the end of the content, growing the buffer if they don't fit.
+    pub fn extend(&mut self, bytes: &[u8]) {
+        self.block();
+        if self.len + bytes.len() > self.content_buf.len() {
+            self.grow(bytes.len());
+        }
+        let len = self.len;
+        self.buf_mut()[len..len + bytes.len()].copy_from_slice(bytes);
+        self.len += bytes.len();
+    }
+
+    /// Replaces the bytes in the range with `bytes`, which can be of a different length. Unlike
+    /// [`insert`](Self::insert) and [`remove`](Self::remove), this moves the bytes after the
+    /// range synchronously.
+    pub fn splice(&mut self, range: Range<usize>, bytes: &[u8]) {
+        self.block();
+        let new_len = self.len - range.len() + bytes.len();
+        if new_len > self.content_buf.len() {
+            self.grow(new_len - self.len);
+        }
+        let len = self.len;
+        let content_buf = self.buf_mut();
+        content_buf.copy_within(range.end..len, range.start + bytes.len());
+        content_buf[range.start..range.start + bytes.len()].copy_from_slice(bytes);
+        self.len = new_len;
+
+        let window_end = self.window_end.load(Ordering::SeqCst);
+        self.send(EditMessage::ModifyWindow(window_end.min(new_len)));
+        self.block();
+    }
+
+    /// Shortens the content to the given length. Does nothing if the content is already shorter.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.block();
+        let window_end = self.window_end.load(Ordering::SeqCst);
+        self.send(EditMessage::ModifyWindow(window_end.min(len)));
+        self.block();
+        self.len = len;
+    }
+
+    /// Picks up changes to the length of the file made by other programs since it was opened,
+    /// saved, or last refreshed. Bytes that were added to the file are appended to the content,
+    /// and if the file shrank, the content is shortened by as much (but never emptied). Edits to
+    /// the rest of the content are kept. Returns the previous and current length of the file.
+    ///
+    /// # Errors
+    ///
+    /// This errors when the file can't be read.
+    pub fn refresh(&mut self, file: &File) -> Result<(usize, usize), Box<dyn Error>> {
+        let previous_len = self.file_len;
+        let file_len = usize::try_from(file.metadata()?.len())?;
+        if file_len > previous_len {
+            let mut added = vec![0; file_len - previous_len];
+            let mut file = file;
+            file.seek(SeekFrom::Start(previous_len as u64))?;
+            file.read_exact(&mut added)?;
+            self.extend(&added);
+        } else if file_len < previous_len {
+            self.truncate(cmp::max(self.len.saturating_sub(previous_len - file_len), 1));
+        }
+        self.file_len = file_len;
+        Ok((previous_len, file_len))
+    }
+
+    /// Records that the content was written to the file, which is then as long as the content.
+    pub fn saved(&mut self) {
+        self.file_len = self.len;
+    }
+
+    /// Takes a snapshot of the content, which keeps reading the same bytes however the buffer is
+    /// edited afterwards. The content is only copied if it is edited while the snapshot is alive.
+    pub fn snapshot(&self) -> Snapshot {
+        // The background thread could still be moving bytes that the snapshot covers
+        self.block();
+        Snapshot { content_buf: Arc::clone(&self.content_buf), len: self.len }
+    }
+
+    /// Returns the mmap to edit, copying it first if a snapshot still reads it.
+    fn buf_mut(&mut self) -> &mut MmapMut {
+        self.unshare();
+        Arc::get_mut(&mut self.content_buf).expect("The buffer is still shared!")
+    }
+
+    /// Moves the content into an anonymous mmap of its own if a snapshot still reads it.
+    fn unshare(&mut self) {
+        if Arc::strong_count(&self.content_buf) > 1 {
+            self.reallocate(self.content_buf.len());
+        }
+    }
+
+    /// Moves the content into a larger anonymous mmap so that at least `additional` bytes can be
+    /// added past the size of the original file.
+    fn grow(&mut self, additional: usize) {
+        self.reallocate(cmp::max(self.len * 2, self.len + cmp::max(additional, SYNC_BUFF_LEN)));
+    }
+
+    /// Moves the content into a new anonymous mmap with room for `capacity` bytes. The background
+    /// thread is stopped while this happens and is restarted on the new mmap afterwards.
+    fn reallocate(&mut self, capacity: usize) {
+        // Replacing the sender disconnects the background thread, which finishes the messages
+        // left in the channel before exiting.
+        let (tx, rx) = crossbeam::channel::unbounded();
+        self.tx = tx;
+        if let Some(worker) = self.worker.take() {
+            worker.join().expect("The buffer's background thread panicked!");
+        }
+
+        let mut content_buf =
+            MmapMut::map_anon(capacity).expect("Failed to allocate memory for the buffer!");
+        content_buf[..self.len].copy_from_slice(&self.content_buf[..self.len]);
+        let internal_buf = content_buf.as_mut_ptr();
+        self.content_buf = Arc::new(content_buf);
+
+        self.worker = Some(AsyncBuffer::process_messages(
+            (internal_buf, capacity),
+            rx,
+            self.pending.clone(),
+            self.window_end.clone(),
+        ));
     }
 
     /// Compute whether the window needs to be extended, blocks if so until there is no
@@ -203,16 +411,114 @@ impl AsyncBuffer {
             || window_end.saturating_sub(new_offset) > SYNC_BUFF_LEN * 4 / 3
         {
             self.block();
-            self.tx
-                .send(EditMessage::ModifyWindow((new_offset + SYNC_BUFF_LEN).min(self.len)))
-                .unwrap();
+            self.send(EditMessage::ModifyWindow((new_offset + SYNC_BUFF_LEN).min(self.len)));
         }
     }
 
+    /// Returns the end of the window that is kept loaded for the editor.
+    pub fn window_end(&self) -> usize {
+        self.window_end.load(Ordering::SeqCst)
+    }
+
+    /// Checks if the background thread still has messages to process.
+    pub fn has_pending_work(&self) -> bool {
+        self.pending.load(Ordering::SeqCst) > 0
+    }
+
+    /// Sends a message to the background thread, which is counted as pending until processed.
+    fn send(&self, message: EditMessage) {
+        self.pending.fetch_add(1, Ordering::SeqCst);
+        self.tx.send(message).unwrap();
+    }
+
     /// Wait until the background thread has finished processing messages
     pub fn block(&self) {
-        while self.has_work.load(Ordering::SeqCst) {
+        while self.pending.load(Ordering::SeqCst) > 0 {
             std::thread::sleep(std::time::Duration::from_millis(1));
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, io::Write};
+
+    use super::*;
+
+    #[test]
+    fn test_grow_past_file_length() {
+        let path = env::temp_dir().join(format!("heh-buffer-test-{}", std::process::id()));
+        fs::write(&path, [0, 1, 2, 3]).unwrap();
+        let file = fs::File::open(&path).unwrap();
+        let mut buffer = AsyncBuffer::new(&file).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // Inserting into a full buffer grows it instead of cutting bytes off.
+        buffer.insert(0, 0xAA);
+        buffer.insert(3, 0xBB);
+        buffer.block();
+        assert_eq!(&*buffer, &[0xAA, 0, 1, 0xBB, 2, 3]);
+
+        buffer.remove(0);
+        buffer.block();
+        assert_eq!(&*buffer, &[0, 1, 0xBB, 2, 3]);
+
+        // Appending and then truncating the appended bytes restores the content.
+        buffer.extend(&vec![0xCC; 0x20000]);
+        assert_eq!(buffer.len(), 0x20005);
+        assert_eq!(buffer[0x20004], 0xCC);
+        buffer.truncate(5);
+        assert_eq!(&*buffer, &[0, 1, 0xBB, 2, 3]);
+
+        buffer.splice(1..3, &[0xDD]);
+        assert_eq!(&*buffer, &[0, 0xDD, 2, 3]);
+        buffer.splice(1..2, &[1, 0xBB]);
+        assert_eq!(&*buffer, &[0, 1, 0xBB, 2, 3]);
+
+        // Inserting many bytes at once keeps single byte edits after them working.
+        buffer.splice(1..1, &vec![0xEE; 0x20000]);
+        buffer.compute_new_window(0x20002);
+        buffer.block();
+        buffer.insert(0x20002, 0xFF);
+        buffer.remove(0x20000);
+        buffer.insert(0x20000, 0xEE);
+        buffer.remove(0);
+        buffer.block();
+        assert_eq!(buffer.len(), 0x20005);
+        assert_eq!(&buffer[0x1FFFF..], &[0xEE, 1, 0xFF, 0xBB, 2, 3]);
+        buffer.splice(0..0x1FFFF, &[0]);
+        assert_eq!(&*buffer, &[0, 0xEE, 1, 0xFF, 0xBB, 2, 3]);
+    }
+
+    #[test]
+    fn test_refresh() {
+        let path = env::temp_dir().join(format!("heh-refresh-test-{}", std::process::id()));
+        fs::write(&path, [0, 1, 2, 3]).unwrap();
+        let file = fs::File::open(&path).unwrap();
+        let mut buffer = AsyncBuffer::new(&file).unwrap();
+        buffer[0] = 0xAA;
+
+        // Bytes added by another program are appended, keeping the edit.
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(&[4, 5]).unwrap();
+        assert_eq!(buffer.refresh(&file).unwrap(), (4, 6));
+        assert_eq!(&*buffer, &[0xAA, 1, 2, 3, 4, 5]);
+
+        fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(3).unwrap();
+        assert_eq!(buffer.refresh(&file).unwrap(), (6, 3));
+        assert_eq!(&*buffer, &[0xAA, 1, 2]);
+
+        // A single byte is kept when the file becomes empty.
+        fs::OpenOptions::new().write(true).open(&path).unwrap().set_len(0).unwrap();
+        assert_eq!(buffer.refresh(&file).unwrap(), (3, 0));
+        assert_eq!(&*buffer, &[0xAA]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_non_regular_file() {
+        let file = fs::File::open("/dev/null").unwrap();
+        let error = AsyncBuffer::new(&file).err().unwrap();
+        assert_eq!(error.to_string(), "heh can only open regular files");
+    }
+}
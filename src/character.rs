@@ -2,19 +2,36 @@ use std::fmt::{Debug, Display, Formatter};
 
 use ratatui::style::Color;
 
+use crate::theme::Theme;
+
 pub(crate) const CHARACTER_NULL: char = '0';
 pub(crate) const CHARACTER_WHITESPACE: char = '_';
 pub(crate) const CHARACTER_CONTROL: char = '⍾';
 pub(crate) const CHARACTER_FILL: char = '•';
 pub(crate) const CHARACTER_UNKNOWN: char = '�';
 
-const COLOR_NULL: Color = Color::DarkGray;
-const COLOR_ASCII: Color = Color::Cyan;
-const COLOR_UNICODE: Color = Color::LightCyan;
-const COLOR_WHITESPACE: Color = Color::Green;
-const COLOR_CONTROL: Color = Color::Magenta;
-const COLOR_FILL: Color = Color::LightCyan;
-const COLOR_UNKNOWN: Color = Color::Yellow;
+/// The abbreviated names of the ASCII control characters, by code point.
+const CONTROL_NAMES: [&str; 32] = [
+    "NUL", "SOH", "STX", "ETX", "EOT", "ENQ", "ACK", "BEL", "BS", "HT", "LF", "VT", "FF", "CR",
+    "SO", "SI", "DLE", "DC1", "DC2", "DC3", "DC4", "NAK", "SYN", "ETB", "CAN", "EM", "SUB", "ESC",
+    "FS", "GS", "RS", "US",
+];
+
+/// Describes an ASCII control character in caret notation with its name, like `^[ ESC` for
+/// 0x1B or `^? DEL` for 0x7F, which tells apart the characters that the editor displays as
+/// [`CHARACTER_CONTROL`]. Other characters have no caret notation.
+pub(crate) fn caret_notation(character: char) -> Option<String> {
+    let code_point = u8::try_from(character).ok()?;
+    match code_point {
+        0x7F => Some(String::from("^? DEL")),
+        0..0x20 => Some(format!(
+            "^{} {}",
+            char::from(code_point + 0x40),
+            CONTROL_NAMES[usize::from(code_point)]
+        )),
+        _ => None,
+    }
+}
 
 #[derive(Clone, Debug, PartialEq)]
 pub(crate) enum Type {
@@ -60,6 +77,16 @@ impl From<&char> for Category {
 }
 
 impl Category {
+    /// Checks if the byte is printable ASCII, counting spaces but not other whitespace.
+    pub(crate) fn is_printable(byte: u8) -> bool {
+        let character = char::from(byte);
+        match Category::from(&character) {
+            Category::Ascii => true,
+            Category::Whitespace => character == ' ',
+            _ => false,
+        }
+    }
+
     pub(crate) fn escape(&self, character: char) -> char {
         match self {
             Category::Null => CHARACTER_NULL,
@@ -71,18 +98,6 @@ impl Category {
             Category::Unknown => CHARACTER_UNKNOWN,
         }
     }
-
-    pub(crate) fn color(&self) -> &'static Color {
-        match self {
-            Category::Null => &COLOR_NULL,
-            Category::Ascii => &COLOR_ASCII,
-            Category::Unicode => &COLOR_UNICODE,
-            Category::Whitespace => &COLOR_WHITESPACE,
-            Category::Control => &COLOR_CONTROL,
-            Category::Fill => &COLOR_FILL,
-            Category::Unknown => &COLOR_UNKNOWN,
-        }
-    }
 }
 
 #[derive(Clone, Debug)]
@@ -100,8 +115,12 @@ impl RichChar {
         self.category.escape(self.character)
     }
 
-    pub(crate) fn color(&self) -> &'static Color {
-        self.category.color()
+    pub(crate) fn category(&self) -> &Category {
+        &self.category
+    }
+
+    pub(crate) fn color(&self, theme: &Theme) -> Color {
+        theme.color(&self.category)
     }
 }
 
@@ -134,3 +153,23 @@ impl From<&RichChar> for String {
         rich_char.character.to_string()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_caret_notation() {
+        assert_eq!(caret_notation('\0').as_deref(), Some("^@ NUL"));
+        assert_eq!(caret_notation('\t').as_deref(), Some("^I HT"));
+        assert_eq!(caret_notation('\x1B').as_deref(), Some("^[ ESC"));
+        assert_eq!(caret_notation('\x1F').as_deref(), Some("^_ US"));
+        assert_eq!(caret_notation('\x7F').as_deref(), Some("^? DEL"));
+
+        // Printable characters and control characters past ASCII have none
+        assert_eq!(caret_notation(' '), None);
+        assert_eq!(caret_notation('a'), None);
+        assert_eq!(caret_notation('\u{85}'), None);
+        assert_eq!(caret_notation('ä'), None);
+    }
+}
@@ -0,0 +1,100 @@
+//! Checksum algorithms used to recompute checksums stored in a file.
+
+use std::{fmt, str::FromStr};
+
+/// The checksum algorithms that can be written into the buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Algorithm {
+    Sum8,
+    Crc16,
+    Crc32,
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "sum8" => Ok(Self::Sum8),
+            "crc16" => Ok(Self::Crc16),
+            "crc32" => Ok(Self::Crc32),
+            _ => Err(format!("Unknown algorithm: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sum8 => write!(f, "SUM8"),
+            Self::Crc16 => write!(f, "CRC16"),
+            Self::Crc32 => write!(f, "CRC32"),
+        }
+    }
+}
+
+impl Algorithm {
+    /// The number of bytes the checksum naturally occupies.
+    pub(crate) fn width(self) -> usize {
+        match self {
+            Self::Sum8 => 1,
+            Self::Crc16 => 2,
+            Self::Crc32 => 4,
+        }
+    }
+
+    /// Computes the checksum of the bytes.
+    pub(crate) fn compute(self, bytes: &[u8]) -> u64 {
+        match self {
+            Self::Sum8 => u64::from(bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))),
+            Self::Crc16 => u64::from(crc16(bytes)),
+            Self::Crc32 => u64::from(crc32(bytes)),
+        }
+    }
+}
+
+/// CRC-16/ARC, the variant most commonly referred to as CRC16.
+fn crc16(bytes: &[u8]) -> u16 {
+    bytes.iter().fold(0, |crc, &byte| {
+        (0..8).fold(crc ^ u16::from(byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xA001
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+/// CRC-32/ISO-HDLC, the variant used by zlib, PNG, and many others.
+pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+    !crc32_update(u32::MAX, bytes)
+}
+
+/// Feeds more bytes into a CRC-32 that was started from `u32::MAX`, leaving out the final
+/// inversion so that large inputs can be processed in pieces.
+pub(crate) fn crc32_update(crc: u32, bytes: &[u8]) -> u32 {
+    bytes.iter().fold(crc, |crc, &byte| {
+        (0..8).fold(crc ^ u32::from(byte), |crc, _| {
+            if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_values() {
+        // The standard check input for CRC catalogues.
+        let input = b"123456789";
+        assert_eq!(Algorithm::Sum8.compute(input), 0xDD);
+        assert_eq!(Algorithm::Crc16.compute(input), 0xBB3D);
+        assert_eq!(Algorithm::Crc32.compute(input), 0xCBF4_3926);
+    }
+}
@@ -0,0 +1,63 @@
+//! The clipboard that labels and lines are copied to and that base64 is pasted from and bytes are
+//! compared with. When the
+//! system clipboard can't be used, like over SSH, copies are kept for the rest of the session
+//! instead so that they can still be pasted within heh.
+
+/// The system clipboard, along with the text that was copied last in case it is unavailable.
+pub(crate) struct Clipboard {
+    /// The system clipboard, if one was found.
+    system: Option<arboard::Clipboard>,
+    /// The text that was copied last, which is pasted when the system clipboard can't be read.
+    session: Option<String>,
+}
+
+impl Clipboard {
+    /// Creates a clipboard that uses the system clipboard if there is one.
+    pub(crate) fn new() -> Self {
+        Self { system: arboard::Clipboard::new().ok(), session: None }
+    }
+
+    /// Creates a clipboard that only keeps copies within the session.
+    #[cfg(test)]
+    pub(crate) fn session() -> Self {
+        Self { system: None, session: None }
+    }
+
+    /// Returns whether the system clipboard was found.
+    pub(crate) fn has_system(&self) -> bool {
+        self.system.is_some()
+    }
+
+    /// Copies the text, returning whether it was copied to the system clipboard. It can be pasted
+    /// within heh either way.
+    pub(crate) fn set_text(&mut self, text: String) -> bool {
+        let copied =
+            self.system.as_mut().is_some_and(|system| system.set_text(text.as_str()).is_ok());
+        self.session = Some(text);
+        copied
+    }
+
+    /// Returns the text on the system clipboard, or the text that was copied last if the system
+    /// clipboard can't be read. This is None when the system clipboard holds something other
+    /// than text, like an image.
+    pub(crate) fn get_text(&mut self) -> Option<String> {
+        match self.system.as_mut().map(arboard::Clipboard::get_text) {
+            Some(Ok(text)) => Some(text),
+            Some(Err(arboard::Error::ContentNotAvailable)) => None,
+            Some(Err(_)) | None => self.session.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Clipboard;
+
+    #[test]
+    fn test_session_clipboard() {
+        let mut clipboard = Clipboard::session();
+        assert_eq!(clipboard.get_text(), None);
+        assert!(!clipboard.set_text(String::from("AAEC")));
+        assert_eq!(clipboard.get_text().as_deref(), Some("AAEC"));
+    }
+}
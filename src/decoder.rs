@@ -1,6 +1,10 @@
 //! Decoder utilities.
 
-use std::str::from_utf8;
+use std::{
+    fmt::{Display, Formatter},
+    ops::Range,
+    str::from_utf8,
+};
 
 use crate::character::{Category, RichChar, Type, CHARACTER_FILL, CHARACTER_UNKNOWN};
 
@@ -15,7 +19,7 @@ impl<'a> From<&'a [u8]> for LossyASCIIDecoder<'a> {
     }
 }
 
-impl<'a> Iterator for LossyASCIIDecoder<'a> {
+impl Iterator for LossyASCIIDecoder<'_> {
     type Item = (char, Type);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -33,6 +37,28 @@ impl<'a> Iterator for LossyASCIIDecoder<'a> {
     }
 }
 
+struct LossyLatin1Decoder<'a> {
+    bytes: &'a [u8],
+    cursor: usize,
+}
+
+impl<'a> From<&'a [u8]> for LossyLatin1Decoder<'a> {
+    fn from(bytes: &'a [u8]) -> Self {
+        Self { bytes, cursor: 0 }
+    }
+}
+
+impl Iterator for LossyLatin1Decoder<'_> {
+    type Item = (char, Type);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Every byte of ISO-8859-1 is the Unicode code point of the same value.
+        let byte = *self.bytes.get(self.cursor)?;
+        self.cursor += 1;
+        Some((char::from(byte), Type::Ascii))
+    }
+}
+
 struct LossyUTF8Decoder<'a> {
     bytes: &'a [u8],
     cursor: usize,
@@ -44,7 +70,7 @@ impl<'a> From<&'a [u8]> for LossyUTF8Decoder<'a> {
     }
 }
 
-impl<'a> Iterator for LossyUTF8Decoder<'a> {
+impl Iterator for LossyUTF8Decoder<'_> {
     type Item = (char, Type);
 
     fn next(&mut self) -> Option<Self::Item> {
@@ -81,10 +107,48 @@ impl<'a> Iterator for LossyUTF8Decoder<'a> {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+/// Finds the bytes of the multibyte UTF-8 character that the byte at `offset` is a part of, or
+/// None if the byte isn't part of a valid multibyte character.
+pub(crate) fn utf8_char_range(bytes: &[u8], offset: usize) -> Option<Range<usize>> {
+    let start = (offset.saturating_sub(3)..=offset).rev().find(|&i| bytes[i] & 0xC0 != 0x80)?;
+    let len = match bytes[start] {
+        0xC0..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF7 => 4,
+        _ => return None,
+    };
+    let end = start + len;
+    (end > offset && end <= bytes.len() && from_utf8(&bytes[start..end]).is_ok())
+        .then_some(start..end)
+}
+
+#[derive(Copy, Clone, Debug, Default)]
 pub enum Encoding {
+    #[default]
     Ascii,
     Utf8,
+    Latin1,
+}
+
+impl Encoding {
+    /// Returns the encoding that follows this one, wrapping around after the last.
+    pub(crate) fn next(self) -> Self {
+        match self {
+            Encoding::Ascii => Encoding::Utf8,
+            Encoding::Utf8 => Encoding::Latin1,
+            Encoding::Latin1 => Encoding::Ascii,
+        }
+    }
+}
+
+impl Display for Encoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Encoding::Ascii => "ASCII",
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Latin1 => "Latin-1",
+        })
+    }
 }
 
 pub(crate) struct ByteAlignedDecoder<D: Iterator<Item = (char, Type)>> {
@@ -99,6 +163,7 @@ impl<'a> ByteAlignedDecoder<BoxedDecoder<'a>> {
         match encoding {
             Encoding::Ascii => Box::new(LossyASCIIDecoder::from(bytes)) as BoxedDecoder,
             Encoding::Utf8 => Box::new(LossyUTF8Decoder::from(bytes)) as BoxedDecoder,
+            Encoding::Latin1 => Box::new(LossyLatin1Decoder::from(bytes)) as BoxedDecoder,
         }
         .into()
     }
@@ -130,6 +195,8 @@ impl<D: Iterator<Item = (char, Type)>> Iterator for ByteAlignedDecoder<D> {
 }
 
 #[cfg(test)]
+// The escaped characters are converted to chars, which they already are
+#[allow(clippy::useless_conversion)]
 mod tests {
     use super::*;
 
@@ -158,4 +225,41 @@ mod tests {
             "text, controls _ __, space _, unicode ä•h à• la 💩•••, null 0, invalid ���"
         );
     }
+
+    #[test]
+    fn test_decoder_latin1() {
+        let bytes = b"caf\xE9 \xA0nbsp \x85 control \xFF";
+        let decoder = ByteAlignedDecoder::new(bytes, Encoding::Latin1);
+        let characters: Vec<_> = decoder.collect();
+
+        assert_eq!(bytes.len(), characters.len());
+        assert_eq!(
+            characters.iter().map(RichChar::escape).collect::<String>(),
+            "café _nbsp _ control ÿ"
+        );
+    }
+
+    #[test]
+    fn test_next_encoding() {
+        // Cycling goes through every encoding before coming back to the first
+        let cycle: Vec<String> = std::iter::successors(Some(Encoding::Ascii), |e| Some(e.next()))
+            .take(4)
+            .map(|encoding| encoding.to_string())
+            .collect();
+        assert_eq!(cycle, ["ASCII", "UTF-8", "Latin-1", "ASCII"]);
+    }
+
+    #[test]
+    fn test_utf8_char_range() {
+        let find = |needle: &[u8]| TEST_BYTES.windows(needle.len()).position(|w| w == needle);
+
+        let a_umlaut = find(b"\xC3\xA4").unwrap();
+        assert_eq!(utf8_char_range(TEST_BYTES, a_umlaut), Some(a_umlaut..a_umlaut + 2));
+        assert_eq!(utf8_char_range(TEST_BYTES, a_umlaut + 1), Some(a_umlaut..a_umlaut + 2));
+        let pile = find(b"\xF0\x9F\x92\xA9").unwrap();
+        assert_eq!(utf8_char_range(TEST_BYTES, pile + 3), Some(pile..pile + 4));
+
+        assert_eq!(utf8_char_range(TEST_BYTES, 0), None);
+        assert_eq!(utf8_char_range(TEST_BYTES, find(b"\xC0").unwrap()), None);
+    }
 }
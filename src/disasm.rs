@@ -0,0 +1,110 @@
+//! Disassembles the instruction at the cursor for the `Disasm` label. This is only built with the
+//! `disasm` feature, since it links to the capstone C library.
+
+use capstone::{
+    arch::{self, BuildsCapstone, BuildsCapstoneExtraMode},
+    Capstone,
+};
+
+/// The title of the label that shows the instruction at the cursor. It is only displayed when an
+/// architecture is set.
+pub(crate) const DISASM_TITLE: &str = "Disasm";
+
+/// The length of the longest instruction of any architecture, which is how many bytes at the
+/// cursor are kept to be disassembled.
+pub(crate) const MAX_INSTRUCTION_LEN: usize = 15;
+
+/// The instruction sets that can be disassembled.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Arch {
+    /// 32 bit x86.
+    X86,
+    /// 64 bit x86.
+    X86_64,
+    /// 32 bit ARM, without Thumb.
+    Arm,
+    /// 64 bit ARM.
+    Arm64,
+    /// 32 bit RISC-V, with compressed instructions.
+    RiscV32,
+    /// 64 bit RISC-V, with compressed instructions.
+    RiscV64,
+}
+
+/// Disassembles single instructions of an architecture.
+pub(crate) struct Disassembler {
+    capstone: Capstone,
+}
+
+impl Disassembler {
+    /// Creates a disassembler for the architecture.
+    ///
+    /// # Errors
+    ///
+    /// This errors if capstone wasn't built with support for the architecture.
+    pub(crate) fn new(arch: Arch) -> Result<Self, String> {
+        let capstone = match arch {
+            Arch::X86 => Capstone::new().x86().mode(arch::x86::ArchMode::Mode32).build(),
+            Arch::X86_64 => Capstone::new().x86().mode(arch::x86::ArchMode::Mode64).build(),
+            Arch::Arm => Capstone::new().arm().mode(arch::arm::ArchMode::Arm).build(),
+            Arch::Arm64 => Capstone::new().arm64().mode(arch::arm64::ArchMode::Arm).build(),
+            Arch::RiscV32 => Capstone::new()
+                .riscv()
+                .mode(arch::riscv::ArchMode::RiscV32)
+                .extra_mode([arch::riscv::ArchExtraMode::RiscVC].into_iter())
+                .build(),
+            Arch::RiscV64 => Capstone::new()
+                .riscv()
+                .mode(arch::riscv::ArchMode::RiscV64)
+                .extra_mode([arch::riscv::ArchExtraMode::RiscVC].into_iter())
+                .build(),
+        };
+        let capstone = capstone.map_err(|e| format!("Can't disassemble {arch:?}: {e}"))?;
+        Ok(Self { capstone })
+    }
+
+    /// Describes the instruction at the start of the bytes, which is at the address, as its
+    /// mnemonic, operands, and length. This is "(n/a)" if the bytes don't start with a valid
+    /// instruction, like when it would continue past the end of the file.
+    pub(crate) fn describe(&self, bytes: &[u8], address: usize) -> String {
+        let Ok(instructions) = self.capstone.disasm_count(bytes, address as u64, 1) else {
+            return String::from("(n/a)");
+        };
+        let Some(instruction) = instructions.iter().next() else {
+            return String::from("(n/a)");
+        };
+        let mnemonic = instruction.mnemonic().unwrap_or_default();
+        let len = instruction.bytes().len();
+        match instruction.op_str().filter(|operands| !operands.is_empty()) {
+            Some(operands) => format!("{mnemonic} {operands} ({len})"),
+            None => format!("{mnemonic} ({len})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let x86 = Disassembler::new(Arch::X86_64).unwrap();
+        assert_eq!(x86.describe(&[0x90, 0xCC], 0), "nop (1)");
+        assert_eq!(x86.describe(&[0xB8, 0x01, 0, 0, 0], 0), "mov eax, 1 (5)");
+        // The mov needs 4 more bytes, and relative jumps are shown at their address
+        assert_eq!(x86.describe(&[0xB8, 0x01], 0), "(n/a)");
+        assert_eq!(x86.describe(&[0xEB, 0x00], 0x1000), "jmp 0x1002 (2)");
+        assert_eq!(x86.describe(&[], 0), "(n/a)");
+
+        let arm = Disassembler::new(Arch::Arm).unwrap();
+        assert_eq!(arm.describe(&[0x01, 0x00, 0xA0, 0xE3], 0), "mov r0, #1 (4)");
+        assert_eq!(arm.describe(&[0xFF; 4], 0), "(n/a)");
+
+        // Compressed RISC-V instructions are 2 bytes long
+        let riscv = Disassembler::new(Arch::RiscV64).unwrap();
+        assert_eq!(riscv.describe(&[0x13, 0x05, 0x10, 0x00], 0), "addi a0, zero, 1 (4)");
+        assert_eq!(riscv.describe(&[0x05, 0x45], 0), "c.li a0, 1 (2)");
+        let riscv = Disassembler::new(Arch::RiscV32).unwrap();
+        assert_eq!(riscv.describe(&[0x6F, 0x00, 0x80, 0x00], 0x1000), "j 8 (4)");
+    }
+}
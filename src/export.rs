@@ -0,0 +1,227 @@
+//! Formats file contents so that they can be shared outside of heh.
+
+use std::{
+    fmt::Write as _,
+    fs,
+    io::{self, Write},
+    path::Path,
+    process::{Command, ExitStatus, Stdio},
+};
+
+use base64::{prelude::BASE64_STANDARD, Engine};
+
+use crate::character::Category;
+
+/// The formats that contents can be printed in with `--export`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Like `hexdump -C`.
+    Hexdump,
+    /// A C array of the bytes, like `xxd -i`.
+    CArray,
+    /// Base64 with padding, wrapped at 76 characters.
+    Base64,
+    /// A Python bytes literal, like `b'ab\\x00'`.
+    Python,
+}
+
+/// Writes the bytes in the format.
+///
+/// # Errors
+///
+/// This errors when writing to `out` fails.
+pub fn write(out: &mut impl Write, contents: &[u8], format: Format) -> io::Result<()> {
+    match format {
+        Format::Hexdump => write_hexdump(out, contents, 0),
+        Format::CArray => write_c_array(out, contents),
+        Format::Base64 => write_base64(out, contents),
+        Format::Python => writeln!(out, "{}", python_bytes(contents)),
+    }
+}
+
+/// Writes the bytes formatted like `hexdump -C`, where `start` is the offset of the first byte.
+///
+/// Every line has the offset of its first byte, up to 16 bytes split into two groups of 8, and
+/// an ASCII gutter where bytes that aren't printable are shown as dots. The output is finished
+/// with the offset after the last byte.
+pub(crate) fn write_hexdump(out: &mut impl Write, contents: &[u8], start: usize) -> io::Result<()> {
+    for (row, chunk) in contents.chunks(16).enumerate() {
+        write!(out, "{:08x}  ", start + row * 16)?;
+        for col in 0..16 {
+            match chunk.get(col) {
+                Some(byte) => write!(out, "{byte:02x} ")?,
+                None => write!(out, "   ")?,
+            }
+            if col == 7 {
+                write!(out, " ")?;
+            }
+        }
+        let gutter: String = chunk.iter().map(|&byte| printable(byte)).collect();
+        writeln!(out, " |{gutter}|")?;
+    }
+    writeln!(out, "{:08x}", start + contents.len())
+}
+
+/// Writes the bytes as a C array named `data` with its length in `data_len`, 12 bytes per line.
+fn write_c_array(out: &mut impl Write, contents: &[u8]) -> io::Result<()> {
+    writeln!(out, "unsigned char data[] = {{")?;
+    for (row, chunk) in contents.chunks(12).enumerate() {
+        let bytes: Vec<String> = chunk.iter().map(|byte| format!("0x{byte:02x}")).collect();
+        let last = (row + 1) * 12 >= contents.len();
+        writeln!(out, "  {}{}", bytes.join(", "), if last { "" } else { "," })?;
+    }
+    writeln!(out, "}};")?;
+    writeln!(out, "unsigned int data_len = {};", contents.len())
+}
+
+/// Writes the bytes as base64 with padding, in lines of 76 characters.
+fn write_base64(out: &mut impl Write, contents: &[u8]) -> io::Result<()> {
+    // Every 3 bytes are 4 characters, so 57 bytes fill a line
+    for line in contents.chunks(57) {
+        writeln!(out, "{}", BASE64_STANDARD.encode(line))?;
+    }
+    Ok(())
+}
+
+/// Formats the bytes as a Python bytes literal, escaped like Python's `repr`: printable ASCII is
+/// kept except for backslashes and quotes, tabs and line breaks are escaped by name, and every
+/// other byte is escaped as `\\xHH`.
+pub(crate) fn python_bytes(contents: &[u8]) -> String {
+    let mut literal = String::from("b'");
+    for &byte in contents {
+        match byte {
+            b'\\' => literal.push_str("\\\\"),
+            b'\'' => literal.push_str("\\'"),
+            b'\t' => literal.push_str("\\t"),
+            b'\n' => literal.push_str("\\n"),
+            b'\r' => literal.push_str("\\r"),
+            byte if Category::is_printable(byte) => literal.push(char::from(byte)),
+            byte => {
+                let _ = write!(literal, "\\x{byte:02x}");
+            }
+        }
+    }
+    literal.push('\'');
+    literal
+}
+
+/// Decodes base64 with padding, ignoring whitespace like the line breaks of
+/// [`Format::Base64`].
+pub(crate) fn decode_base64(text: &str) -> Result<Vec<u8>, String> {
+    let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    BASE64_STANDARD.decode(text).map_err(|e| format!("Invalid base64: {e}"))
+}
+
+/// Formats the bytes like `hexdump -C` into a string. See [`write_hexdump`].
+pub(crate) fn export_hexdump(contents: &[u8], start: usize) -> String {
+    let mut hexdump = Vec::new();
+    write_hexdump(&mut hexdump, contents, start).expect("Writing to a Vec can't fail");
+    String::from_utf8(hexdump).expect("The hexdump is ASCII")
+}
+
+/// Runs the program with the arguments and pipes a hexdump of the bytes into it (see
+/// [`write_hexdump`]), waiting for it to exit.
+pub(crate) fn pipe_hexdump<'a>(
+    program: &str,
+    args: impl IntoIterator<Item = &'a str>,
+    contents: &[u8],
+    start: usize,
+) -> io::Result<ExitStatus> {
+    let mut child = Command::new(program).args(args).stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user quitting a pager before reading everything closes the pipe, which isn't an
+        // error worth reporting.
+        let _ = write_hexdump(&mut stdin, contents, start);
+    }
+    child.wait()
+}
+
+/// Writes `head` to the `first` path and `tail` to the `second`, replacing any existing files.
+pub(crate) fn split(head: &[u8], tail: &[u8], first: &Path, second: &Path) -> io::Result<()> {
+    fs::write(first, head)?;
+    fs::write(second, tail)
+}
+
+/// Checks if both paths lead to the same existing file.
+pub(crate) fn is_same_file(path: &Path, other: &Path) -> bool {
+    match (fs::canonicalize(path), fs::canonicalize(other)) {
+        (Ok(path), Ok(other)) => path == other,
+        _ => false,
+    }
+}
+
+/// Returns the byte as a character if it is printable ASCII, or a dot otherwise.
+fn printable(byte: u8) -> char {
+    if Category::is_printable(byte) {
+        char::from(byte)
+    } else {
+        '.'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write() {
+        let export = |contents: &[u8], format| {
+            let mut out = Vec::new();
+            write(&mut out, contents, format).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+        assert_eq!(
+            export(&[0, 0xFF, 0x10], Format::CArray),
+            "unsigned char data[] = {\n  0x00, 0xff, 0x10\n};\nunsigned int data_len = 3;\n"
+        );
+        assert_eq!(export(b"M", Format::Base64), "TQ==\n");
+        assert_eq!(export(b"Ma", Format::Base64), "TWE=\n");
+        assert_eq!(export(b"Man", Format::Base64), "TWFu\n");
+        assert_eq!(export(&[0; 60], Format::Base64), format!("{}\nAAAA\n", "A".repeat(76)));
+        assert_eq!(export(b"a\x00", Format::Python), "b'a\\x00'\n");
+    }
+
+    #[test]
+    fn test_python_bytes() {
+        assert_eq!(python_bytes(b""), "b''");
+        assert_eq!(python_bytes(b"hi there"), "b'hi there'");
+        assert_eq!(python_bytes(b"a\\b'c\"d"), "b'a\\\\b\\'c\"d'");
+        assert_eq!(python_bytes(b"\t\n\r"), "b'\\t\\n\\r'");
+        assert_eq!(python_bytes(&[0, 0x1B, 0x7F, 0x80, 0xFF]), "b'\\x00\\x1b\\x7f\\x80\\xff'");
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64("TWFu\nTQ==\n").unwrap(), b"ManM");
+        assert_eq!(decode_base64(" TWE= ").unwrap(), b"Ma");
+        assert!(decode_base64("TWE").unwrap_err().starts_with("Invalid base64: "));
+    }
+
+    #[test]
+    fn test_export_hexdump() {
+        let contents: Vec<u8> = (0x41..0x41 + 20).collect();
+        assert_eq!(
+            export_hexdump(&contents, 0x10),
+            "00000010  41 42 43 44 45 46 47 48  49 4a 4b 4c 4d 4e 4f 50  |ABCDEFGHIJKLMNOP|\n\
+             00000020  51 52 53 54                                       |QRST|\n\
+             00000024\n"
+        );
+        assert_eq!(
+            export_hexdump(b"a\x00\n", 0),
+            "00000000  61 00 0a                                          |a..|\n00000003\n"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_pipe_hexdump() {
+        let path = std::env::temp_dir().join(format!("heh-pager-test-{}", std::process::id()));
+        let of = format!("of={}", path.display());
+        let status = pipe_hexdump("dd", [of.as_str(), "status=none"], b"hi", 0x20).unwrap();
+        assert!(status.success());
+        assert_eq!(fs::read_to_string(&path).unwrap(), export_hexdump(b"hi", 0x20));
+        fs::remove_file(&path).unwrap();
+
+        assert!(pipe_hexdump("heh-missing-pager", [], b"hi", 0).is_err());
+    }
+}
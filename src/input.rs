@@ -3,241 +3,968 @@
 //! This is where mouse actions are programmed. It's also a wrapper around calls to a dynamic
 //! [`KeyHandler`](crate::windows::KeyHandler), which handles keyboared input.
 
-use std::{
-    cmp,
-    error::Error,
-    io::{Seek, Write},
-};
+use std::{cmp, env, error::Error, fs, io, path::Path};
 
-use ratatui::crossterm::event::{
-    KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+use ratatui::{
+    crossterm::event::{
+        Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    layout::Rect,
 };
 
 use crate::{
-    app::{Action, Application, Nibble},
-    label::LABEL_TITLES,
+    app::{Action, Application, ColorMode, Data, EditMode, Nibble, PendingEdit},
+    export::{decode_base64, export_hexdump, pipe_hexdump, python_bytes},
+    keymap::Command,
+    label::Endianness,
+    pattern::Pattern,
+    save,
+    screen::{self, overview_offset, Handler as ScreenHandler},
     windows::{
-        adjust_offset,
+        adjust_offset, centered_start_address, editor, restore_backup,
         search::{perform_search, SearchDirection},
         PopupOutput, Window,
     },
 };
 
-/// Wrapper function that calls the corresponding [`KeyHandler`](crate::windows::KeyHandler) methods of
+/// The most bytes that truncating the file can remove, since they are kept in memory to undo it.
+const MAX_TRUNCATE_LEN: usize = 64 * 1024 * 1024;
+
+/// The most keys that a macro can record.
+const MAX_MACRO_LEN: usize = 1024;
+
+/// Runs the command bound to the key in [the application's keymap](Application::keymap), or
+/// otherwise calls the corresponding [`KeyHandler`](crate::windows::KeyHandler) methods of
 /// [the application's `key_handler`.](Application::key_handler)
 pub(crate) fn handle_key_input(
     app: &mut Application,
     key: KeyEvent,
 ) -> Result<bool, Box<dyn Error>> {
+    if app.recording {
+        record_key(app, key);
+    }
     match key.code {
-        // Arrow key input
-        KeyCode::Left => {
-            app.key_handler.left(&mut app.data, &mut app.display, &mut app.labels);
-        }
-        KeyCode::Right => {
-            app.key_handler.right(&mut app.data, &mut app.display, &mut app.labels);
-        }
-        KeyCode::Up => {
-            app.key_handler.up(&mut app.data, &mut app.display, &mut app.labels);
-        }
-        KeyCode::Down => {
-            app.key_handler.down(&mut app.data, &mut app.display, &mut app.labels);
-        }
-
-        // Cursor shortcuts
-        KeyCode::Home => {
-            app.key_handler.home(&mut app.data, &mut app.display, &mut app.labels);
-        }
-        KeyCode::End => {
-            app.key_handler.end(&mut app.data, &mut app.display, &mut app.labels);
-        }
-        KeyCode::PageUp => {
-            app.key_handler.page_up(&mut app.data, &mut app.display, &mut app.labels);
-        }
-        KeyCode::PageDown => {
-            app.key_handler.page_down(&mut app.data, &mut app.display, &mut app.labels);
-        }
-
-        // Removals
-        KeyCode::Backspace => {
-            app.key_handler.backspace(&mut app.data, &mut app.display, &mut app.labels);
-        }
-        KeyCode::Delete => {
-            app.key_handler.delete(&mut app.data, &mut app.display, &mut app.labels);
-        }
         KeyCode::Esc => {
+            app.data.pending_edit = None;
+            app.data.count = None;
+            app.data.escape = None;
+            app.data.discard_pending_nibble();
             app.focus_editor();
+            return Ok(true);
         }
-
         KeyCode::Enter => {
             if app.key_handler.is_focusing(Window::UnsavedChanges)
                 && app.key_handler.get_user_input() == PopupOutput::Boolean(true)
             {
                 return Ok(false);
             }
+            if app.key_handler.is_focusing(Window::ProtectedEdit) {
+                let confirmed = app.key_handler.get_user_input() == PopupOutput::Boolean(true);
+                let pending_edit = app.data.pending_edit.take();
+                app.focus_editor();
+                if let Some(edit) = pending_edit.filter(|_| confirmed) {
+                    replay_edit(app, edit);
+                }
+                return Ok(true);
+            }
             app.key_handler.enter(&mut app.data, &mut app.display, &mut app.labels);
-            app.focus_editor();
+            // The replace window stays open so that matches can be replaced one after another,
+            // and the hash window stays open to show the hash.
+            if !app.key_handler.is_focusing(Window::Replace)
+                && !app.key_handler.is_focusing(Window::Hash)
+            {
+                app.focus_editor();
+            }
+            return Ok(true);
         }
+        _ => {}
+    }
 
-        KeyCode::Char(char) => {
-            // Because CNTRLq is the signal to quit, we propogate the message
-            // if this handling method returns false
-            return handle_character_input(app, char, key.modifiers);
+    let typed = match key.code {
+        KeyCode::Char(c)
+            if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+        {
+            Some(c)
+        }
+        _ => None,
+    };
+    if let Some(c) = typed {
+        if let Some(keep_running) = answer_confirmation(app, c) {
+            return Ok(keep_running);
+        }
+    }
+    // Shift and the arrow keys select, unless the keymap binds them to something else
+    if key.modifiers.contains(KeyModifiers::SHIFT)
+        && app.keymap.bound(key).is_none()
+        && (app.key_handler.is_focusing(Window::Hex) || app.key_handler.is_focusing(Window::Ascii))
+    {
+        if let Some(offset) = selection_target(app, key.code) {
+            extend_selection(app, offset);
+            return Ok(true);
+        }
+    }
+    if let Some(count) = app.data.count.take() {
+        if let Some(digit) = typed.and_then(|c| c.to_digit(10)) {
+            let count = count.saturating_mul(10).saturating_add(digit as usize);
+            app.data.count = Some(count);
+            app.labels.notification = format!("Count: {count}");
+            return Ok(true);
+        }
+        if app.keymap.command(key) == Some(Command::PlayMacro) {
+            return play_macro(app, count);
+        }
+        if let Some(command) = app.keymap.command(key).filter(|command| command.is_motion()) {
+            // Moving more times than there are bytes can't move any further
+            for _ in 0..count.clamp(1, app.data.contents.len()) {
+                run_command(app, command)?;
+            }
+            return Ok(true);
+        }
+    }
+    if let Some(command) = app.keymap.command(key) {
+        // Keys that type a character are only shortcuts in the hex editor.
+        if typed.is_none() || app.key_handler.is_focusing(Window::Hex) {
+            return run_command(app, command);
         }
-        _ => {}
+    }
+    if let Some(c) = typed {
+        app.key_handler.char(&mut app.data, &mut app.display, &mut app.labels, c);
+        focus_pending_edit(app);
     }
     Ok(true)
 }
 
-/// Handles a character key press. While used predominantly to edit a file, it also checks for
-/// any shortcut commands being used.
-pub(crate) fn handle_character_input(
-    app: &mut Application,
-    char: char,
-    modifiers: KeyModifiers,
-) -> Result<bool, Box<dyn Error>> {
-    if modifiers == KeyModifiers::CONTROL {
-        return handle_control_options(char, app);
-    } else if modifiers == KeyModifiers::ALT {
-        match char {
-            '=' => {
-                app.labels.update_stream_length(cmp::min(app.labels.get_stream_length() + 1, 64));
-                app.labels.update_streams(&app.data.contents[app.data.offset..]);
-            }
-            '-' => {
-                app.labels.update_stream_length(cmp::max(
-                    app.labels.get_stream_length().saturating_sub(1),
-                    0,
-                ));
-                app.labels.update_streams(&app.data.contents[app.data.offset..]);
+/// Answers a confirmation popup with y or n if one is focused, returning whether the application
+/// keeps running.
+fn answer_confirmation(app: &mut Application, c: char) -> Option<bool> {
+    if app.key_handler.is_focusing(Window::UnsavedChanges) {
+        match c.to_ascii_lowercase() {
+            'y' => return Some(false),
+            'n' => app.focus_editor(),
+            _ => {}
+        }
+        return Some(true);
+    }
+    if app.key_handler.is_focusing(Window::RestoreBackup) {
+        match c.to_ascii_lowercase() {
+            'y' => {
+                app.focus_editor();
+                restore_backup::restore(&mut app.data, &mut app.labels);
             }
+            'n' => app.focus_editor(),
             _ => {}
         }
-    } else if modifiers | KeyModifiers::NONE | KeyModifiers::SHIFT
-        == KeyModifiers::NONE | KeyModifiers::SHIFT
-    {
-        let is_hex = app.key_handler.is_focusing(Window::Hex);
-
-        match char {
-            'q' if is_hex => {
-                if !app.key_handler.is_focusing(Window::UnsavedChanges) {
-                    if !app.data.dirty {
-                        return Ok(false);
-                    }
-                    app.set_focused_window(Window::UnsavedChanges);
+        return Some(true);
+    }
+    if app.key_handler.is_focusing(Window::ProtectedEdit) {
+        match c.to_ascii_lowercase() {
+            'y' => {
+                if let Some(edit) = app.data.pending_edit.take() {
+                    app.focus_editor();
+                    replay_edit(app, edit);
                 }
             }
-            'h' if is_hex => {
-                app.key_handler.left(&mut app.data, &mut app.display, &mut app.labels);
-            }
-            'l' if is_hex => {
-                app.key_handler.right(&mut app.data, &mut app.display, &mut app.labels);
-            }
-            'k' if is_hex => {
-                app.key_handler.up(&mut app.data, &mut app.display, &mut app.labels);
-            }
-            'j' if is_hex => {
-                app.key_handler.down(&mut app.data, &mut app.display, &mut app.labels);
-            }
-            '^' if is_hex => {
-                app.key_handler.home(&mut app.data, &mut app.display, &mut app.labels);
-            }
-            '$' if is_hex => {
-                app.key_handler.end(&mut app.data, &mut app.display, &mut app.labels);
-            }
-            '/' if is_hex => {
-                app.set_focused_window(Window::Search);
-            }
-            _ => {
-                app.key_handler.char(&mut app.data, &mut app.display, &mut app.labels, char);
+            'n' => {
+                app.data.pending_edit = None;
+                app.focus_editor();
             }
+            _ => {}
         }
+        return Some(true);
     }
-    Ok(true)
+    None
 }
 
-fn handle_control_options(char: char, app: &mut Application) -> Result<bool, Box<dyn Error>> {
-    match char {
-        'j' => {
-            if app.key_handler.is_focusing(Window::JumpToByte) {
-                app.focus_editor();
-            } else {
-                app.set_focused_window(Window::JumpToByte);
-            }
+/// Runs a command, returning false if the application should quit.
+// Every command has an arm here, which keeps growing with them
+#[allow(clippy::too_many_lines)]
+fn run_command(app: &mut Application, command: Command) -> Result<bool, Box<dyn Error>> {
+    let (data, display, labels) = (&mut app.data, &mut app.display, &mut app.labels);
+    match command {
+        Command::Left => app.key_handler.left(data, display, labels),
+        Command::Right => app.key_handler.right(data, display, labels),
+        Command::Up => app.key_handler.up(data, display, labels),
+        Command::Down => app.key_handler.down(data, display, labels),
+        Command::Home => app.key_handler.home(data, display, labels),
+        Command::End => app.key_handler.end(data, display, labels),
+        Command::FileStart => app.key_handler.file_start(data, display, labels),
+        Command::FileEnd => app.key_handler.file_end(data, display, labels),
+        Command::PageUp => app.key_handler.page_up(data, display, labels),
+        Command::PageDown => app.key_handler.page_down(data, display, labels),
+        Command::Backspace => {
+            app.key_handler.backspace(data, display, labels);
+            focus_pending_edit(app);
         }
-        'f' => {
-            if app.key_handler.is_focusing(Window::Search) {
-                app.focus_editor();
-            } else {
-                app.set_focused_window(Window::Search);
-            }
+        Command::Delete => {
+            app.key_handler.delete(data, display, labels);
+            focus_pending_edit(app);
+        }
+        Command::ToggleEditMode => {
+            data.edit_mode.toggle();
+            labels.update_edit_mode(data.edit_mode);
         }
-        'q' => {
+        Command::Quit => {
             if !app.key_handler.is_focusing(Window::UnsavedChanges) {
-                if !app.data.dirty {
+                if !app.all_files().any(|data| data.dirty) || !app.confirm_quit {
                     return Ok(false);
                 }
                 app.set_focused_window(Window::UnsavedChanges);
             }
         }
-        's' => {
-            app.data.contents.block();
-            app.data.file.rewind()?;
-            app.data.file.write_all(&app.data.contents)?;
-            app.data.file.set_len(app.data.contents.len() as u64)?;
+        Command::Save => save(app),
+        Command::SaveQuit => {
+            save_all(app);
+            // Saving fails without a file or when it can't be written, which keeps the changes
+            // unsaved
+            if !app.all_files().any(|data| data.dirty) {
+                return Ok(false);
+            }
+        }
+        Command::Undo => undo(app),
+        Command::Search => app.toggle_window(Window::Search),
+        Command::NextMatch => perform_search(data, display, labels, &SearchDirection::Forward),
+        Command::PreviousMatch => {
+            perform_search(data, display, labels, &SearchDirection::Backward);
+        }
+        Command::JumpToByte => app.toggle_window(Window::JumpToByte),
+        Command::AlignCursor => app.toggle_window(Window::AlignCursor),
+        Command::CompareClipboard => compare_clipboard(app),
+        Command::Strings => app.toggle_window(Window::Strings),
+        Command::Info => app.toggle_window(Window::Info),
+        Command::Refresh => refresh_file_length(app),
+        Command::CycleEncoding => {
+            data.encoding = data.encoding.next();
+            labels.encoding = data.encoding;
+            labels.update_all(&data.contents[data.offset..]);
+            labels.notification = format!("Encoding: {}", data.encoding);
+        }
+        Command::SwitchEndianness => {
+            labels.switch_endianness();
+            labels.update_all(&data.contents[data.offset..]);
+            labels.notification = labels.endianness.to_string();
+        }
+        Command::IncreaseStreamLength => {
+            labels.update_stream_length(cmp::min(
+                labels.get_stream_length() + 1,
+                labels.max_stream_length,
+            ));
+            labels.update_streams(&data.contents[data.offset..]);
+        }
+        Command::DecreaseStreamLength => {
+            labels.update_stream_length(labels.get_stream_length().saturating_sub(1));
+            labels.update_streams(&data.contents[data.offset..]);
+        }
+        Command::Append => app.toggle_window(Window::Append),
+        Command::Checksum => app.toggle_window(Window::Checksum),
+        Command::Split => app.toggle_window(Window::Split),
+        Command::ViewInPager => view_in_pager(app)?,
+        Command::ExportHexdump => export_hexdump_file(app),
+        Command::Replace => app.toggle_window(Window::Replace),
+        Command::Fill => app.toggle_window(Window::Fill),
+        Command::WriteValue => app.toggle_window(Window::WriteValue),
+        Command::InsertBytes => app.toggle_window(Window::InsertBytes),
+        Command::AppendBytes => app.toggle_window(Window::AppendBytes),
+        Command::ToggleBookmark => {
+            let offset = data.offset;
+            labels.notification = if data.toggle_bookmark(offset) {
+                format!("Bookmarked {:#X}", data.address(offset))
+            } else {
+                String::from("Removed bookmark")
+            };
+        }
+        Command::NextBookmark => jump_to_next_bookmark(app),
+        Command::NextChange => jump_to_change(app, &SearchDirection::Forward),
+        Command::PreviousChange => jump_to_change(app, &SearchDirection::Backward),
+        Command::NextZero => jump_to_zero(app, true),
+        Command::NextNonZero => jump_to_zero(app, false),
+        Command::Symbols => app.toggle_window(Window::Symbols),
+        Command::Hash => app.toggle_window(Window::Hash),
+        Command::CopyPatchLine => copy_patch_line(app),
+        Command::PasteBase64 => paste_base64(app),
+        Command::CopyOffset => copy_offset(app),
+        Command::CopyPython => copy_python(app),
+        Command::FollowPointer => follow_pointer(app),
+        Command::PointerBack => pointer_back(app),
+        Command::TogglePin => toggle_pin(app),
+        Command::Notifications => app.toggle_window(Window::Notifications),
+        Command::Histogram => app.toggle_window(Window::Histogram),
+        Command::Truncate => truncate_at_cursor(app),
+        Command::ToggleRuler => toggle_ruler(app),
+        Command::ToggleLabels => toggle_labels(app),
+        Command::RecordMacro => toggle_recording(app),
+        Command::PlayMacro => return play_macro(app, 1),
+        Command::NextFile => app.next_file(),
+        Command::ToggleHeatMap => {
+            data.color_mode.toggle();
+            labels.notification = match data.color_mode {
+                ColorMode::Character => String::from("Coloring bytes by character"),
+                ColorMode::HeatMap => String::from("Coloring bytes by value"),
+            };
+        }
+        Command::NextBoundary | Command::PreviousBoundary => {
+            let alignment = data.alignment.unwrap_or(display.comp_layouts.bytes_per_line);
+            jump_to_boundary(app, alignment, command == Command::NextBoundary);
+        }
+        Command::NextPage | Command::PreviousPage => {
+            let page_size = labels.page_size;
+            jump_to_boundary(app, page_size, command == Command::NextPage);
+        }
+        Command::FillRandom => fill_random(app),
+        Command::CycleAddressRadix => {
+            data.address_radix.cycle();
+            labels.update_offset(data.address(data.offset), data.address_radix);
+            labels.notification = format!("Addresses: {}", data.address_radix);
+        }
+        Command::ToggleProtection => toggle_protection(app),
+        Command::Count => {
+            data.count = Some(0);
+            labels.notification = String::from("Count: ");
+        }
+        Command::CycleStep => {
+            data.step = if data.step >= 8 { 1 } else { data.step * 2 };
+            labels.notification = format!("Left and right move by {} bytes", data.step);
+        }
+    }
+    Ok(true)
+}
 
-            app.data.dirty = false;
+/// Focuses the protected edit confirmation if the editor intercepted an edit to a protected byte.
+fn focus_pending_edit(app: &mut Application) {
+    if app.data.pending_edit.is_some() {
+        app.set_focused_window(Window::ProtectedEdit);
+    }
+}
 
-            app.labels.notification = String::from("Saved!");
+/// Performs an edit that was intercepted by a protected range after the user confirmed it.
+fn replay_edit(app: &mut Application, edit: PendingEdit) {
+    app.data.edit_confirmed = true;
+    match edit {
+        PendingEdit::Char(c) => {
+            app.key_handler.char(&mut app.data, &mut app.display, &mut app.labels, c);
         }
-        'e' => {
-            app.labels.switch_endianness();
-            app.labels.update_all(&app.data.contents[app.data.offset..]);
+        PendingEdit::Backspace => {
+            app.key_handler.backspace(&mut app.data, &mut app.display, &mut app.labels);
+        }
+        PendingEdit::Delete => {
+            app.key_handler.delete(&mut app.data, &mut app.display, &mut app.labels);
+        }
+        PendingEdit::Byte(byte) => {
+            editor::type_byte(&mut app.data, &mut app.display, &mut app.labels, byte);
+        }
+    }
+    app.data.edit_confirmed = false;
+}
+
+/// Pipes a hexdump of the selection, or the whole file if nothing is selected, into `$PAGER`
+/// (or `less` if it is not set). The terminal UI is suspended until the pager exits.
+fn view_in_pager(app: &mut Application) -> Result<(), Box<dyn Error>> {
+    if app.display.terminal.is_none() {
+        app.labels.notification = String::from("There is no terminal to suspend!");
+        return Ok(());
+    }
+    let pager = env::var("PAGER").unwrap_or_else(|_| String::from("less"));
+    let mut args = pager.split_whitespace();
+    let Some(program) = args.next() else {
+        app.labels.notification = String::from("PAGER is empty!");
+        return Ok(());
+    };
+
+    app.data.contents.block();
+    let range = app.data.selection().unwrap_or(0..app.data.contents.len());
 
-            app.labels.notification = app.labels.endianness.to_string();
+    app.display.teardown()?;
+    let result = pipe_hexdump(program, args, &app.data.contents[range.clone()], range.start);
+    ScreenHandler::setup()?;
+    if let Some(terminal) = &mut app.display.terminal {
+        terminal.clear()?;
+    }
+
+    if let Err(e) = result {
+        app.labels.notification = format!("Can't open {program}: {e}");
+    }
+    Ok(())
+}
+
+/// Writes a hexdump of the selection, or the whole file if nothing is selected, next to the file
+/// as `<file>.hexdump`.
+fn export_hexdump_file(app: &mut Application) {
+    let Some(path) = &app.data.path else {
+        app.labels.notification = String::from("The file has no path to write next to!");
+        return;
+    };
+    let mut hexdump_path = path.clone().into_os_string();
+    hexdump_path.push(".hexdump");
+
+    app.data.contents.block();
+    let range = app.data.selection().unwrap_or(0..app.data.contents.len());
+    let hexdump = export_hexdump(&app.data.contents[range.clone()], range.start);
+    app.labels.notification = match fs::write(&hexdump_path, hexdump) {
+        Ok(()) => format!("Wrote {}", Path::new(&hexdump_path).display()),
+        Err(e) => format!("Can't write the hexdump: {e}"),
+    };
+}
+
+/// Shows or hides the indices of the columns above the editors, which takes a row from them.
+fn toggle_ruler(app: &mut Application) {
+    app.display.ruler = !app.display.ruler;
+    app.display.comp_layouts =
+        app.display.layout(app.display.terminal_size, app.key_handler.as_ref());
+    adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
+}
+
+/// Shows or hides the labels below the editors, which take their rows while they are hidden.
+fn toggle_labels(app: &mut Application) {
+    app.display.hide_labels = !app.display.hide_labels;
+    app.display.comp_layouts =
+        app.display.layout(app.display.terminal_size, app.key_handler.as_ref());
+    adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
+}
+
+/// Starts recording a new macro, or stops recording it.
+fn toggle_recording(app: &mut Application) {
+    app.recording = !app.recording;
+    app.labels.notification = if app.recording {
+        app.macro_keys.clear();
+        String::from("Recording a macro...")
+    } else {
+        format!("Recorded a macro of {} keys", app.macro_keys.len())
+    };
+}
+
+/// Adds the key to the macro that is being recorded, unless it records or plays macros. Recording
+/// stops once the macro has [`MAX_MACRO_LEN`] keys.
+fn record_key(app: &mut Application, key: KeyEvent) {
+    if matches!(app.keymap.command(key), Some(Command::RecordMacro | Command::PlayMacro)) {
+        return;
+    }
+    if app.macro_keys.len() >= MAX_MACRO_LEN {
+        app.recording = false;
+        app.labels.notification = format!("Macros can't be longer than {MAX_MACRO_LEN} keys");
+        return;
+    }
+    app.macro_keys.push(key);
+}
+
+/// Plays the keys of the macro `times` times, returning false if they quit the application.
+///
+/// Playing stops early when a key that moves the cursor leaves it on the first or last byte, which
+/// it can't move past, so that a macro that edits its way through the file can be played a large
+/// count of times.
+fn play_macro(app: &mut Application, times: usize) -> Result<bool, Box<dyn Error>> {
+    if app.recording {
+        app.labels.notification = String::from("Can't play the macro while recording it");
+        return Ok(true);
+    }
+    if app.macro_keys.is_empty() {
+        app.labels.notification = String::from("No macro was recorded (F5 to record one)");
+        return Ok(true);
+    }
+    let keys = app.macro_keys.clone();
+    // Bounded like motions, so that a mistyped count can't keep heh busy
+    for _ in 0..times.clamp(1, app.data.contents.len()) {
+        for &key in &keys {
+            let offset = app.data.offset;
+            if !app.handle_input(&Event::Key(key))? {
+                return Ok(false);
+            }
+            let motion = app.keymap.command(key).is_some_and(Command::is_motion);
+            let at_edge = offset == 0 || offset == app.data.contents.len() - 1;
+            if motion && at_edge && offset == app.data.offset {
+                app.labels.notification = String::from("Stopped the macro at the edge of the file");
+                return Ok(true);
+            }
+        }
+    }
+    if app.labels.notification.is_empty() {
+        app.labels.notification = format!("Played the macro of {} keys", keys.len());
+    }
+    Ok(true)
+}
+
+/// Removes the bytes from the cursor to the end of the file as a single action. The removed bytes
+/// are kept to undo it, so at most [`MAX_TRUNCATE_LEN`] bytes can be removed at once.
+fn truncate_at_cursor(app: &mut Application) {
+    let data = &mut app.data;
+    let range = data.offset..data.contents.len();
+    if range.start == 0 {
+        app.labels.notification = String::from("Can't delete the whole file");
+        return;
+    } else if range.len() > MAX_TRUNCATE_LEN {
+        app.labels.notification =
+            format!("Can't truncate more than {MAX_TRUNCATE_LEN} bytes, since it can't be undone");
+        return;
+    } else if range.clone().any(|offset| data.is_protected(offset)) {
+        app.labels.notification = String::from("Can't delete protected bytes");
+        return;
+    }
+    data.contents.block();
+    data.replace_bytes(range.clone(), &[]);
+    data.dirty = true;
+    data.last_drag = None;
+    data.drag_nibble = None;
+    data.offset = range.start - 1;
+    data.nibble = Nibble::Beginning;
+    app.labels.update_all(&data.contents[data.offset..]);
+    adjust_offset(data, &mut app.display, &mut app.labels);
+    app.labels.notification = format!("Truncated {} bytes", range.len());
+}
+
+/// Extends or shortens the buffer to follow changes to the length of the file on disk, keeping
+/// the cursor and selection inside the content.
+fn refresh_file_length(app: &mut Application) {
+    let Some(file) = &app.data.file else {
+        app.labels.notification = String::from("There is no file to refresh from!");
+        return;
+    };
+    let (previous_len, file_len) = match app.data.contents.refresh(file) {
+        Ok(lengths) => lengths,
+        Err(e) => {
+            app.labels.notification = format!("Can't refresh the file: {e}");
+            return;
         }
-        'd' => {
-            app.key_handler.page_down(&mut app.data, &mut app.display, &mut app.labels);
+    };
+    let last_offset = app.data.contents.len() - 1;
+    app.data.offset = cmp::min(app.data.offset, last_offset);
+    app.data.last_drag = app.data.last_drag.map(|drag| cmp::min(drag, last_offset));
+    if file_len < previous_len {
+        // Edits past the new end can no longer be undone.
+        app.data.actions.clear();
+    }
+    if !app.data.search_term.is_empty() {
+        app.data.reindex_search().ok();
+    }
+    app.labels.update_all(&app.data.contents[app.data.offset..]);
+    adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
+    app.labels.notification = match file_len.cmp(&previous_len) {
+        cmp::Ordering::Greater => format!("File grew by {} bytes", file_len - previous_len),
+        cmp::Ordering::Less => format!("File shrank by {} bytes", previous_len - file_len),
+        cmp::Ordering::Equal => String::from("File length unchanged"),
+    };
+}
+
+/// Writes the contents to the file. A file that can't be written keeps its changes unsaved, with
+/// the error in the notification.
+fn save(app: &mut Application) {
+    app.labels.notification = match write_file(&mut app.data) {
+        Ok(true) => String::from("Saved!"),
+        Ok(false) => String::from("There is no file to save to!"),
+        Err(e) => format!("Can't save the file: {e}"),
+    };
+}
+
+/// Writes the contents of every open file that has unsaved changes to it.
+fn save_all(app: &mut Application) {
+    let mut notification = String::from("Saved!");
+    for data in app.all_files_mut().filter(|data| data.dirty) {
+        match write_file(data) {
+            Ok(true) => {}
+            Ok(false) => notification = String::from("There is no file to save to!"),
+            Err(e) => notification = format!("Can't save {}: {e}", data.file_name()),
         }
-        'u' => {
-            app.key_handler.page_up(&mut app.data, &mut app.display, &mut app.labels);
+    }
+    app.labels.notification = notification;
+}
+
+/// Writes the contents to their file, returning false if there is no file to write to. The file is
+/// replaced through a temporary file when its path is known, so that it can't be left half
+/// written.
+fn write_file(data: &mut Data) -> io::Result<bool> {
+    let Some(file) = &mut data.file else {
+        return Ok(false);
+    };
+    data.contents.block();
+    match &data.path {
+        Some(path) => *file = save::replace(path, &data.contents)?,
+        None => save::overwrite(file, &data.contents)?,
+    }
+    data.contents.saved();
+    data.dirty = false;
+    Ok(true)
+}
+
+/// Reverts the most recent action.
+fn undo(app: &mut Application) {
+    let Some(action) = app.data.actions.pop() else {
+        return;
+    };
+    app.data.search_actions = None;
+    match action {
+        Action::CharacterInput(offset, byte, nibble) => {
+            app.data.offset = offset;
+            if let Some(nibble) = nibble {
+                app.data.nibble = nibble;
+            }
+            app.data.contents[offset] = byte;
         }
-        'n' => {
-            perform_search(
-                &mut app.data,
-                &mut app.display,
-                &mut app.labels,
-                &SearchDirection::Forward,
-            );
+        Action::Delete(offset, byte) => {
+            app.data.contents.insert(offset, byte);
+            app.data.shift_marks(&(offset..offset), 1);
+            app.data.offset = offset;
         }
-        'p' => {
-            perform_search(
-                &mut app.data,
-                &mut app.display,
-                &mut app.labels,
-                &SearchDirection::Backward,
-            );
+        Action::Overwrite(offset, bytes) => {
+            app.data.contents[offset..offset + bytes.len()].copy_from_slice(&bytes);
+            app.data.offset = offset;
         }
-        'z' => {
-            if let Some(action) = app.data.actions.pop() {
-                match action {
-                    Action::CharacterInput(offset, byte, nibble) => {
-                        app.data.offset = offset;
-                        if let Some(nibble) = nibble {
-                            app.data.nibble = nibble;
-                        }
-                        app.data.contents[offset] = byte;
-                    }
-                    Action::Delete(offset, byte) => {
-                        app.data.contents.insert(offset, byte);
-                        app.data.offset = offset;
-                    }
-                }
+        Action::OverwriteMany(overwritten) => {
+            for (offset, bytes) in &overwritten {
+                app.data.contents[*offset..*offset + bytes.len()].copy_from_slice(bytes);
+            }
+            if let Some(&(offset, _)) = overwritten.first() {
+                app.data.offset = offset;
             }
         }
-        _ => {}
+        Action::Append(len) => {
+            app.data.shift_marks(&(len..app.data.contents.len()), 0);
+            app.data.contents.truncate(len);
+            app.data.offset = cmp::min(app.data.offset, len - 1);
+        }
+        Action::Replace(offset, bytes, len) => {
+            app.data.contents.splice(offset..offset + len, &bytes);
+            app.data.shift_marks(&(offset..offset + len), bytes.len());
+            app.data.offset = offset;
+        }
+        Action::Insert(offset) => {
+            app.data.contents.remove(offset);
+            app.data.shift_marks(&(offset..offset + 1), 0);
+            app.data.offset = cmp::min(offset, app.data.contents.len() - 1);
+        }
+    }
+}
+
+/// Moves the cursor to the bookmark after it.
+fn jump_to_next_bookmark(app: &mut Application) {
+    if let Some(bookmark) = app.data.next_bookmark(app.data.offset) {
+        move_cursor(app, bookmark);
+    } else {
+        app.labels.notification = String::from("No bookmarks");
+    }
+}
+
+/// Moves the cursor to the nearest byte in the direction that differs from the byte at the
+/// cursor.
+fn jump_to_change(app: &mut Application, direction: &SearchDirection) {
+    let current = app.data.contents[app.data.offset];
+    if let Some(offset) = app.data.find_byte(direction, |byte| byte != current) {
+        move_cursor(app, offset);
+    } else {
+        app.labels.notification = String::from("No change found");
+    }
+}
+
+/// Moves the cursor to the next byte that is or isn't zero, wrapping around to the start.
+fn jump_to_zero(app: &mut Application, zero: bool) {
+    if let Some(offset) = app.data.find_byte_wrapping(|byte| (byte == 0) == zero) {
+        move_cursor(app, offset);
+    } else if zero {
+        app.labels.notification = String::from("No zero byte found");
+    } else {
+        app.labels.notification = String::from("No non-zero byte found");
+    }
+}
+
+/// Jumps to the address held by the pointer at the cursor, which is read in the endianness of the
+/// labels, and remembers where it was followed from.
+fn follow_pointer(app: &mut Application) {
+    let (offset, size) = (app.data.offset, app.data.pointer_size);
+    if offset + size > app.data.contents.len() {
+        app.labels.notification = format!("A pointer needs {size} bytes after the cursor");
+        return;
+    }
+    let bytes = &app.data.contents[offset..offset + size];
+    let mut word = [0; 8];
+    let pointer = match app.labels.endianness {
+        Endianness::LittleEndian => {
+            word[..size].copy_from_slice(bytes);
+            u64::from_le_bytes(word)
+        }
+        Endianness::BigEndian => {
+            word[8 - size..].copy_from_slice(bytes);
+            u64::from_be_bytes(word)
+        }
+    };
+    let target = usize::try_from(pointer)
+        .ok()
+        .and_then(|address| address.checked_sub(app.data.base))
+        .filter(|&target| target < app.data.contents.len());
+    if let Some(target) = target {
+        app.data.pointer_history.push(offset);
+        move_cursor(app, target);
+    } else {
+        app.labels.notification = format!("Pointer {pointer:#X} is outside of the file");
     }
-    Ok(true)
+}
+
+/// Goes back to where the last pointer was followed from.
+fn pointer_back(app: &mut Application) {
+    if let Some(offset) = app.data.pointer_history.pop() {
+        // The file can have been shortened since
+        move_cursor(app, offset.min(app.data.contents.len() - 1));
+    } else {
+        app.labels.notification = String::from("No pointer to go back from");
+    }
+}
+
+/// Pins the address of the cursor as the anchor of the `Δ from pin` label, or unpins it if the
+/// cursor is already on it.
+fn toggle_pin(app: &mut Application) {
+    let data = &mut app.data;
+    let address = data.address(data.offset);
+    if app.labels.pin == Some(address) {
+        app.labels.pin = None;
+        app.labels.notification = String::from("Unpinned");
+    } else {
+        app.labels.pin = Some(address);
+        app.labels.notification = format!("Pinned {}", data.address_radix.format_offset(address));
+    }
+    app.labels.update_offset(address, data.address_radix);
+}
+
+/// Moves the cursor in the editors to the next or previous address that is a multiple of the
+/// alignment, like the start of the next record or page.
+fn jump_to_boundary(app: &mut Application, alignment: usize, forward: bool) {
+    if !app.key_handler.is_focusing(Window::Hex) && !app.key_handler.is_focusing(Window::Ascii) {
+        return;
+    }
+    let (offset, last_offset) = (app.data.offset, app.data.contents.len() - 1);
+    let past_boundary = app.data.address(offset) % alignment;
+    let target = if forward {
+        cmp::min(offset + (alignment - past_boundary), last_offset)
+    } else if past_boundary == 0 {
+        offset.saturating_sub(alignment)
+    } else {
+        offset.saturating_sub(past_boundary)
+    };
+    app.data.last_drag = None;
+    app.data.drag_nibble = None;
+    move_cursor(app, target);
+}
+
+/// Moves the cursor to the start of the byte at the offset and scrolls to it.
+fn move_cursor(app: &mut Application, offset: usize) {
+    app.data.discard_pending_nibble();
+    app.data.offset = offset;
+    app.data.nibble = Nibble::Beginning;
+    app.labels.update_all(&app.data.contents[app.data.offset..]);
+    adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
+}
+
+/// Returns the offset that shift and an arrow key move the cursor to, or None for other keys.
+fn selection_target(app: &Application, code: KeyCode) -> Option<usize> {
+    let (offset, last_offset) = (app.data.offset, app.data.contents.len() - 1);
+    let bytes_per_line = app.display.comp_layouts.bytes_per_line;
+    match code {
+        KeyCode::Left => Some(offset.saturating_sub(app.data.step)),
+        KeyCode::Right => Some(cmp::min(offset + app.data.step, last_offset)),
+        KeyCode::Up => Some(offset.checked_sub(bytes_per_line).unwrap_or(offset)),
+        KeyCode::Down if offset + bytes_per_line <= last_offset => Some(offset + bytes_per_line),
+        KeyCode::Down => Some(offset),
+        _ => None,
+    }
+}
+
+/// Moves the cursor while keeping the selection anchored where it started, or at the cursor if
+/// nothing is selected yet.
+fn extend_selection(app: &mut Application, offset: usize) {
+    app.data.discard_pending_nibble();
+    let anchor = app.data.last_drag.unwrap_or(app.data.offset);
+    app.data.last_drag = Some(anchor);
+    app.data.offset = offset;
+    // Whole bytes are selected at both ends, rather than the nibbles the cursor was on
+    (app.data.drag_nibble, app.data.nibble) = if offset >= anchor {
+        (Some(Nibble::Beginning), Nibble::End)
+    } else {
+        (Some(Nibble::End), Nibble::Beginning)
+    };
+    app.labels.update_all(&app.data.contents[offset..]);
+    adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
+}
+
+/// Moves the cursor to the part of the file that the clicked row of the overview bar stands for,
+/// and scrolls it to the middle of the screen.
+fn jump_to_overview_row(app: &mut Application, row: u16) {
+    let overview = app.display.comp_layouts.overview;
+    let len = app.data.contents.len();
+    let offset = overview_offset(row - overview.y, overview.height, len).min(len - 1);
+    app.data.last_drag = None;
+    app.data.drag_nibble = None;
+    app.data.start_address = centered_start_address(
+        offset,
+        len,
+        app.display.comp_layouts.bytes_per_line,
+        app.display.comp_layouts.lines_per_screen,
+    );
+    move_cursor(app, offset);
+}
+
+/// Protects the selection, or the byte at the cursor, or removes its protection.
+fn toggle_protection(app: &mut Application) {
+    let data = &mut app.data;
+    let range = data.selection().unwrap_or(data.offset..data.offset + 1);
+    let radix = data.address_radix;
+    let start = radix.format_offset(data.address(range.start));
+    let end = radix.format_offset(data.address(range.end - 1));
+    app.labels.notification = if data.toggle_protection(range) {
+        format!("Protected {start}-{end}")
+    } else {
+        String::from("Unprotected selection")
+    };
+}
+
+/// Copies the byte at the cursor as a patch line, showing the value it had before it was edited.
+/// The line starts with the address of the byte in the radix of the address column.
+fn copy_patch_line(app: &mut Application) {
+    let offset = app.data.offset;
+    let address = app.data.address_radix.format_offset(app.data.address(offset));
+    let current = app.data.contents[offset];
+    let patch_line = match app.data.original_byte(offset) {
+        Some(original) if original != current => {
+            format!("{address}: {original:#04X} -> {current:#04X}")
+        }
+        _ => format!("{address}: {current:#04X}"),
+    };
+    // Without the system clipboard, the line is shown so that it can be copied from the screen
+    if app.data.clipboard.set_text(patch_line.clone()) {
+        app.labels.notification = String::from("Patch line copied!");
+    } else {
+        app.labels.notification = patch_line;
+    }
+}
+
+/// Copies the selection, or the whole file if nothing is selected, as a Python bytes literal.
+fn copy_python(app: &mut Application) {
+    app.data.contents.block();
+    let range = app.data.selection().unwrap_or(0..app.data.contents.len());
+    let literal = python_bytes(&app.data.contents[range.clone()]);
+    app.labels.notification = if app.data.clipboard.set_text(literal) {
+        format!("Copied {} bytes as a Python literal!", range.len())
+    } else {
+        format!("Copied {} bytes as a Python literal within heh", range.len())
+    };
+}
+
+/// Copies the address of the cursor in the radix of the address column.
+fn copy_offset(app: &mut Application) {
+    let offset = app.data.address_radix.format_offset(app.data.address(app.data.offset));
+    app.labels.notification = if app.data.clipboard.set_text(offset.clone()) {
+        format!("Offset {offset} copied!")
+    } else {
+        format!("Offset {offset} copied within heh")
+    };
+}
+
+/// Decodes base64 from the clipboard and inserts the bytes at the cursor, or overwrites the bytes
+/// from the cursor on, depending on the edit mode. Overwriting past the end grows the file.
+fn paste_base64(app: &mut Application) {
+    let bytes = match app.data.clipboard.get_text().map(|text| decode_base64(&text)) {
+        Some(Ok(bytes)) => bytes,
+        Some(Err(e)) => {
+            app.labels.notification = e;
+            return;
+        }
+        None => {
+            app.labels.notification = String::from("The clipboard has no text");
+            return;
+        }
+    };
+    let offset = app.data.offset;
+    let end = match app.data.edit_mode {
+        EditMode::Insert => offset,
+        EditMode::Overwrite => cmp::min(offset + bytes.len(), app.data.contents.len()),
+    };
+    if bytes.is_empty() {
+        app.labels.notification = String::from("The clipboard has no bytes");
+    } else if (offset..cmp::max(end, offset + 1)).any(|offset| app.data.is_protected(offset)) {
+        app.labels.notification = String::from("Can't paste over protected bytes");
+    } else {
+        app.data.contents.block();
+        app.data.replace_bytes(offset..end, &bytes);
+        app.data.dirty = true;
+        app.labels.update_all(&app.data.contents[offset..]);
+        app.labels.notification = format!("Pasted {} bytes", bytes.len());
+    }
+}
+
+/// Compares the bytes from the cursor with the clipboard and moves the cursor to the first one
+/// that differs. The clipboard is parsed as hexadecimal if it is made of hexadecimal digits, like
+/// `DE AD ?F` where `?` matches any nibble, and is compared as text otherwise.
+fn compare_clipboard(app: &mut Application) {
+    let Some(text) = app.data.clipboard.get_text() else {
+        app.labels.notification = String::from("The clipboard has no text");
+        return;
+    };
+    let pattern = Pattern::from_hex(&text).unwrap_or_else(|_| Pattern::exact(text.as_bytes()));
+    if pattern.len() == 0 {
+        app.labels.notification = String::from("The clipboard has no bytes");
+        return;
+    }
+    app.data.contents.block();
+    let offset = app.data.offset;
+    let end = cmp::min(offset.saturating_add(pattern.len()), app.data.contents.len());
+    let Some(index) = pattern.first_mismatch(&app.data.contents[offset..end]) else {
+        app.labels.notification = if end - offset == pattern.len() {
+            format!("The {} bytes at the cursor match the clipboard", pattern.len())
+        } else {
+            format!(
+                "The file ends after {} matching bytes of the {} in the clipboard",
+                end - offset,
+                pattern.len()
+            )
+        };
+        return;
+    };
+    let differing = offset + index;
+    app.data.last_drag = None;
+    app.data.drag_nibble = None;
+    move_cursor(app, differing);
+    let data = &app.data;
+    app.labels.notification = format!(
+        "First difference at {}: {:02X} in the file, {} in the clipboard",
+        data.address_radix.format_offset(data.address(differing)),
+        data.contents[differing],
+        pattern.hex_byte(index)
+    );
+}
+
+/// Fills the selection, or the byte at the cursor, with random bytes.
+fn fill_random(app: &mut Application) {
+    #[cfg(feature = "random")]
+    {
+        let range = app.data.selection().unwrap_or(app.data.offset..app.data.offset + 1);
+        if range.clone().any(|offset| app.data.is_protected(offset)) {
+            app.labels.notification = String::from("Can't fill protected bytes");
+        } else {
+            app.labels.notification = format!("Filled {} random bytes", range.len());
+            app.data.fill_random(range);
+            app.labels.update_all(&app.data.contents[app.data.offset..]);
+        }
+    }
+    #[cfg(not(feature = "random"))]
+    {
+        app.labels.notification = String::from("heh was built without random fills");
+    }
+}
+
+/// Moves the cursor while dragging. The selection stays anchored where the drag began, unless ALT
+/// is held, which re-anchors it at the cursor so that the selection starts over from there.
+fn drag_cursor(
+    app: &mut Application,
+    cursor_pos: usize,
+    nibble: Option<Nibble>,
+    modifiers: KeyModifiers,
+) {
+    app.data.discard_pending_nibble();
+    if app.data.last_drag.is_none() || modifiers.contains(KeyModifiers::ALT) {
+        app.data.last_drag = Some(app.data.offset);
+        if nibble.is_some() {
+            app.data.drag_nibble = Some(app.data.nibble);
+        }
+    }
+    app.data.offset = cursor_pos;
+    if let Some(nibble) = nibble {
+        app.data.nibble = nibble;
+    }
+    app.labels.update_all(&app.data.contents[app.data.offset..]);
+    adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
 }
 
 /// Handles the mouse input, which consists of things like scrolling and focusing components
@@ -252,6 +979,8 @@ pub(crate) fn handle_mouse_input(app: &mut Application, mouse: MouseEvent) {
                 Window::Ascii => {
                     if let Some((cursor_pos, _)) = handle_editor_click(Window::Ascii, app, mouse) {
                         app.data.offset = cursor_pos;
+                        app.labels.update_all(&app.data.contents[app.data.offset..]);
+                        adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
                     }
                 }
                 Window::Hex => {
@@ -259,49 +988,28 @@ pub(crate) fn handle_mouse_input(app: &mut Application, mouse: MouseEvent) {
                     {
                         app.data.offset = cursor_pos;
                         app.data.nibble = nibble.expect("Clicking on Hex should return a nibble!");
+                        app.labels.update_all(&app.data.contents[app.data.offset..]);
+                        adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
                     }
                 }
-                Window::Label(_)
-                | Window::Unhandled
-                | Window::JumpToByte
-                | Window::Search
-                | Window::UnsavedChanges => {}
+                Window::Overview => jump_to_overview_row(app, mouse.row),
+                _ => {}
             }
         }
-        MouseEventKind::Drag(MouseButton::Left) => {
-            if app.data.drag_enabled {
-                match app.data.last_click {
-                    Window::Ascii => {
-                        if let Some((cursor_pos, _)) = handle_editor_drag(Window::Ascii, app, mouse)
-                        {
-                            if app.data.last_drag.is_none() {
-                                app.data.last_drag = Some(app.data.offset);
-                            }
-                            app.data.offset = cursor_pos;
-                            app.labels.update_all(&app.data.contents[app.data.offset..]);
-                            adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
-                        }
+        MouseEventKind::Drag(MouseButton::Left) if app.data.drag_enabled => {
+            match app.data.last_click {
+                Window::Ascii => {
+                    if let Some((cursor_pos, _)) = handle_editor_drag(Window::Ascii, app, mouse) {
+                        drag_cursor(app, cursor_pos, None, mouse.modifiers);
                     }
-                    Window::Hex => {
-                        if let Some((cursor_pos, nibble)) =
-                            handle_editor_drag(Window::Hex, app, mouse)
-                        {
-                            if app.data.last_drag.is_none() {
-                                app.data.last_drag = Some(app.data.offset);
-                                app.data.drag_nibble = Some(app.data.nibble);
-                            }
-                            app.data.offset = cursor_pos;
-                            app.data.nibble = nibble.unwrap();
-                            app.labels.update_all(&app.data.contents[app.data.offset..]);
-                            adjust_offset(&mut app.data, &mut app.display, &mut app.labels);
-                        }
+                }
+                Window::Hex => {
+                    if let Some((cursor_pos, nibble)) = handle_editor_drag(Window::Hex, app, mouse)
+                    {
+                        drag_cursor(app, cursor_pos, nibble, mouse.modifiers);
                     }
-                    Window::Label(_)
-                    | Window::Unhandled
-                    | Window::JumpToByte
-                    | Window::Search
-                    | Window::UnsavedChanges => {}
                 }
+                _ => {}
             }
         }
         MouseEventKind::Up(MouseButton::Left) => {
@@ -309,27 +1017,47 @@ pub(crate) fn handle_mouse_input(app: &mut Application, mouse: MouseEvent) {
                 Window::Label(i) => {
                     if app.data.last_click == component {
                         // Put string into clipboard
-                        if let Some(clipboard) = app.data.clipboard.as_mut() {
-                            clipboard.set_text(app.labels[LABEL_TITLES[i]].clone()).unwrap();
-                            app.labels.notification = format!("{} copied!", LABEL_TITLES[i]);
-                        } else {
-                            app.labels.notification = String::from("Can't find clipboard!");
-                        }
+                        let title = app.labels.titles[i];
+                        app.labels.notification =
+                            if app.data.clipboard.set_text(app.labels[title].clone()) {
+                                format!("{title} copied!")
+                            } else {
+                                format!("{title} copied within heh")
+                            };
                     }
                 }
                 Window::Hex
                 | Window::Ascii
                 | Window::Unhandled
                 | Window::JumpToByte
+                | Window::AlignCursor
                 | Window::Search
-                | Window::UnsavedChanges => {}
+                | Window::UnsavedChanges
+                | Window::ProtectedEdit
+                | Window::Checksum
+                | Window::Split
+                | Window::Append
+                | Window::Strings
+                | Window::Info
+                | Window::Replace
+                | Window::Fill
+                | Window::InsertBytes
+                | Window::AppendBytes
+                | Window::Symbols
+                | Window::Hash
+                | Window::RestoreBackup
+                | Window::WriteValue
+                | Window::Notifications
+                | Window::Histogram
+                | Window::Overview => {}
             }
         }
         MouseEventKind::ScrollUp => {
             let bytes_per_line = app.display.comp_layouts.bytes_per_line;
 
-            // Scroll up a line in the viewport without changing cursor.
-            app.data.start_address = app.data.start_address.saturating_sub(bytes_per_line);
+            // Scroll up the viewport without changing cursor.
+            app.data.start_address =
+                app.data.start_address.saturating_sub(bytes_per_line * app.data.scroll_lines);
         }
         MouseEventKind::ScrollDown => {
             let bytes_per_line = app.display.comp_layouts.bytes_per_line;
@@ -338,11 +1066,13 @@ pub(crate) fn handle_mouse_input(app: &mut Application, mouse: MouseEvent) {
             let content_lines = app.data.contents.len() / bytes_per_line + 1;
             let start_row = app.data.start_address / bytes_per_line;
 
-            // Scroll down a line in the viewport without changing cursor.
+            // Scroll down the viewport without changing cursor.
             // Until the viewport contains the last page of content.
-            if start_row + lines_per_screen < content_lines {
-                app.data.start_address = app.data.start_address.saturating_add(bytes_per_line);
-            }
+            let lines = cmp::min(
+                app.data.scroll_lines,
+                content_lines.saturating_sub(start_row + lines_per_screen),
+            );
+            app.data.start_address = app.data.start_address.saturating_add(bytes_per_line * lines);
         }
         _ => {}
     }
@@ -357,24 +1087,19 @@ fn handle_editor_click(
 ) -> Option<(usize, Option<Nibble>)> {
     app.set_focused_window(window);
 
-    let (editor, word_size) = match window {
-        Window::Ascii => (&app.display.comp_layouts.ascii, 1),
-        Window::Hex => (&app.display.comp_layouts.hex, 3),
-        _ => {
-            panic!("Trying to move cursor on unhandled window!")
-        }
-    };
+    let (editor, row_width) = editor_row_width(window, app);
 
     // In the hex editor, a cursor click in between two bytes will select the first nibble of the
     // latter one. In the case that we're at the end of the row, this is just a tweak so that the
     // cursor is selected as the last nibble of the first byte.
-    let end_of_row = editor.x + app.display.comp_layouts.bytes_per_line as u16 * word_size;
+    let end_of_row = editor.x + row_width(app.display.comp_layouts.bytes_per_line);
     if mouse.column == end_of_row {
         mouse.column = end_of_row;
     }
     let res = handle_editor_cursor_action(window, app, mouse);
     if res.is_some() {
         // Reset the dragged highlighting.
+        app.data.discard_pending_nibble();
         app.data.last_drag = None;
         app.data.drag_nibble = None;
         app.data.drag_enabled = true;
@@ -391,21 +1116,15 @@ fn handle_editor_drag(
     app: &mut Application,
     mut mouse: MouseEvent,
 ) -> Option<(usize, Option<Nibble>)> {
-    let (editor, word_size) = match window {
-        Window::Ascii => (&app.display.comp_layouts.ascii, 1),
-        Window::Hex => (&app.display.comp_layouts.hex, 3),
-        _ => {
-            panic!("Trying to move cursor on unhandled window!")
-        }
-    };
+    let (editor, row_width) = editor_row_width(window, app);
 
     let click_past_contents = app.display.comp_layouts.bytes_per_line
         * app.display.comp_layouts.lines_per_screen
         + app.data.start_address
         > app.data.contents.len();
 
-    let mut editor_last_col = app.display.comp_layouts.bytes_per_line as u16;
-    let mut end_of_row = 1 + editor.x + (editor_last_col * word_size);
+    let mut editor_last_col = app.display.comp_layouts.bytes_per_line;
+    let mut end_of_row = 1 + editor.x + row_width(editor_last_col);
 
     // Allows cursor x position to be tracked outside of the initially selected viewport when
     // dragged. Quickly dragging to the right will select everything to the end of the row.
@@ -424,8 +1143,8 @@ fn handle_editor_drag(
             (app.data.contents.len() - app.data.start_address)
                 / app.display.comp_layouts.bytes_per_line,
         ) as u16;
-    if mouse.row == 0 {
-        mouse.row = 1;
+    if mouse.row <= editor.top() {
+        mouse.row = editor.top() + 1;
         if let Some(mut result) = handle_editor_cursor_action(window, app, mouse) {
             if let Some(new_y) = result.0.checked_sub(app.display.comp_layouts.bytes_per_line) {
                 result.0 = new_y;
@@ -438,9 +1157,9 @@ fn handle_editor_drag(
         // When the mouse is dragged past the end of the contents, we need to update drag, but not
         // change the start address/scroll.
         if click_past_contents {
-            editor_last_col = ((app.data.contents.len() - app.data.start_address)
-                % app.display.comp_layouts.bytes_per_line) as u16;
-            end_of_row = 1 + editor.x + (editor_last_col * word_size);
+            editor_last_col = (app.data.contents.len() - app.data.start_address)
+                % app.display.comp_layouts.bytes_per_line;
+            end_of_row = 1 + editor.x + row_width(editor_last_col);
             if mouse.column >= end_of_row {
                 mouse.column = end_of_row;
             }
@@ -461,6 +1180,25 @@ fn handle_editor_drag(
     }
 }
 
+/// Returns the editor in the window, and a function that gives the number of columns taken by
+/// the given number of bytes in it, including the spaces after them.
+#[allow(clippy::cast_possible_truncation)]
+fn editor_row_width(window: Window, app: &Application) -> (Rect, impl Fn(usize) -> u16) {
+    let layouts = &app.display.comp_layouts;
+    let (editor, group_size) = match window {
+        Window::Ascii => (layouts.ascii, None),
+        Window::Hex => (layouts.hex, Some(layouts.group_size)),
+        _ => {
+            panic!("Trying to move cursor on unhandled window!")
+        }
+    };
+    let row_width = move |bytes| match group_size {
+        Some(group_size) => screen::hex_row_width(bytes, group_size) as u16,
+        None => bytes as u16,
+    };
+    (editor, row_width)
+}
+
 /// Determines if the relative cursor/drag position should be updated.
 #[allow(clippy::cast_possible_truncation)]
 fn handle_editor_cursor_action(
@@ -468,13 +1206,7 @@ fn handle_editor_cursor_action(
     app: &mut Application,
     mouse: MouseEvent,
 ) -> Option<(usize, Option<Nibble>)> {
-    let (editor, word_size) = match window {
-        Window::Ascii => (&app.display.comp_layouts.ascii, 1),
-        Window::Hex => (&app.display.comp_layouts.hex, 3),
-        _ => {
-            panic!("Trying to move cursor on unhandled window!")
-        }
-    };
+    let (editor, row_width) = editor_row_width(window, app);
     // Identify the byte that was clicked on based on the relative position.
     let (mut rel_x, mut rel_y) =
         (mouse.column.saturating_sub(editor.x), mouse.row.saturating_sub(editor.y));
@@ -484,8 +1216,7 @@ fn handle_editor_cursor_action(
     // byte on the next row for dragging purposes.
     if rel_y == editor.height - 2
         && rel_x
-            > app.display.comp_layouts.bytes_per_line as u16 * word_size
-                - u16::from(window == Window::Hex)
+            > row_width(app.display.comp_layouts.bytes_per_line) - u16::from(window == Window::Hex)
     {
         return None;
     }
@@ -504,7 +1235,10 @@ fn handle_editor_cursor_action(
                 }
             }
             Window::Hex => {
-                (rel_x, rel_y) = (rel_x, rel_y - 1);
+                let group_size = app.display.comp_layouts.group_size;
+                // Past the border, the column is counted as if there were no gaps between groups
+                rel_x = 1 + screen::ungrouped_hex_column(rel_x as usize - 1, group_size) as u16;
+                rel_y -= 1;
                 let content_pos = app.data.start_address
                     + (rel_y as usize * app.display.comp_layouts.bytes_per_line)
                     + (rel_x as usize / 3);
@@ -0,0 +1,431 @@
+//! The keys that run each command, which can be changed with a keymap file.
+//!
+//! A keymap file has lines of the form `command = "key"` or `command = ["key", "key"]`, which
+//! replace the default keys of the command. An empty list (`command = []`) unbinds the command.
+//! Keys are written like `ctrl+s`, `alt+=`, `pagedown`, or `/`. Empty lines and lines starting
+//! with `#` are ignored.
+//!
+//! Keys without `ctrl` or `alt` that type a character, like `h` or `/`, only run their command in
+//! the hex editor, since they type the character everywhere else.
+
+use std::collections::HashMap;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// The actions that keys can be bound to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Command {
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    FileStart,
+    FileEnd,
+    PageUp,
+    PageDown,
+    Backspace,
+    Delete,
+    ToggleEditMode,
+    Quit,
+    Save,
+    Undo,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    JumpToByte,
+    Strings,
+    Info,
+    Refresh,
+    CycleEncoding,
+    SwitchEndianness,
+    IncreaseStreamLength,
+    DecreaseStreamLength,
+    Append,
+    Checksum,
+    Split,
+    ViewInPager,
+    ExportHexdump,
+    Replace,
+    Fill,
+    InsertBytes,
+    ToggleBookmark,
+    NextBookmark,
+    CopyPatchLine,
+    FillRandom,
+    CycleAddressRadix,
+    ToggleProtection,
+    NextChange,
+    PreviousChange,
+    NextZero,
+    NextNonZero,
+    Symbols,
+    Hash,
+    CycleStep,
+    Count,
+    PasteBase64,
+    SaveQuit,
+    CopyOffset,
+    WriteValue,
+    FollowPointer,
+    PointerBack,
+    NextBoundary,
+    PreviousBoundary,
+    TogglePin,
+    Notifications,
+    NextFile,
+    ToggleHeatMap,
+    NextPage,
+    PreviousPage,
+    CopyPython,
+    Histogram,
+    Truncate,
+    ToggleRuler,
+    ToggleLabels,
+    RecordMacro,
+    PlayMacro,
+    AlignCursor,
+    CompareClipboard,
+    AppendBytes,
+}
+
+impl Command {
+    /// Returns whether the command only moves the cursor, which makes it repeatable with a count.
+    pub(crate) fn is_motion(self) -> bool {
+        matches!(
+            self,
+            Self::Left
+                | Self::Right
+                | Self::Up
+                | Self::Down
+                | Self::PageUp
+                | Self::PageDown
+                | Self::NextBoundary
+                | Self::PreviousBoundary
+                | Self::NextPage
+                | Self::PreviousPage
+        )
+    }
+}
+
+/// Every command with its name in keymap files and its default keys.
+const COMMANDS: [(Command, &str, &[&str]); 73] = [
+    (Command::Left, "left", &["left", "h"]),
+    (Command::Right, "right", &["right", "l"]),
+    (Command::Up, "up", &["up", "k"]),
+    (Command::Down, "down", &["down", "j"]),
+    (Command::Home, "home", &["home", "^"]),
+    (Command::End, "end", &["end", "$"]),
+    (Command::FileStart, "file_start", &["ctrl+home", "g"]),
+    (Command::FileEnd, "file_end", &["ctrl+end", "G"]),
+    (Command::PageUp, "page_up", &["pageup", "ctrl+u"]),
+    (Command::PageDown, "page_down", &["pagedown", "ctrl+d"]),
+    (Command::Backspace, "backspace", &["backspace"]),
+    (Command::Delete, "delete", &["delete"]),
+    (Command::ToggleEditMode, "toggle_edit_mode", &["insert"]),
+    (Command::Quit, "quit", &["ctrl+q"]),
+    (Command::Save, "save", &["ctrl+s"]),
+    (Command::Undo, "undo", &["ctrl+z"]),
+    (Command::Search, "search", &["ctrl+f", "/"]),
+    (Command::NextMatch, "next_match", &["ctrl+n"]),
+    (Command::PreviousMatch, "previous_match", &["ctrl+p"]),
+    (Command::JumpToByte, "jump_to_byte", &["ctrl+j"]),
+    (Command::Strings, "strings", &["ctrl+t"]),
+    (Command::Info, "info", &["ctrl+."]),
+    (Command::Refresh, "refresh", &["ctrl+r"]),
+    (Command::CycleEncoding, "cycle_encoding", &["ctrl+g"]),
+    (Command::SwitchEndianness, "switch_endianness", &["ctrl+e"]),
+    (Command::IncreaseStreamLength, "increase_stream_length", &["alt+="]),
+    (Command::DecreaseStreamLength, "decrease_stream_length", &["alt+-"]),
+    (Command::Append, "append", &["alt+a"]),
+    (Command::Checksum, "checksum", &["alt+c"]),
+    (Command::Split, "split", &["alt+s"]),
+    (Command::ViewInPager, "view_in_pager", &["alt+v"]),
+    (Command::ExportHexdump, "export_hexdump", &["alt+h"]),
+    (Command::Replace, "replace", &["alt+f"]),
+    (Command::Fill, "fill", &["alt+o"]),
+    (Command::InsertBytes, "insert_bytes", &["alt+i"]),
+    (Command::ToggleBookmark, "toggle_bookmark", &["alt+b"]),
+    (Command::NextBookmark, "next_bookmark", &["alt+n"]),
+    (Command::CopyPatchLine, "copy_patch_line", &["alt+y"]),
+    (Command::FillRandom, "fill_random", &["alt+r"]),
+    (Command::CycleAddressRadix, "cycle_address_radix", &["alt+x"]),
+    (Command::ToggleProtection, "toggle_protection", &["alt+p"]),
+    (Command::NextChange, "next_change", &["alt+."]),
+    (Command::PreviousChange, "previous_change", &["alt+,"]),
+    (Command::NextZero, "next_zero", &["alt+0"]),
+    (Command::NextNonZero, "next_non_zero", &["alt+1"]),
+    (Command::Symbols, "symbols", &["alt+m"]),
+    (Command::Hash, "hash", &["alt+d"]),
+    (Command::CycleStep, "cycle_step", &["alt+w"]),
+    (Command::Count, "count", &["#"]),
+    (Command::PasteBase64, "paste_base64", &["alt+e"]),
+    (Command::SaveQuit, "save_quit", &["ctrl+x"]),
+    (Command::CopyOffset, "copy_offset", &["alt+l"]),
+    (Command::WriteValue, "write_value", &["alt+t"]),
+    (Command::FollowPointer, "follow_pointer", &["alt+j"]),
+    (Command::PointerBack, "pointer_back", &["alt+k"]),
+    (Command::NextBoundary, "next_boundary", &["tab"]),
+    (Command::PreviousBoundary, "previous_boundary", &["backtab"]),
+    (Command::TogglePin, "toggle_pin", &["alt+z"]),
+    (Command::Notifications, "notifications", &["ctrl+l"]),
+    (Command::NextFile, "next_file", &["ctrl+o"]),
+    (Command::ToggleHeatMap, "toggle_heat_map", &["alt+g"]),
+    (Command::NextPage, "next_page", &["alt+pagedown"]),
+    (Command::PreviousPage, "previous_page", &["alt+pageup"]),
+    (Command::CopyPython, "copy_python", &["alt+u"]),
+    (Command::Histogram, "histogram", &["alt+q"]),
+    (Command::Truncate, "truncate", &["ctrl+k"]),
+    (Command::ToggleRuler, "toggle_ruler", &["ctrl+y"]),
+    (Command::ToggleLabels, "toggle_labels", &["ctrl+b"]),
+    (Command::RecordMacro, "record_macro", &["f5"]),
+    (Command::PlayMacro, "play_macro", &["f6"]),
+    (Command::AlignCursor, "align_cursor", &["ctrl+a"]),
+    (Command::CompareClipboard, "compare_clipboard", &["ctrl+v"]),
+    (Command::AppendBytes, "append_bytes", &["alt+A"]),
+];
+
+/// Keys that are bound like in vim with `--vim`, on top of the other keys.
+const VIM_KEYS: [(Command, &str); 1] = [(Command::Quit, "q")];
+
+/// Maps keys to the commands they run.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<(KeyCode, KeyModifiers), Command>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let keys = COMMANDS.iter().map(|&(command, _, keys)| (command, keys.to_vec())).collect();
+        Self::with_keys(keys).expect("The default keys conflict!")
+    }
+}
+
+impl Keymap {
+    /// Returns the command that the key runs, if any. Keys that don't type a character, like the
+    /// arrow keys, run the command they are bound to without modifiers when they aren't bound with
+    /// the ones held, so that ctrl+left still moves left.
+    pub(crate) fn command(&self, key: KeyEvent) -> Option<Command> {
+        self.bound(key).or_else(|| match key.code {
+            KeyCode::Char(_) => None,
+            code => self.bindings.get(&normalize(code, KeyModifiers::NONE)).copied(),
+        })
+    }
+
+    /// Returns the command that the key is bound to with exactly the modifiers held, if any.
+    pub(crate) fn bound(&self, key: KeyEvent) -> Option<Command> {
+        self.bindings.get(&normalize(key.code, key.modifiers)).copied()
+    }
+
+    /// Binds the keys of [`VIM_KEYS`], like `q` to quit, unless they are already bound to
+    /// something else.
+    pub(crate) fn add_vim_keys(&mut self) {
+        for (command, key) in VIM_KEYS {
+            let key = parse_key(key).expect("The vim keys are invalid!");
+            self.bindings.entry(key).or_insert(command);
+        }
+    }
+
+    /// Binds every command to its keys, making sure that no key is bound twice.
+    fn with_keys(keys: Vec<(Command, Vec<&str>)>) -> Result<Self, String> {
+        let mut bindings = HashMap::new();
+        for (command, keys) in keys {
+            for key in keys {
+                if let Some(bound) = bindings.insert(parse_key(key)?, command) {
+                    return Err(format!(
+                        "`{key}` is bound to both {} and {}",
+                        name(bound),
+                        name(command)
+                    ));
+                }
+            }
+        }
+        Ok(Self { bindings })
+    }
+}
+
+/// Returns the name of the command in keymap files.
+fn name(command: Command) -> &'static str {
+    COMMANDS.iter().find(|(c, _, _)| *c == command).map_or("", |(_, name, _)| name)
+}
+
+/// Parses the contents of a keymap file, starting from the default keys.
+///
+/// # Errors
+///
+/// This errors with the offending line number when a line cannot be parsed or names an unknown
+/// command or key, and errors when a key is bound to more than one command.
+pub fn parse(text: &str) -> Result<Keymap, String> {
+    let mut keys: Vec<(Command, Vec<&str>)> =
+        COMMANDS.iter().map(|&(command, _, keys)| (command, keys.to_vec())).collect();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (command, command_keys) =
+            parse_line(line).map_err(|e| format!("Line {}: {e}", idx + 1))?;
+        for key in &command_keys {
+            parse_key(key).map_err(|e| format!("Line {}: {e}", idx + 1))?;
+        }
+        if let Some(entry) = keys.iter_mut().find(|(c, _)| *c == command) {
+            entry.1 = command_keys;
+        }
+    }
+    Keymap::with_keys(keys)
+}
+
+fn parse_line(line: &str) -> Result<(Command, Vec<&str>), String> {
+    let (name, value) = line.split_once('=').ok_or("expected `command = \"key\"`")?;
+    let name = name.trim();
+    let command = COMMANDS
+        .iter()
+        .find(|(_, n, _)| *n == name)
+        .map(|&(command, _, _)| command)
+        .ok_or_else(|| format!("unknown command: {name}"))?;
+
+    let value = value.trim();
+    let keys = match value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+        Some(list) => list.split(',').map(str::trim).filter(|key| !key.is_empty()).collect(),
+        None => vec![value],
+    };
+    let keys = keys
+        .into_iter()
+        .map(|key| {
+            key.strip_prefix('"')
+                .and_then(|k| k.strip_suffix('"'))
+                .ok_or_else(|| format!("expected a quoted key: {key}"))
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((command, keys))
+}
+
+/// Parses a key like `ctrl+s` or `pagedown`.
+fn parse_key(key: &str) -> Result<(KeyCode, KeyModifiers), String> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut rest = key;
+    loop {
+        if let Some(stripped) = rest.strip_prefix("ctrl+") {
+            modifiers |= KeyModifiers::CONTROL;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("alt+") {
+            modifiers |= KeyModifiers::ALT;
+            rest = stripped;
+        } else if let Some(stripped) = rest.strip_prefix("shift+") {
+            modifiers |= KeyModifiers::SHIFT;
+            rest = stripped;
+        } else {
+            break;
+        }
+    }
+
+    let mut chars = rest.chars();
+    let code = match (chars.next(), chars.next()) {
+        (Some(c), None) => KeyCode::Char(c),
+        _ => match rest {
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "backspace" => KeyCode::Backspace,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            _ => match rest.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key: {key}")),
+            },
+        },
+    };
+    Ok(normalize(code, modifiers))
+}
+
+/// Folds shift into the character, since terminals report typing `A` or `$` with or without it.
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+            (KeyCode::Char(c.to_ascii_uppercase()), modifiers - KeyModifiers::SHIFT)
+        }
+        // Shift+Tab is sent as a back tab, with or without shift
+        KeyCode::BackTab => (code, modifiers - KeyModifiers::SHIFT),
+        _ => (code, modifiers),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let press = |code, modifiers| KeyEvent::new(code, modifiers);
+
+        let keymap =
+            parse("# emacs-ish\nsave = \"ctrl+x\"\nsave_quit = []\n\nsearch = [\"ctrl+s\", \"f3\"]\nundo = []")
+                .unwrap();
+        assert_eq!(
+            keymap.command(press(KeyCode::Char('x'), KeyModifiers::CONTROL)),
+            Some(Command::Save)
+        );
+        assert_eq!(
+            keymap.command(press(KeyCode::Char('s'), KeyModifiers::CONTROL)),
+            Some(Command::Search)
+        );
+        assert_eq!(keymap.command(press(KeyCode::F(3), KeyModifiers::NONE)), Some(Command::Search));
+        assert_eq!(keymap.command(press(KeyCode::Char('/'), KeyModifiers::NONE)), None);
+        assert_eq!(keymap.command(press(KeyCode::Char('z'), KeyModifiers::CONTROL)), None);
+        // Keys that weren't changed keep their defaults
+        assert_eq!(
+            keymap.command(press(KeyCode::Char('$'), KeyModifiers::SHIFT)),
+            Some(Command::End)
+        );
+
+        assert_eq!(
+            parse("save = \"ctrl+q\"").unwrap_err(),
+            "`ctrl+q` is bound to both quit and save"
+        );
+        assert_eq!(parse("\nsave = \"hyper+s\"").unwrap_err(), "Line 2: unknown key: hyper+s");
+        assert_eq!(parse("write = \"ctrl+w\"").unwrap_err(), "Line 1: unknown command: write");
+        assert!(parse("save").is_err());
+
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.command(press(KeyCode::BackTab, KeyModifiers::SHIFT)),
+            Some(Command::PreviousBoundary)
+        );
+
+        // Keys that don't type a character fall back to their binding without modifiers
+        assert_eq!(
+            keymap.command(press(KeyCode::Left, KeyModifiers::CONTROL)),
+            Some(Command::Left)
+        );
+        assert_eq!(keymap.bound(press(KeyCode::Left, KeyModifiers::CONTROL)), None);
+        assert_eq!(keymap.command(press(KeyCode::Home, KeyModifiers::SHIFT)), Some(Command::Home));
+        assert_eq!(
+            keymap.command(press(KeyCode::Home, KeyModifiers::CONTROL)),
+            Some(Command::FileStart)
+        );
+        assert_eq!(keymap.command(press(KeyCode::Char('h'), KeyModifiers::CONTROL)), None);
+    }
+
+    #[test]
+    fn test_vim_keys() {
+        let q = KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE);
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.command(q), None);
+        keymap.add_vim_keys();
+        assert_eq!(keymap.command(q), Some(Command::Quit));
+
+        // Keys bound in the keymap file keep their binding
+        let mut keymap = parse("save = \"q\"").unwrap();
+        keymap.add_vim_keys();
+        assert_eq!(keymap.command(q), Some(Command::Save));
+    }
+}
@@ -2,29 +2,90 @@
 
 #![allow(clippy::cast_possible_wrap)]
 
+use std::collections::VecDeque;
 use std::fmt::Formatter;
 use std::fmt::{self, Write};
 use std::ops::Index;
+use std::time::{Duration, Instant};
 
-pub(crate) static LABEL_TITLES: [&str; 16] = [
+#[cfg(feature = "disasm")]
+use crate::disasm::{Disassembler, MAX_INSTRUCTION_LEN};
+use crate::{
+    app::{AddressRadix, EditMode},
+    character::{caret_notation, Category, CHARACTER_UNKNOWN},
+    decoder::{ByteAlignedDecoder, Encoding},
+};
+
+/// The titles of the labels, in the order they are displayed (row by row, 4 labels per row).
+pub(crate) static LABEL_TITLES: [&str; 23] = [
     "Signed 8 bit",
-    "Unsigned 8 bit",
-    "Signed 16 bit",
-    "Unsigned 16 bit",
     "Signed 32 bit",
-    "Unsigned 32 bit",
-    "Signed 64 bit",
-    "Unsigned 64 bit",
     "Hexadecimal",
-    "Octal",
-    "Binary",
-    "Stream Length",
     "Float 32 bit",
+    "Unsigned 8 bit",
+    "Unsigned 32 bit",
+    "Octal",
     "Float 64 bit",
+    "Signed 16 bit",
+    "Signed 64 bit",
+    "Binary",
     "Offset",
+    "Unsigned 16 bit",
+    "Unsigned 64 bit",
+    "Stream Length",
     "Notifications",
+    "Edit Mode",
+    "Position",
+    "Char",
+    "Δ from pin",
+    "Entropy",
+    "Page",
+    "Byte",
 ];
 
+/// The number of bytes that the entropy is computed over by default.
+pub(crate) const DEFAULT_ENTROPY_WINDOW: usize = 256;
+
+/// The size of the pages that the `Page` label counts by default, which is the usual size of
+/// memory pages.
+pub(crate) const DEFAULT_PAGE_SIZE: usize = 0x1000;
+
+/// The default for the most bits that `ALT=` lengthens the streams of the binary, octal, and
+/// hexadecimal labels to.
+pub(crate) const DEFAULT_MAX_STREAM_LENGTH: usize = 64;
+
+/// The number of notifications that are kept in the history.
+const NOTIFICATION_HISTORY_LEN: usize = 50;
+
+/// Converts a label title to the name used to select it with `--labels`, e.g. "Signed 16 bit" to
+/// `signed16` and "Δ from pin" to `frompin`.
+fn label_name(title: &str) -> String {
+    title.to_lowercase().replace(" bit", "").chars().filter(char::is_ascii_alphanumeric).collect()
+}
+
+/// Finds the titles of the labels with the given names, keeping the order of [`LABEL_TITLES`].
+/// Every label is selected if no names are given.
+///
+/// # Errors
+///
+/// This errors with the list of valid names if a name doesn't belong to any label.
+pub(crate) fn select_titles(names: &[String]) -> Result<Vec<&'static str>, String> {
+    if let Some(unknown) = names
+        .iter()
+        .find(|name| !LABEL_TITLES.iter().any(|&title| label_name(title) == name.to_lowercase()))
+    {
+        let valid: Vec<String> = LABEL_TITLES.iter().map(|title| label_name(title)).collect();
+        return Err(format!("Unknown label `{unknown}`. Valid labels are: {}", valid.join(", ")));
+    }
+    Ok(LABEL_TITLES
+        .iter()
+        .copied()
+        .filter(|&title| {
+            names.is_empty() || names.iter().any(|name| label_name(title) == name.to_lowercase())
+        })
+        .collect())
+}
+
 #[derive(Default)]
 pub(crate) enum Endianness {
     #[default]
@@ -58,9 +119,43 @@ pub struct Handler {
     hexadecimal: String,
     stream_length: usize,
     stream_length_string: String,
+    /// The most bits that the stream length can be increased to.
+    pub(crate) max_stream_length: usize,
     pub(crate) offset: String,
     pub notification: String,
+    /// The most recent notifications and when they were shown, oldest first.
+    pub(crate) history: VecDeque<(Instant, String)>,
+    /// How long a notification stays on the screen through the keys and clicks that follow it.
+    /// Notifications are cleared by the next key or click if unset.
+    pub(crate) notification_timeout: Option<Duration>,
+    /// When the notification was recorded, which its timeout counts from.
+    notification_shown: Option<Instant>,
+    edit_mode: String,
+    position: String,
+    character: String,
+    /// The address pinned as an anchor, which the distance of the cursor is shown from.
+    pub(crate) pin: Option<usize>,
+    pin_distance: String,
+    /// The number of bytes from the cursor that the entropy is computed over.
+    entropy_window: usize,
+    entropy: String,
+    /// The size of the pages that the cursor is located in by the `Page` label.
+    pub(crate) page_size: usize,
+    page: String,
+    byte: String,
+    /// Disassembles the instruction at the cursor, if an architecture was set.
+    #[cfg(feature = "disasm")]
+    pub(crate) disassembler: Option<Disassembler>,
+    /// The bytes at the cursor and their address, which the instruction is disassembled from.
+    #[cfg(feature = "disasm")]
+    code: (Vec<u8>, usize),
+    #[cfg(feature = "disasm")]
+    disasm: String,
     pub(crate) endianness: Endianness,
+    /// The encoding used to decode the character at the cursor.
+    pub(crate) encoding: Encoding,
+    /// The titles of the labels that are displayed, in the order of [`LABEL_TITLES`].
+    pub(crate) titles: Vec<&'static str>,
 }
 
 impl Index<&str> for Handler {
@@ -84,17 +179,39 @@ impl Index<&str> for Handler {
             "Float 64 bit" => &self.float_sixtyfour,
             "Offset" => &self.offset,
             "Notifications" => &self.notification,
+            "Edit Mode" => &self.edit_mode,
+            "Position" => &self.position,
+            "Char" => &self.character,
+            "Δ from pin" => &self.pin_distance,
+            "Entropy" => &self.entropy,
+            "Page" => &self.page,
+            "Byte" => &self.byte,
+            #[cfg(feature = "disasm")]
+            "Disasm" => &self.disasm,
             _ => panic!(),
         }
     }
 }
 
 impl Handler {
-    pub(crate) fn new(bytes: &[u8], offset: usize) -> Self {
-        let mut labels = Self { ..Default::default() };
-        labels.update_stream_length(8);
+    pub(crate) fn new(
+        bytes: &[u8],
+        offset: usize,
+        stream_length: usize,
+        encoding: Encoding,
+    ) -> Self {
+        let mut labels = Self {
+            encoding,
+            titles: LABEL_TITLES.to_vec(),
+            entropy_window: DEFAULT_ENTROPY_WINDOW,
+            page_size: DEFAULT_PAGE_SIZE,
+            max_stream_length: DEFAULT_MAX_STREAM_LENGTH,
+            ..Default::default()
+        };
+        labels.update_stream_length(stream_length);
         labels.update_all(&bytes[offset..]);
-        labels.offset = format!("{offset:#X?}");
+        labels.update_offset(offset, AddressRadix::default());
+        labels.update_edit_mode(EditMode::default());
         labels
     }
     pub(crate) fn update_all(&mut self, bytes: &[u8]) {
@@ -112,7 +229,88 @@ impl Handler {
         self.update_float_thirtytwo(&filled_bytes[0..4]);
         self.update_float_sixtyfour(&filled_bytes[0..8]);
 
+        self.update_character(bytes);
+        self.update_byte(bytes);
         self.update_streams(bytes);
+        self.update_entropy(bytes);
+
+        #[cfg(feature = "disasm")]
+        {
+            self.code.0 = bytes[..bytes.len().min(MAX_INSTRUCTION_LEN)].to_vec();
+            self.update_disasm();
+        }
+    }
+    /// Shows the instruction at the cursor. The bytes and the address of the cursor are updated
+    /// separately, so this is done whenever either of them changes.
+    #[cfg(feature = "disasm")]
+    fn update_disasm(&mut self) {
+        if let Some(disassembler) = &self.disassembler {
+            self.disasm = disassembler.describe(&self.code.0, self.code.1);
+        }
+    }
+    /// Shows the character that starts at the first byte and its code point, with whitespace
+    /// escaped and ASCII control characters in caret notation.
+    fn update_character(&mut self, bytes: &[u8]) {
+        let Some(character) =
+            ByteAlignedDecoder::new(&bytes[..bytes.len().min(4)], self.encoding).next()
+        else {
+            self.character.clear();
+            return;
+        };
+        let code_point = u32::from(char::from(&character));
+        self.character = match character.category() {
+            Category::Unknown | Category::Fill => String::from("Invalid"),
+            Category::Null => format!("NUL U+{code_point:04X}"),
+            Category::Whitespace if code_point == 0x20 => format!("Space U+{code_point:04X}"),
+            Category::Control if code_point < 0x80 => {
+                let caret = caret_notation(char::from(&character)).unwrap_or_default();
+                format!("{caret} U+{code_point:04X}")
+            }
+            Category::Whitespace | Category::Control => {
+                format!("{} U+{code_point:04X}", char::from(&character).escape_default())
+            }
+            Category::Ascii | Category::Unicode => format!("{character} U+{code_point:04X}"),
+        };
+    }
+    /// Shows the byte at the cursor both in hexadecimal and like the text editor displays it, to
+    /// check the byte in one editor while typing in the other.
+    fn update_byte(&mut self, bytes: &[u8]) {
+        let Some(&byte) = bytes.first() else {
+            self.byte.clear();
+            return;
+        };
+        let glyph = ByteAlignedDecoder::new(&bytes[..bytes.len().min(4)], self.encoding)
+            .next()
+            .map_or(CHARACTER_UNKNOWN, |character| character.escape());
+        self.byte = format!("{byte:02X} '{glyph}'");
+    }
+    /// Changes the number of bytes that the entropy is computed over, and computes it again over
+    /// the bytes at the cursor.
+    pub(crate) fn set_entropy_window(&mut self, window: usize, bytes: &[u8]) {
+        self.entropy_window = window;
+        self.update_entropy(bytes);
+    }
+    /// Shows the Shannon entropy of the bytes in the window at the cursor, from 0 bits for a
+    /// repeated byte to 8 bits for bytes that are evenly spread over every value, like compressed
+    /// or encrypted data. The window ends early at the end of the file.
+    #[allow(clippy::cast_precision_loss)]
+    fn update_entropy(&mut self, bytes: &[u8]) {
+        let window = &bytes[..bytes.len().min(self.entropy_window)];
+        let mut counts = [0_usize; 256];
+        for &byte in window {
+            counts[usize::from(byte)] += 1;
+        }
+        let len = window.len() as f64;
+        let entropy: f64 = counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / len;
+                -p * p.log2()
+            })
+            .sum();
+        // A single value would otherwise be shown as -0.000
+        self.entropy = format!("{:.3} bits", entropy.max(0.0));
     }
     pub(crate) fn update_streams(&mut self, bytes: &[u8]) {
         let mut filled_bytes = fill_slice(bytes, self.stream_length / 8);
@@ -132,6 +330,76 @@ impl Handler {
         self.stream_length = length;
         self.stream_length_string = self.stream_length.to_string();
     }
+    /// Shows the address of the cursor, which is its offset in the file plus any `--base`, its
+    /// signed distance from the pin if there is one, and the page that it is in.
+    pub(crate) fn update_offset(&mut self, address: usize, radix: AddressRadix) {
+        self.offset = radix.format_offset(address);
+        self.page = format!(
+            "{}, +{}",
+            address / self.page_size,
+            radix.format_offset(address % self.page_size)
+        );
+        self.pin_distance = match self.pin {
+            Some(pin) if address < pin => format!("-{}", radix.format_offset(pin - address)),
+            Some(pin) if address > pin => format!("+{}", radix.format_offset(address - pin)),
+            Some(_) => radix.format_offset(0),
+            None => String::new(),
+        };
+
+        #[cfg(feature = "disasm")]
+        {
+            self.code.1 = address;
+            self.update_disasm();
+        }
+    }
+    /// Shows the cursor as the row it's on and its column within the row.
+    pub(crate) fn update_position(&mut self, offset: usize, bytes_per_line: usize) {
+        self.position = format!("{}, {}", offset / bytes_per_line, offset % bytes_per_line);
+    }
+    /// Keeps the notification in the history, if there is one, dropping the oldest one once the
+    /// history is full.
+    pub(crate) fn record_notification(&mut self) {
+        if self.notification.is_empty() {
+            return;
+        }
+        // A notification that outlasted the input isn't shown again
+        let kept = self.notification_shown.is_some()
+            && self.history.back().is_some_and(|(_, recorded)| *recorded == self.notification);
+        if kept {
+            return;
+        }
+        if self.history.len() == NOTIFICATION_HISTORY_LEN {
+            self.history.pop_front();
+        }
+        let now = Instant::now();
+        self.history.push_back((now, self.notification.clone()));
+        self.notification_shown = Some(now);
+    }
+    /// Returns how much longer the notification stays on the screen, or None if it stays until
+    /// the next key or click.
+    pub(crate) fn notification_time_left(&self) -> Option<Duration> {
+        let (timeout, shown) = self.notification_timeout.zip(self.notification_shown)?;
+        Some(timeout.saturating_sub(shown.elapsed()))
+    }
+    /// Clears the notification before a key or click is handled, unless it is kept on the screen
+    /// for longer.
+    pub(crate) fn expire_notification(&mut self) {
+        if self.notification_time_left().is_none_or(|left| left.is_zero()) {
+            self.notification.clear();
+            self.notification_shown = None;
+        }
+    }
+    /// Clears the notification once it has been on the screen for its timeout, even if nothing
+    /// was pressed since.
+    pub(crate) fn clear_expired_notification(&mut self) {
+        if self.notification_time_left().is_some_and(|left| left.is_zero()) {
+            self.notification.clear();
+            self.notification_shown = None;
+        }
+    }
+    pub(crate) fn update_edit_mode(&mut self, edit_mode: EditMode) {
+        self.edit_mode = edit_mode.to_string();
+    }
     pub(crate) fn switch_endianness(&mut self) {
         self.endianness = match self.endianness {
             Endianness::LittleEndian => Endianness::BigEndian,
@@ -243,7 +511,7 @@ mod tests {
     fn test_binary_label() {
         // Given a label handler with the content 'hello' and offset of 0
         let content = "hello".as_bytes();
-        let mut label_handler = Handler::new(content, 0);
+        let mut label_handler = Handler::new(content, 0, 8, Encoding::Ascii);
         // The binary label should contain the binary veresion of the first character
         assert!(label_handler.binary.eq("01101000"));
 
@@ -254,4 +522,115 @@ mod tests {
         // The second character should also be represented
         assert!(label_handler.binary.eq("0110100001100101"));
     }
+
+    #[test]
+    fn test_long_streams() {
+        // Streams can be longer than the 64 bits of the widest integer labels
+        let content: Vec<u8> = (0..32).collect();
+        let mut label_handler = Handler::new(&content, 0, 8, Encoding::Ascii);
+        label_handler.update_stream_length(132);
+        label_handler.update_streams(&content);
+        assert_eq!(label_handler.binary.len(), 132);
+        assert!(label_handler.binary.ends_with("000011110001"));
+        assert_eq!(label_handler.hexadecimal.split(' ').count(), 17);
+        assert!(label_handler.hexadecimal.ends_with("0F 10"));
+        assert!(label_handler.octal.ends_with("017 020"));
+
+        // Past the end of the file, the streams are filled with 0s
+        label_handler.update_stream_length(256);
+        label_handler.update_streams(&content[30..]);
+        assert_eq!(label_handler.hexadecimal, format!("1E 1F{}", " 00".repeat(30)));
+    }
+
+    #[test]
+    fn test_character_label() {
+        let mut label_handler = Handler::new(b"h\xC3\xA4\n\x00", 0, 8, Encoding::Utf8);
+        assert_eq!(label_handler.character, "h U+0068");
+
+        label_handler.update_all(b"\xC3\xA4\n\x00");
+        assert_eq!(label_handler.character, "ä U+00E4");
+        label_handler.update_all(b"\xA4\n\x00");
+        assert_eq!(label_handler.character, "Invalid");
+        label_handler.update_all(b"\n\x00");
+        assert_eq!(label_handler.character, "\\n U+000A");
+        label_handler.update_all(b"\x00");
+        assert_eq!(label_handler.character, "NUL U+0000");
+
+        // Control characters are told apart by their caret notation
+        label_handler.update_all(b"\x1B[0m");
+        assert_eq!(label_handler.character, "^[ ESC U+001B");
+        label_handler.update_all(b"\x01");
+        assert_eq!(label_handler.character, "^A SOH U+0001");
+        label_handler.update_all(b"\x7F");
+        assert_eq!(label_handler.character, "^? DEL U+007F");
+        label_handler.update_all(b"\xC2\x85");
+        assert_eq!(label_handler.character, "\\u{85} U+0085");
+    }
+
+    #[test]
+    fn test_notification_timeout() {
+        let mut label_handler = Handler::new(b"\x00", 0, 8, Encoding::Ascii);
+        label_handler.notification = String::from("Saved!");
+        label_handler.record_notification();
+        label_handler.expire_notification();
+        assert!(label_handler.notification.is_empty());
+
+        // With a timeout, the notification outlasts the input without being recorded again
+        label_handler.notification_timeout = Some(Duration::from_secs(1000));
+        label_handler.notification = String::from("Not found");
+        label_handler.record_notification();
+        label_handler.expire_notification();
+        label_handler.clear_expired_notification();
+        label_handler.record_notification();
+        assert_eq!(label_handler.notification, "Not found");
+        assert_eq!(label_handler.history.len(), 2);
+        assert!(label_handler.notification_time_left().unwrap() > Duration::from_secs(990));
+
+        label_handler.notification_timeout = Some(Duration::ZERO);
+        label_handler.clear_expired_notification();
+        assert!(label_handler.notification.is_empty());
+        assert_eq!(label_handler.notification_time_left(), None);
+    }
+
+    #[test]
+    fn test_byte_label() {
+        let mut label_handler = Handler::new(b"A \x1B\x00", 0, 8, Encoding::Ascii);
+        assert_eq!(label_handler.byte, "41 'A'");
+        label_handler.update_all(b" \x1B\x00");
+        assert_eq!(label_handler.byte, "20 ' '");
+        label_handler.update_all(b"\x1B\x00");
+        assert_eq!(label_handler.byte, "1B '⍾'");
+        label_handler.update_all(b"\x00");
+        assert_eq!(label_handler.byte, "00 '0'");
+
+        // Only the first byte of a character is shown, with the character it starts
+        label_handler.encoding = Encoding::Utf8;
+        label_handler.update_all(b"\xC3\xA4");
+        assert_eq!(label_handler.byte, "C3 'ä'");
+    }
+
+    #[test]
+    fn test_entropy_label() {
+        let mut label_handler = Handler::new(&[0; 300], 0, 8, Encoding::Ascii);
+        assert_eq!(label_handler.entropy, "0.000 bits");
+
+        // Every value once is the most entropy there can be
+        let bytes: Vec<u8> = (0..=255).collect();
+        label_handler.update_all(&bytes);
+        assert_eq!(label_handler.entropy, "8.000 bits");
+
+        // Only the bytes in the window count
+        label_handler.set_entropy_window(2, b"ABBB");
+        assert_eq!(label_handler.entropy, "1.000 bits");
+    }
+
+    #[test]
+    fn test_select_titles() {
+        assert_eq!(select_titles(&[]).unwrap(), LABEL_TITLES.to_vec());
+        let names = ["offset", "Float32", "signed16"].map(String::from);
+        assert_eq!(select_titles(&names).unwrap(), vec!["Float 32 bit", "Signed 16 bit", "Offset"]);
+        assert_eq!(select_titles(&[String::from("frompin")]).unwrap(), vec!["Δ from pin"]);
+        let error = select_titles(&[String::from("nope")]).unwrap_err();
+        assert!(error.starts_with("Unknown label `nope`. Valid labels are: signed8, signed32,"));
+    }
 }
@@ -1,9 +1,22 @@
+pub mod annotation;
 pub mod app;
+mod backup;
 mod buffer;
 mod character;
+mod checksum;
 mod chunk;
+mod clipboard;
 pub mod decoder;
+#[cfg(feature = "disasm")]
+pub mod disasm;
+pub mod export;
 pub mod input;
+pub mod keymap;
 pub mod label;
+mod pattern;
+mod save;
 pub mod screen;
+mod search_index;
+pub mod symbol;
+pub mod theme;
 pub mod windows;
@@ -5,13 +5,25 @@
 //! **heh is currently in alpha** - it's not ready to be used in any production manner. It lacks a
 //! variety of quality of life features and does not store backups if killed or crashing.
 
-use std::{error::Error, fs::OpenOptions, io, process};
+use std::{
+    error::Error,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    process,
+    time::Duration,
+};
 
 use clap::{Parser, ValueEnum};
 use ratatui::crossterm::tty::IsTty;
 
-use heh::app::Application;
+use heh::annotation;
+use heh::app::{AddressRadix, Application, HexEntry, MultibyteEdit, Options};
 use heh::decoder::Encoding;
+#[cfg(feature = "disasm")]
+use heh::disasm::Arch;
+use heh::export;
+use heh::symbol;
 
 const ABOUT: &str = "
 A HEx Helper to edit bytes by the nibble.
@@ -33,21 +45,90 @@ Like GHex, you cannot create files with heh, only modify them.
 Terminal UI Commands:
     ALT=                Increase the stream length by 1
     ALT-                Decrease the stream length by 1
+    ALT.                Jump to the next byte that differs from the one at the cursor
+    ALT,                Jump to the previous byte that differs from the one at the cursor
+    ALT0                Jump to the next zero byte
+    ALT1                Jump to the next non-zero byte
+    ALTa                Append a file to the end of the buffer
+    ALTShift+a          Append a number of bytes to the end of the buffer
+    ALTb                Toggle a bookmark at the cursor
+    ALTc                Write a checksum of the selection
+    ALTd                Hash the selection with CRC32, MD5, or SHA-256
+    ALTe                Paste base64 from the clipboard at the cursor
+    ALTf                Replace search matches with bytes of the same length
+    ALTg                Color bytes by value like a heat map, or by character
+    ALTh                Write a hexdump of the selection to <file>.hexdump
+    ALTi                Insert a number of bytes at the cursor
+    ALTj                Follow the pointer at the cursor (see --pointer-size)
+    ALTk                Go back to where the last pointer was followed from
+    ALTl                Copy the address of the cursor
+    ALTm                List the symbols loaded with --symbols and jump to one
+    ALTn                Jump to the next bookmark
+    ALTo                Fill the selection with a constant byte
+    ALTp                Toggle edit protection of the selection
+    ALTq                Show a histogram of the most common bytes in the selection
+    ALTr                Fill the selection with random bytes
+    ALTs                Split the file at the cursor into two files
+    ALTt                Write a typed value like f32 1.5 at the cursor
+    ALTu                Copy the selection as a Python bytes literal like b'\\x00'
+    ALTv                View the selection in a pager
+    ALTw                Cycle how many bytes left and right move by (1, 2, 4, 8)
+    ALTx                Cycle the address radix (hex, decimal, octal)
+    ALTy                Copy the byte at the cursor as a patch line
+    ALTz                Pin the cursor to show the distance from it, or unpin it
     CNTRLs              Save
-    CNTRLq              Quit
+    CNTRLq              Quit (or q in the hex editor with --vim)
+    CNTRLx              Save every file and quit
+    CNTRLo              Switch to the next file, when several are opened
     CNTRLj              Jump to Byte
+    CNTRLa              Move to the next multiple of an alignment (-N for the previous)
+    CNTRLt              List the strings in the file
+    CNTRLr              Reload the length of the file if another program changed it
+    CNTRLk              Cut off the file from the cursor to the end
+    CNTRLv              Go to the first byte from the cursor that differs from the clipboard
+    CNTRLg              Cycle the encoding of the text editor
+    CNTRLl              List the recent notifications
+    CNTRLy              Show or hide the indices of the columns above the editors
+    CNTRLb              Show or hide the labels below the editors
+    F5                  Start or stop recording a macro of keys
+    F6                  Play the macro back (a count plays it that many times)
+    CNTRL.              Show version and diagnostic info for bug reports
     CNTRLe              Switch Endianness
     CNTRLd              Page Down
     CNTRLu              Page Up
+    CNTRLHome or g      Go to the start of the file
+    CNTRLEnd or G       Go to the end of the file
     CNTRLf or /         Search
     CNTRLn or Enter     Next Search Match
     CNTRLp              Prev Search Match
+    Insert              Toggle between inserting and overwriting bytes
+    Delete              Delete the selection, or the byte at the cursor
+    #<count>            Repeat the next movement, like #10j to go down 10 lines
+    Shift+Arrows        Select bytes from the cursor
+    Tab or Shift+Tab    Jump to the next or previous record set with --record-size
+    ALT+PageDown/Up     Jump to the next or previous page set with --page-size
+    \\xHH                Type a byte in the ASCII editor, like \\x1b or \\\\
 
 Left-clicking on a label will copy the contents to the clipboard.
 Left-clicking on the ASCII or hex table will focus it.
+Left-clicking on the overview bar on the right will jump to that
+part of the file.
+Holding ALT while dragging restarts the selection from the cursor.
+
+Commands can be bound to other keys with a keymap file passed to
+--keymap, where every line is like `save = \"ctrl+w\"` or
+`search = [\"ctrl+f\", \"/\"]`. Lines starting with # are
+ignored, but TOML tables and comments after a value are not
+supported.
+
+With --autosave, unsaved edits are backed up to <file>.heh.bak,
+which heh offers to restore the next time the file is opened if
+it was killed or crashed.
 
 Zooming in and out will change the size of the components.";
 
+// Flags without a value are bools
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Parser)]
 #[command(version, about = ABOUT, long_about = LONG_ABOUT)]
 struct Cli {
@@ -60,28 +141,295 @@ struct Cli {
     )]
     encoding: EncodingOption,
     #[arg(
-        value_parser = parse_hex_or_dec,
+        value_parser = parse_offset,
         long = "offset",
         default_value = "0",
-        help="Read file at offset (indicated by a decimal or hexadecimal number)"
+        help = "Read file at offset (indicated by a decimal or hexadecimal number, or by @name of a \
+                symbol from --symbols)"
+    )]
+    offset: StartOffset,
+    #[arg(
+        value_parser = clap::value_parser!(u16).range(0..=4096),
+        long = "stream-length",
+        default_value = "8",
+        help = "Initial stream length of the binary, octal, and hexadecimal labels (0 to \
+                --max-stream-length)"
+    )]
+    stream_length: u16,
+    #[arg(
+        value_parser = clap::value_parser!(u16).range(1..=4096),
+        long = "max-stream-length",
+        value_name = "BITS",
+        default_value = "64",
+        help = "Most bits that ALT= lengthens the binary, octal, and hexadecimal labels to (up to \
+                4096)"
+    )]
+    max_stream_length: u16,
+    #[arg(
+        value_parser = clap::value_parser!(u16).range(1..),
+        long = "width",
+        help = "Number of bytes to display per line, if it fits in the terminal"
+    )]
+    width: Option<u16>,
+    #[arg(
+        long = "group",
+        value_name = "SIZE",
+        default_value = "8",
+        help = "Number of bytes between the extra spaces in the hex editor (0 to disable)"
+    )]
+    group: u16,
+    #[arg(
+        long = "annotations",
+        help = "File of labeled byte ranges to highlight, one `offset length color label` per line"
+    )]
+    annotations: Option<String>,
+    #[arg(
+        long = "symbols",
+        help = "JSON file mapping names to offsets, like {\"main\": \"0x1040\"}"
+    )]
+    symbols: Option<String>,
+    #[arg(long = "diff", help = "File to compare with, displayed next to the hex editor")]
+    diff: Option<PathBuf>,
+    #[arg(
+        value_enum,
+        long = "multibyte-edit",
+        default_value = "replace",
+        help = "What typing over part of a multibyte UTF-8 character does"
+    )]
+    multibyte_edit: MultibyteEditOption,
+    #[arg(
+        value_enum,
+        long = "hex-entry",
+        default_value = "nibble",
+        help = "Whether hex digits are written as they are typed or a whole byte at a time"
+    )]
+    hex_entry: HexEntryOption,
+    #[arg(
+        value_parser = parse_hex_or_dec,
+        long = "base",
+        default_value = "0",
+        help = "Address of the first byte, added to the displayed addresses (decimal or hexadecimal)"
+    )]
+    base: usize,
+    #[arg(
+        value_enum,
+        long = "address-radix",
+        default_value = "hex",
+        help = "Base that addresses are displayed in"
+    )]
+    address_radix: AddressRadixOption,
+    #[arg(
+        long = "labels",
+        value_delimiter = ',',
+        help = "Comma separated labels to display, e.g. `signed16,float32,offset` (default: all)"
+    )]
+    labels: Vec<String>,
+    #[arg(
+        value_parser = clap::value_parser!(u16).range(1..),
+        long = "scroll-lines",
+        default_value = "1",
+        help = "Number of lines to scroll by with the mouse wheel"
+    )]
+    scroll_lines: u16,
+    #[arg(
+        long = "scrolloff",
+        value_name = "LINES",
+        default_value = "0",
+        help = "Number of lines to keep above and below the cursor when scrolling, like in vim"
+    )]
+    scrolloff: u16,
+    #[arg(
+        value_parser = clap::value_parser!(u32).range(1..),
+        long = "entropy-window",
+        value_name = "BYTES",
+        default_value = "256",
+        help = "Number of bytes from the cursor that the Entropy label is computed over"
     )]
-    offset: usize,
+    entropy_window: u32,
+    #[arg(
+        long = "page-size",
+        value_parser = parse_page_size,
+        default_value = "0x1000",
+        help = "Size of the memory pages that the Page label counts and ALT+PageUp or \
+                ALT+PageDown move between (decimal or hexadecimal)"
+    )]
+    page_size: usize,
+    #[arg(
+        value_parser = clap::value_parser!(u8).range(1..=8),
+        long = "pointer-size",
+        default_value = "4",
+        help = "Number of bytes in the pointers that ALTj follows (1 to 8)"
+    )]
+    pointer_size: u8,
+    #[arg(
+        value_parser = clap::value_parser!(u16).range(1..),
+        long = "align",
+        visible_alias = "record-size",
+        value_name = "BYTES",
+        help = "Size of the records whose starts are highlighted and jumped between with Tab \
+                [default: Tab jumps a line]"
+    )]
+    align: Option<u16>,
+    #[arg(
+        long = "keymap",
+        help = "File of keys for commands, with a line like `save = \"ctrl+w\"` for each command"
+    )]
+    keymap: Option<PathBuf>,
+    #[arg(
+        long = "theme",
+        help = "File of colors, with a line like `ascii = \"blue\"` for each color"
+    )]
+    theme: Option<PathBuf>,
+    #[arg(
+        value_parser = clap::value_parser!(u64).range(1..),
+        long = "autosave",
+        value_name = "SECONDS",
+        help = "Back up edits to <file>.heh.bak every number of seconds, and offer to restore the \
+                backup the next time the file is opened if heh didn't exit normally"
+    )]
+    autosave: Option<u64>,
+    #[arg(
+        long = "notification-timeout",
+        value_name = "SECONDS",
+        help = "Keep notifications on the screen for a number of seconds instead of clearing them \
+                on the next key"
+    )]
+    notification_timeout: Option<u64>,
+    #[arg(
+        long = "no-confirm-quit",
+        help = "Quit without asking to discard unsaved changes (they are lost)"
+    )]
+    no_confirm_quit: bool,
+    #[arg(long = "vim", help = "Also quit with a bare q in the hex editor, like in vim")]
+    vim: bool,
+    #[arg(long = "wrap", help = "Wrap left and right around the ends of the file")]
+    wrap: bool,
+    #[arg(long = "ruler", help = "Show the indices of the columns above the editors (CNTRLy)")]
+    ruler: bool,
+    #[arg(long = "no-labels", help = "Hide the labels below the editors at the start (CNTRLb)")]
+    no_labels: bool,
+    #[arg(
+        value_enum,
+        long = "export",
+        value_name = "FORMAT",
+        help = "Print the file in a format to stdout instead of editing it"
+    )]
+    export: Option<ExportOption>,
+    #[cfg(feature = "random")]
+    #[arg(long = "seed", help = "Seed for filling the selection with random bytes (ALTr)")]
+    seed: Option<u64>,
+    #[cfg(feature = "disasm")]
+    #[arg(
+        value_enum,
+        long = "arch",
+        help = "Architecture to disassemble the instruction at the cursor as, in a Disasm label"
+    )]
+    arch: Option<ArchOption>,
 
     // Positional argument.
-    #[arg(help = "File to open")]
-    file: String,
+    #[arg(required = true, help = "Files to open, which CNTRLo switches between")]
+    files: Vec<String>,
 }
 
-/// Opens the specified file, creates a new application and runs it!
+/// Opens the specified files, creates a new application and runs it!
 fn main() -> Result<(), Box<dyn Error>> {
+    let cli = Cli::parse();
+    let (path, other_paths) = cli.files.split_first().expect("A file is required");
+    if let Some(format) = cli.export {
+        if !other_paths.is_empty() {
+            eprintln!("Only one file can be exported at a time.");
+            process::exit(1);
+        }
+        return export_file(path, format);
+    }
+
+    if cli.stream_length > cli.max_stream_length {
+        eprintln!("The stream length can't be more than --max-stream-length.");
+        process::exit(1);
+    }
     if !io::stdout().is_tty() {
         eprintln!("Stdout is not a TTY device.");
         process::exit(1);
     }
-
-    let cli = Cli::parse();
-    let file = OpenOptions::new().read(true).write(true).open(cli.file)?;
-    let mut app = Application::new(file, cli.encoding.into(), cli.offset)?;
+    let open = |path: &String| {
+        OpenOptions::new().read(true).write(true).open(path).unwrap_or_else(|e| {
+            eprintln!("Can't open {path}: {e}");
+            process::exit(1);
+        })
+    };
+    let file = open(path);
+    let symbols = match cli.symbols {
+        Some(path) => fs::read_to_string(&path)
+            .map_err(|e| format!("Can't read {path}: {e}"))
+            .and_then(|text| symbol::parse(&text))
+            .unwrap_or_else(|e| {
+                eprintln!("Invalid symbols file: {e}");
+                process::exit(1);
+            }),
+        None => Vec::new(),
+    };
+    let offset = match cli.offset {
+        StartOffset::Offset(offset) => offset,
+        StartOffset::Symbol(name) => symbol::lookup(&symbols, &name).unwrap_or_else(|| {
+            eprintln!("Unknown symbol: {name} (symbols are loaded with --symbols)");
+            process::exit(1);
+        }),
+    };
+    let options = Options {
+        stream_length: cli.stream_length.into(),
+        max_stream_length: cli.max_stream_length.into(),
+        bytes_per_line: cli.width.map(usize::from),
+        group_size: cli.group.into(),
+        annotations: match cli.annotations {
+            Some(path) => fs::read_to_string(&path)
+                .map_err(|e| format!("Can't read {path}: {e}"))
+                .and_then(|text| annotation::parse(&text))
+                .unwrap_or_else(|e| {
+                    eprintln!("Invalid annotations file: {e}");
+                    process::exit(1);
+                }),
+            None => Vec::new(),
+        },
+        symbols,
+        diff: cli.diff,
+        path: Some(path.into()),
+        multibyte_edit: cli.multibyte_edit.into(),
+        hex_entry: cli.hex_entry.into(),
+        base: cli.base,
+        address_radix: cli.address_radix.into(),
+        labels: cli.labels,
+        scroll_lines: cli.scroll_lines.into(),
+        scrolloff: cli.scrolloff.into(),
+        entropy_window: cli.entropy_window as usize,
+        page_size: cli.page_size,
+        pointer_size: cli.pointer_size.into(),
+        alignment: cli.align.map(usize::from),
+        keymap: cli.keymap,
+        theme: cli.theme,
+        autosave: cli.autosave.map(Duration::from_secs),
+        notification_timeout: cli.notification_timeout.map(Duration::from_secs),
+        confirm_quit: !cli.no_confirm_quit,
+        vim: cli.vim,
+        wrap: cli.wrap,
+        ruler: cli.ruler,
+        hide_labels: cli.no_labels,
+        #[cfg(feature = "random")]
+        seed: cli.seed,
+        #[cfg(feature = "disasm")]
+        arch: cli.arch.map(Into::into),
+    };
+    let mut app = Application::with_options(file, cli.encoding.into(), offset, &options)
+        .unwrap_or_else(|e| {
+            eprintln!("{e}");
+            process::exit(1);
+        });
+    for path in other_paths {
+        let options = Options { path: Some(path.into()), ..options.clone() };
+        app.add_file(open(path), &options).unwrap_or_else(|e| {
+            eprintln!("Can't open {path}: {e}");
+            process::exit(1);
+        });
+    }
     app.run()?;
 
     Ok(())
@@ -91,6 +439,7 @@ fn main() -> Result<(), Box<dyn Error>> {
 pub enum EncodingOption {
     Ascii,
     Utf8,
+    Latin1,
 }
 
 impl From<EncodingOption> for Encoding {
@@ -98,10 +447,135 @@ impl From<EncodingOption> for Encoding {
         match encoding {
             EncodingOption::Ascii => Encoding::Ascii,
             EncodingOption::Utf8 => Encoding::Utf8,
+            EncodingOption::Latin1 => Encoding::Latin1,
         }
     }
 }
 
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MultibyteEditOption {
+    /// Replace the whole character with the typed one
+    Replace,
+    /// Refuse the edit with a warning
+    Warn,
+}
+
+impl From<MultibyteEditOption> for MultibyteEdit {
+    fn from(multibyte_edit: MultibyteEditOption) -> Self {
+        match multibyte_edit {
+            MultibyteEditOption::Replace => MultibyteEdit::Replace,
+            MultibyteEditOption::Warn => MultibyteEdit::Warn,
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HexEntryOption {
+    /// Write every digit as it is typed
+    Nibble,
+    /// Hold the first digit until the second is typed or Enter is pressed
+    Byte,
+}
+
+impl From<HexEntryOption> for HexEntry {
+    fn from(hex_entry: HexEntryOption) -> Self {
+        match hex_entry {
+            HexEntryOption::Nibble => HexEntry::Nibble,
+            HexEntryOption::Byte => HexEntry::Byte,
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AddressRadixOption {
+    Hex,
+    Dec,
+    Oct,
+}
+
+impl From<AddressRadixOption> for AddressRadix {
+    fn from(radix: AddressRadixOption) -> Self {
+        match radix {
+            AddressRadixOption::Hex => AddressRadix::Hex,
+            AddressRadixOption::Dec => AddressRadix::Dec,
+            AddressRadixOption::Oct => AddressRadix::Oct,
+        }
+    }
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExportOption {
+    Hexdump,
+    CArray,
+    Base64,
+    Python,
+}
+
+impl From<ExportOption> for export::Format {
+    fn from(format: ExportOption) -> Self {
+        match format {
+            ExportOption::Hexdump => export::Format::Hexdump,
+            ExportOption::CArray => export::Format::CArray,
+            ExportOption::Base64 => export::Format::Base64,
+            ExportOption::Python => export::Format::Python,
+        }
+    }
+}
+
+#[cfg(feature = "disasm")]
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ArchOption {
+    X86,
+    #[value(name = "x86-64")]
+    X86_64,
+    Arm,
+    Arm64,
+    #[value(name = "riscv32")]
+    RiscV32,
+    #[value(name = "riscv64")]
+    RiscV64,
+}
+
+#[cfg(feature = "disasm")]
+impl From<ArchOption> for Arch {
+    fn from(arch: ArchOption) -> Self {
+        match arch {
+            ArchOption::X86 => Arch::X86,
+            ArchOption::X86_64 => Arch::X86_64,
+            ArchOption::Arm => Arch::Arm,
+            ArchOption::Arm64 => Arch::Arm64,
+            ArchOption::RiscV32 => Arch::RiscV32,
+            ArchOption::RiscV64 => Arch::RiscV64,
+        }
+    }
+}
+
+/// Where the file is opened, either at an offset or at a symbol loaded with `--symbols`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum StartOffset {
+    Offset(usize),
+    Symbol(String),
+}
+
+/// Prints the file in the format for `--export`, without opening the editor.
+fn export_file(path: &str, format: ExportOption) -> Result<(), Box<dyn Error>> {
+    let contents = fs::read(path).unwrap_or_else(|e| {
+        eprintln!("Can't read {path}: {e}");
+        process::exit(1);
+    });
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    export::write(&mut out, &contents, format.into())?;
+    out.flush()?;
+    Ok(())
+}
+
+fn parse_offset(arg: &str) -> Result<StartOffset, String> {
+    match arg.strip_prefix('@') {
+        Some(name) => Ok(StartOffset::Symbol(name.to_string())),
+        None => parse_hex_or_dec(arg).map(StartOffset::Offset),
+    }
+}
+
 fn parse_hex_or_dec(arg: &str) -> Result<usize, String> {
     if let Some(stripped) = arg.strip_prefix("0x") {
         usize::from_str_radix(stripped, 16).map_err(|e| format!("Invalid hexadecimal number: {e}"))
@@ -109,3 +583,10 @@ fn parse_hex_or_dec(arg: &str) -> Result<usize, String> {
         arg.parse().map_err(|e| format!("Invalid decimal number: {e}"))
     }
 }
+
+fn parse_page_size(arg: &str) -> Result<usize, String> {
+    match parse_hex_or_dec(arg)? {
+        0 => Err(String::from("The page size can't be 0")),
+        size => Ok(size),
+    }
+}
@@ -0,0 +1,141 @@
+//! Byte patterns with bits that don't matter, which are searched for with `bits:` search terms like
+//! `bits:1010????` or hexadecimal search terms with `?` nibbles like `DE ?D`.
+
+/// A sequence of bytes where only the bits that are set in the mask have to match.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct Pattern {
+    bytes: Vec<u8>,
+    mask: Vec<u8>,
+}
+
+impl Pattern {
+    /// Creates a pattern that only matches exactly these bytes.
+    pub(crate) fn exact(bytes: &[u8]) -> Self {
+        Self { bytes: bytes.to_vec(), mask: vec![0xFF; bytes.len()] }
+    }
+
+    /// Parses a pattern of bits, where `?` matches either bit. Whitespace is ignored.
+    ///
+    /// # Errors
+    ///
+    /// This errors when there is something other than `0`, `1`, or `?`, or when the bits don't
+    /// make up whole bytes.
+    pub(crate) fn from_bits(text: &str) -> Result<Self, String> {
+        Self::from_digits(text, 1)
+    }
+
+    /// Parses a pattern of hexadecimal digits, where `?` matches any nibble. Whitespace is
+    /// ignored.
+    ///
+    /// # Errors
+    ///
+    /// This errors when there is something other than a hexadecimal digit or `?`, or when the
+    /// digits don't make up whole bytes.
+    pub(crate) fn from_hex(text: &str) -> Result<Self, String> {
+        Self::from_digits(text, 4)
+    }
+
+    /// Parses digits of `bits` bits each, and `?` for digits that don't matter.
+    fn from_digits(text: &str, bits: u32) -> Result<Self, String> {
+        let digits: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let per_byte = (8 / bits) as usize;
+        if digits.is_empty() || !digits.len().is_multiple_of(per_byte) {
+            return Err(format!("expected a multiple of {per_byte} digits"));
+        }
+        let mut pattern = Self { bytes: Vec::new(), mask: Vec::new() };
+        for byte_digits in digits.chunks(per_byte) {
+            let (mut byte, mut mask) = (0_u32, 0_u32);
+            for &c in byte_digits {
+                byte <<= bits;
+                mask <<= bits;
+                if c != '?' {
+                    let digit =
+                        c.to_digit(1 << bits).ok_or_else(|| format!("invalid digit {c}"))?;
+                    byte |= digit;
+                    mask |= (1 << bits) - 1;
+                }
+            }
+            // A byte is made of 8 bits, so these fit
+            #[allow(clippy::cast_possible_truncation)]
+            {
+                pattern.bytes.push(byte as u8);
+                pattern.mask.push(mask as u8);
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// Returns the number of bytes that the pattern matches.
+    pub(crate) fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns whether the bytes match the pattern.
+    pub(crate) fn matches(&self, bytes: &[u8]) -> bool {
+        bytes.len() == self.bytes.len()
+            && bytes.iter().zip(&self.bytes).zip(&self.mask).all(|((b, p), m)| b & m == p & m)
+    }
+
+    /// Returns the index of the first byte that doesn't match the pattern, comparing as many
+    /// bytes as both have.
+    pub(crate) fn first_mismatch(&self, bytes: &[u8]) -> Option<usize> {
+        bytes.iter().zip(&self.bytes).zip(&self.mask).position(|((b, p), m)| b & m != p & m)
+    }
+
+    /// Formats the byte of the pattern at the index as hexadecimal, with `?` for the nibbles that
+    /// don't have to match in full.
+    pub(crate) fn hex_byte(&self, index: usize) -> String {
+        let (byte, mask) = (self.bytes[index], self.mask[index]);
+        [(byte >> 4, mask >> 4), (byte & 0xF, mask & 0xF)]
+            .iter()
+            .map(
+                |&(nibble, mask)| {
+                    if mask == 0xF {
+                        format!("{nibble:X}")
+                    } else {
+                        String::from("?")
+                    }
+                },
+            )
+            .collect()
+    }
+
+    /// Finds the offset and length of every match of the pattern in `haystack`.
+    pub(crate) fn find_all(&self, haystack: &[u8]) -> Vec<(usize, usize)> {
+        haystack
+            .windows(self.bytes.len())
+            .enumerate()
+            .filter_map(|(idx, w)| self.matches(w).then_some((idx, self.bytes.len())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pattern;
+
+    #[test]
+    fn test_pattern() {
+        let bits = Pattern::from_bits("1010????").unwrap();
+        assert!(bits.matches(&[0xA0]));
+        assert!(bits.matches(&[0xAF]));
+        assert!(!bits.matches(&[0xB0]));
+        assert_eq!(bits.find_all(&[0xA3, 0x0A, 0xAA]), [(0, 1), (2, 1)]);
+
+        let hex = Pattern::from_hex("DE ?D").unwrap();
+        assert_eq!(hex, Pattern { bytes: vec![0xDE, 0x0D], mask: vec![0xFF, 0x0F] });
+        assert_eq!(hex.find_all(&[0xDE, 0xDE, 0x3D, 0xDE]), [(1, 2)]);
+
+        assert!(Pattern::from_bits("1010").is_err());
+        assert!(Pattern::from_bits("1010102?").is_err());
+        assert!(Pattern::from_hex("DG").is_err());
+        assert!(Pattern::from_hex("").is_err());
+
+        assert_eq!(Pattern::exact(b"AB").find_all(b"ABAB"), [(0, 2), (2, 2)]);
+
+        assert_eq!(hex.first_mismatch(&[0xDE, 0x3D]), None);
+        assert_eq!(hex.first_mismatch(&[0xDE, 0x3E]), Some(1));
+        assert_eq!(hex.first_mismatch(&[0xDE]), None);
+        assert_eq!((hex.hex_byte(0), hex.hex_byte(1)), (String::from("DE"), String::from("?D")));
+    }
+}
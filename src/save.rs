@@ -0,0 +1,122 @@
+//! Writes the contents back to their file. The contents are written to a temporary file next to
+//! it that is then renamed over it, so that a save that is interrupted can't leave the file half
+//! written.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Seek, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+/// Replaces the file at `path` with the contents, keeping its permissions, and returns the new
+/// file opened for reading and writing.
+///
+/// If `path` is a symlink, the file it leads to is replaced instead of the link. If no temporary
+/// file can be created next to the file, like in a directory that can't be written to, or the
+/// temporary file can't be renamed over it, like on another device or over a file that is open on
+/// Windows, the contents are written into the file directly instead.
+///
+/// # Errors
+///
+/// This errors when the file or the temporary file can't be written.
+pub(crate) fn replace(path: &Path, contents: &[u8]) -> io::Result<File> {
+    let target = fs::canonicalize(path)?;
+    let metadata = fs::metadata(&target)?;
+    let temp = temp_path_for(&target);
+    let Ok(mut temp_file) = OpenOptions::new().write(true).create_new(true).open(&temp) else {
+        return write_in_place(&target, contents);
+    };
+    let written = temp_file
+        .write_all(contents)
+        .and_then(|()| temp_file.sync_all())
+        .and_then(|()| fs::set_permissions(&temp, metadata.permissions()));
+    drop(temp_file);
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+    // Keeping the owner fails unless heh runs as root, in which case it matters most
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let _ = std::os::unix::fs::chown(&temp, Some(metadata.uid()), Some(metadata.gid()));
+    }
+
+    if fs::rename(&temp, &target).is_ok() {
+        return OpenOptions::new().read(true).write(true).open(&target);
+    }
+    let _ = fs::remove_file(&temp);
+    write_in_place(&target, contents)
+}
+
+/// Writes the contents over the start of the file at `path` and cuts it to their length.
+fn write_in_place(path: &Path, contents: &[u8]) -> io::Result<File> {
+    let mut file = OpenOptions::new().read(true).write(true).open(path)?;
+    overwrite(&mut file, contents)?;
+    Ok(file)
+}
+
+/// Writes the contents over the start of the file and cuts it to their length. This is used for
+/// files that were opened without a path.
+///
+/// # Errors
+///
+/// This errors when the file can't be written.
+pub(crate) fn overwrite(file: &mut File, contents: &[u8]) -> io::Result<()> {
+    file.rewind()?;
+    file.write_all(contents)?;
+    file.set_len(contents.len() as u64)
+}
+
+/// Returns the path of the temporary file that the file at `path` is written to before it is
+/// replaced, which is hidden next to it.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_owned();
+    name.push(format!(".heh-save-{}", process::id()));
+    let mut hidden = std::ffi::OsString::from(".");
+    hidden.push(name);
+    path.with_file_name(hidden)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_replace() {
+        let dir = env::temp_dir().join(format!("heh-save-test-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("file");
+        fs::write(&path, b"hello world").unwrap();
+        let mut file = replace(&path, b"bye").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"bye");
+        assert_eq!(file.metadata().unwrap().len(), 3);
+
+        // Nothing is left next to the file, and the returned file can be written to
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        overwrite(&mut file, b"again").unwrap();
+        assert_eq!(fs::read(&path).unwrap(), b"again");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{symlink, PermissionsExt};
+
+            // The permissions are kept
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+            replace(&path, b"private").unwrap();
+            let mode = fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+
+            // A symlink is followed instead of being replaced by a file
+            let link = dir.join("link");
+            symlink(&path, &link).unwrap();
+            replace(&link, b"linked").unwrap();
+            assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+            assert_eq!(fs::read(&path).unwrap(), b"linked");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
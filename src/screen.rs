@@ -3,7 +3,9 @@
 use std::{
     cmp,
     error::Error,
+    fmt::Write as _,
     io::{self, Stdout},
+    ops::Range,
     rc::Rc,
 };
 
@@ -15,7 +17,7 @@ use ratatui::{
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     },
     layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     text::{Line, Span, Text},
     widgets::{Block, Borders, Clear, Paragraph},
     Frame, Terminal,
@@ -23,28 +25,105 @@ use ratatui::{
 
 use crate::chunk::OverlappingChunks;
 use crate::{
-    app::{Data, Nibble},
+    app::{Data, Nibble, ADDRESS_DIGITS},
     decoder::ByteAlignedDecoder,
-    label::{Handler as LabelHandler, LABEL_TITLES},
+    label::Handler as LabelHandler,
     windows::{editor::Editor, KeyHandler, Window},
 };
 
-const COLOR_NULL: Color = Color::DarkGray;
+/// The number of labels displayed side by side in each row.
+const LABEL_COLUMNS: usize = 4;
+/// The height of each label, including its borders.
+const LABEL_HEIGHT: u16 = 3;
+/// The width of the address column when the addresses fit in the digits they are padded to.
+const ADDRESS_WIDTH: u16 = 10;
+/// The width of the overview bar on the right of the editors.
+const OVERVIEW_WIDTH: u16 = 1;
+/// The narrowest terminal where both editors fit one byte per line between their borders, given
+/// that the hex editor takes 3/4 of the width between the address column and the overview bar.
+const MIN_WIDTH: u16 = ADDRESS_WIDTH + OVERVIEW_WIDTH + 9;
+/// The narrowest terminal where both hex editors fit one byte per line when comparing files,
+/// since they split the width that a single hex editor takes.
+const MIN_DIFF_WIDTH: u16 = ADDRESS_WIDTH + OVERVIEW_WIDTH + 14;
+/// The shortest terminal that fits one line of bytes between the borders of the editors and the
+/// status line below them. Labels are left out until there is room for them.
+const MIN_HEIGHT: u16 = 4;
 
 pub struct Handler {
-    pub terminal: Terminal<CrosstermBackend<Stdout>>,
+    /// The terminal that [`Application::run`](crate::app::Application::run) draws on, which is
+    /// left out when the application is only rendered through
+    /// [`render_frame`](crate::app::Application::render_frame).
+    pub terminal: Option<Terminal<CrosstermBackend<Stdout>>>,
     pub terminal_size: Rect,
     pub comp_layouts: ComponentLayouts,
+    /// The number of digits that the address column is laid out to fit.
+    pub(crate) address_digits: usize,
+    /// The number of bytes per line requested by the user, used instead of the computed value
+    /// whenever it fits in the terminal.
+    pub(crate) requested_width: Option<usize>,
+    /// The number of bytes after which an extra space is displayed in the hex editor, or 0 to
+    /// leave the bytes ungrouped.
+    pub(crate) group_size: usize,
+    /// The number of labels displayed, which determines the height of the label grid.
+    pub(crate) label_count: usize,
+    /// Whether a file is being compared with, which is displayed in a second hex editor.
+    pub(crate) diff: bool,
+    /// Whether the indices of the columns are displayed in a row above the editors.
+    pub(crate) ruler: bool,
+    /// Whether the labels are left out, which gives their rows to the editors.
+    pub(crate) hide_labels: bool,
 }
 
 pub struct ComponentLayouts {
     line_numbers: Rect,
     pub(crate) hex: Rect,
     pub(crate) ascii: Rect,
+    diff: Rect,
+    /// The rows of the overview bar, which line up with the rows of bytes in the editors.
+    pub(crate) overview: Rect,
     labels: Rc<Vec<Rect>>,
+    status: Rect,
+    /// The row above the editors that the indices of the columns are displayed in, which is empty
+    /// unless the ruler is shown.
+    ruler: Rect,
     pub(crate) popup: Rect,
     pub(crate) bytes_per_line: usize,
     pub(crate) lines_per_screen: usize,
+    /// The number of bytes between the extra spaces in the hex editor, or 0 if there are none.
+    pub(crate) group_size: usize,
+}
+
+impl ComponentLayouts {
+    /// Returns the number of bytes displayed on each line.
+    #[must_use]
+    pub fn bytes_per_line(&self) -> usize {
+        self.bytes_per_line
+    }
+
+    /// Returns the number of lines of bytes that fit between the borders of the editors.
+    #[must_use]
+    pub fn lines_per_screen(&self) -> usize {
+        self.lines_per_screen
+    }
+
+    /// Moves the address column, the editors, and the overview bar down a row to make room for
+    /// the ruler above them.
+    fn add_ruler(&mut self) {
+        let top = self.line_numbers.y;
+        for rect in [
+            &mut self.line_numbers,
+            &mut self.hex,
+            &mut self.diff,
+            &mut self.ascii,
+            &mut self.overview,
+        ] {
+            // The overview bar starts below the top border, so it already lines up with the row
+            rect.y += 1;
+            rect.height = rect.height.saturating_sub(1);
+        }
+        self.lines_per_screen = self.lines_per_screen.saturating_sub(1).max(1);
+        self.ruler = Rect::new(self.line_numbers.x, top, self.status.width, 1);
+    }
 }
 
 impl Handler {
@@ -53,15 +132,79 @@ impl Handler {
     /// # Errors
     ///
     /// This errors when constructing the terminal or retrieving the terminal size fails.
-    pub fn new() -> Result<Self, Box<dyn Error>> {
+    pub fn new(
+        requested_width: Option<usize>,
+        group_size: usize,
+        label_count: usize,
+        diff: bool,
+    ) -> Result<Self, Box<dyn Error>> {
         let terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
         let size = terminal.size()?;
-        let terminal_size = Rect::new(0, 0, size.width, size.height);
-        Ok(Self {
-            terminal,
+        let mut handler = Self::with_size(
+            Rect::new(0, 0, size.width, size.height),
+            requested_width,
+            group_size,
+            label_count,
+            diff,
+        );
+        handler.terminal = Some(terminal);
+        Ok(handler)
+    }
+    /// Creates a screen handler for an area of the given size without a terminal of its own.
+    #[must_use]
+    pub fn with_size(
+        terminal_size: Rect,
+        requested_width: Option<usize>,
+        group_size: usize,
+        label_count: usize,
+        diff: bool,
+    ) -> Self {
+        Self {
+            terminal: None,
             terminal_size,
-            comp_layouts: Self::calculate_dimensions(terminal_size, &Editor::Hex),
-        })
+            comp_layouts: Self::calculate_dimensions(
+                terminal_size,
+                &Editor::Hex,
+                requested_width,
+                group_size,
+                label_count,
+                diff,
+                ADDRESS_DIGITS,
+            ),
+            address_digits: ADDRESS_DIGITS,
+            requested_width,
+            group_size,
+            label_count,
+            diff,
+            ruler: false,
+            hide_labels: false,
+        }
+    }
+    /// Lays out the components for the area like
+    /// [`calculate_dimensions`](Self::calculate_dimensions), with a row for the ruler above the
+    /// editors if it is shown.
+    pub(crate) fn layout(&self, area: Rect, window: &dyn KeyHandler) -> ComponentLayouts {
+        let mut layouts = Self::calculate_dimensions(
+            area,
+            window,
+            self.requested_width,
+            self.group_size,
+            self.displayed_label_count(),
+            self.diff,
+            self.address_digits,
+        );
+        if self.ruler {
+            layouts.add_ruler();
+        }
+        layouts
+    }
+    /// Returns the number of labels that are laid out, which is none while they are hidden.
+    fn displayed_label_count(&self) -> usize {
+        if self.hide_labels {
+            0
+        } else {
+            self.label_count
+        }
     }
     pub(crate) fn setup() -> Result<(), Box<dyn Error>> {
         enable_raw_mode()?;
@@ -70,8 +213,10 @@ impl Handler {
     }
     pub(crate) fn teardown(&mut self) -> Result<(), Box<dyn Error>> {
         disable_raw_mode()?;
-        execute!(self.terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
-        self.terminal.show_cursor()?;
+        if let Some(terminal) = &mut self.terminal {
+            execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
+            terminal.show_cursor()?;
+        }
         Ok(())
     }
     pub(crate) fn identify_clicked_component(
@@ -86,6 +231,8 @@ impl Handler {
             return Window::Unhandled;
         } else if self.comp_layouts.hex.union(click) == self.comp_layouts.hex {
             return Window::Hex;
+        } else if self.comp_layouts.overview.union(click) == self.comp_layouts.overview {
+            return Window::Overview;
         } else if self.comp_layouts.ascii.union(click) == self.comp_layouts.ascii {
             return Window::Ascii;
         }
@@ -97,67 +244,115 @@ impl Handler {
         Window::Unhandled
     }
 
+    /// The number of label rows that fit at the bottom of a screen of the given height, leaving
+    /// room for at least one line of bytes.
+    fn label_rows(height: u16, label_count: usize) -> usize {
+        let fitting = height.saturating_sub(MIN_HEIGHT) / LABEL_HEIGHT;
+        label_count.div_ceil(LABEL_COLUMNS).min(usize::from(fitting))
+    }
+
     /// Calculates the dimensions of the components that will be continually displayed.
     ///
-    /// This includes the editors, labels, and address table.
-    pub fn calculate_dimensions(frame: Rect, window: &dyn KeyHandler) -> ComponentLayouts {
+    /// This includes the editors, overview bar, labels, status line, and address table. If `requested_width`
+    /// bytes per line fit in the hex editor, the editors are shrunk to display exactly that many
+    /// bytes per line. Every `group_size` bytes, the hex editor takes an extra column for the space
+    /// that separates the groups. The labels are laid out in a grid that fits `label_count` of them, leaving
+    /// out the rows that don't fit on the screen. When `diff` is set, the hex editor shares its
+    /// width with a second one that displays the file being compared with. The address table is
+    /// widened past [`ADDRESS_WIDTH`] when the addresses take more than `address_digits` digits.
+    pub fn calculate_dimensions(
+        frame: Rect,
+        window: &dyn KeyHandler,
+        requested_width: Option<usize>,
+        group_size: usize,
+        label_count: usize,
+        diff: bool,
+        address_digits: usize,
+    ) -> ComponentLayouts {
+        // The address table fits the digits between its borders, as specified below. Because the
+        // hex editor takes 3 graphemes for every 1 that ASCII takes (each nibble plus a space), we
+        // multiply the editors by those ratios.
+        let address_width =
+            u16::try_from(address_digits + 2).unwrap_or(u16::MAX).max(ADDRESS_WIDTH);
+        let editors_width = frame.width.saturating_sub(address_width + OVERVIEW_WIDTH);
+        let hex_editors = if diff { 2 } else { 1 };
+        let hex_width = editors_width * 3 / 4 / hex_editors;
+        let ascii_width = editors_width - hex_width * hex_editors;
+        let (hex_width, ascii_width) = requested_width
+            .filter(|&width| width > 0)
+            .and_then(|width| {
+                let hex = u16::try_from(hex_row_width(width, group_size) + 2).ok()?;
+                // The ASCII editor is narrower than the hex editor, so its width fits too
+                #[allow(clippy::cast_possible_truncation)]
+                (hex <= hex_width).then_some((hex, width as u16 + 2))
+            })
+            .unwrap_or((hex_width, ascii_width));
+
         // Establish Constraints
+        let label_rows = Self::label_rows(frame.height, label_count);
+        #[allow(clippy::cast_possible_truncation)]
+        let labels_height = label_rows as u16 * LABEL_HEIGHT;
         let sections = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Min(3), Constraint::Length(12)])
+            .constraints([
+                Constraint::Min(MIN_HEIGHT - 1),
+                Constraint::Length(labels_height),
+                Constraint::Length(1),
+            ])
             .split(frame);
         let editors = Layout::default()
             .direction(Direction::Horizontal)
             .constraints([
-                Constraint::Length(10),
-                // The address table is Length(10) as specified above. Because the hex editor takes
-                // 3 graphemes for every 1 that ASCII takes (each nibble plus a space), we multiply
-                // the editors by those ratios.
-                Constraint::Length((frame.width - 10) * 3 / 4),
-                Constraint::Length((frame.width - 10) / 4 + 1),
+                Constraint::Length(address_width),
+                Constraint::Length(hex_width),
+                Constraint::Length(if diff { hex_width } else { 0 }),
+                Constraint::Length(ascii_width),
+                Constraint::Length(OVERVIEW_WIDTH),
             ])
             .split(sections[0]);
-        let mut labels = Rc::new(Vec::with_capacity(12));
-        let label_columns = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([
-                Constraint::Ratio(1, 4),
-                Constraint::Ratio(1, 4),
-                Constraint::Ratio(1, 4),
-                Constraint::Ratio(1, 4),
-            ])
+        // The labels are laid out row by row, in the order of their titles.
+        let mut labels = Vec::with_capacity(label_rows * LABEL_COLUMNS);
+        let label_row_layouts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Length(LABEL_HEIGHT); label_rows])
             .split(sections[1]);
-        for label in &*label_columns {
-            let column_layout = &mut Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Ratio(1, 4),
-                    Constraint::Ratio(1, 4),
-                    Constraint::Ratio(1, 4),
-                    Constraint::Ratio(1, 4),
-                ])
-                .split(*label);
-
-            if let Some(labels) = Rc::get_mut(&mut labels) {
-                labels.extend_from_slice(column_layout);
-            }
+        for label_row in &*label_row_layouts {
+            let row_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(vec![Constraint::Fill(1); LABEL_COLUMNS])
+                .split(*label_row);
+            labels.extend_from_slice(&row_layout);
         }
+        labels.truncate(label_count.min(label_rows * LABEL_COLUMNS));
 
         // Calculate popup dimensions
         let popup = Self::calculate_popup_dimensions(frame, window);
 
-        // Calculate bytes per line
-        let bytes_per_line = ((editors[1].width - 2) / 3) as usize;
-        let lines_per_screen = (editors[1].height - 2) as usize;
+        // Calculate bytes per line, which is the most bytes whose hex fits between the borders
+        let hex_columns = editors[1].width.saturating_sub(2) as usize;
+        let mut bytes_per_line = (hex_columns / 3).max(1);
+        while bytes_per_line > 1 && hex_row_width(bytes_per_line, group_size) > hex_columns {
+            bytes_per_line -= 1;
+        }
+        let lines_per_screen = editors[1].height.saturating_sub(2).max(1) as usize;
 
         ComponentLayouts {
             line_numbers: editors[0],
             hex: editors[1],
-            ascii: editors[2],
+            diff: editors[2],
+            ascii: editors[3],
+            overview: Rect {
+                y: editors[4].y + 1,
+                height: editors[4].height.saturating_sub(2),
+                ..editors[4]
+            },
             popup,
             bytes_per_line,
             lines_per_screen,
-            labels: labels.to_vec().into(),
+            group_size,
+            labels: labels.into(),
+            status: sections[2],
+            ruler: Rect::default(),
         }
     }
 
@@ -178,15 +373,20 @@ impl Handler {
         app_info: &mut Data,
         bytes_per_line: usize,
         lines_per_screen: usize,
-    ) -> (Text, Text, Text) {
+        group_size: usize,
+    ) -> (Text<'_>, Text<'_>, Text<'_>) {
         let content_lines = app_info.contents.len() / bytes_per_line + 1;
         let start_row = app_info.start_address / bytes_per_line;
 
         // Generate address lines
+        let address_digits = app_info.address_digits();
         let address_text = (0..cmp::min(lines_per_screen, content_lines - start_row))
             .map(|i| {
                 let row_address = app_info.start_address + i * bytes_per_line;
-                let mut span = Span::from(format!("{row_address:08X?}\n"));
+                let address = app_info
+                    .address_radix
+                    .format_row(app_info.address(row_address), address_digits);
+                let mut span = Span::from(format!("{address}\n"));
                 // Highlight the address row that the cursor is in for visibility
                 if (row_address..row_address + bytes_per_line).contains(&app_info.offset) {
                     span.style = span.style.fg(Color::Black).bg(Color::White);
@@ -195,7 +395,7 @@ impl Handler {
             })
             .collect::<Vec<Line>>();
 
-        let hex_text = generate_hex(app_info, bytes_per_line, lines_per_screen);
+        let hex_text = generate_hex(app_info, bytes_per_line, lines_per_screen, group_size);
         let decoded_text = generate_decoded(app_info, bytes_per_line, lines_per_screen);
 
         (address_text.into(), hex_text.into(), decoded_text.into())
@@ -207,19 +407,34 @@ impl Handler {
     pub(crate) fn render(
         &mut self,
         app_info: &mut Data,
-        labels: &LabelHandler,
+        labels: &mut LabelHandler,
         window: &dyn KeyHandler,
     ) -> Result<(), Box<dyn Error>> {
         app_info.contents.compute_new_window(app_info.offset);
 
-        self.terminal.draw(|frame| {
+        let terminal = self.terminal.as_mut().ok_or("There is no terminal to render to")?;
+        terminal.draw(|frame| {
             // We check if we need to recompute the terminal size in the case that the saved off
             // variable differs from the current frame, which can occur when a terminal is resized
             // between an event handling and a rendering.
             let size = frame.area();
-            if size != self.terminal_size {
+            let address_digits = app_info.address_digits();
+            if size != self.terminal_size || address_digits != self.address_digits {
                 self.terminal_size = size;
-                self.comp_layouts = Self::calculate_dimensions(self.terminal_size, window);
+                self.address_digits = address_digits;
+                // The terminal is borrowed, so this lays out like `layout` from the fields
+                self.comp_layouts = Self::calculate_dimensions(
+                    self.terminal_size,
+                    window,
+                    self.requested_width,
+                    self.group_size,
+                    if self.hide_labels { 0 } else { self.label_count },
+                    self.diff,
+                    self.address_digits,
+                );
+                if self.ruler {
+                    self.comp_layouts.add_ruler();
+                }
 
                 // We change the start_address here to ensure that 0 is ALWAYS the first start
                 // address. We round to preventing constant resizing always moving to 0.
@@ -227,6 +442,7 @@ impl Handler {
                     + (self.comp_layouts.bytes_per_line / 2))
                     / self.comp_layouts.bytes_per_line
                     * self.comp_layouts.bytes_per_line;
+                labels.update_position(app_info.offset, self.comp_layouts.bytes_per_line);
             }
 
             Self::render_frame(
@@ -241,6 +457,21 @@ impl Handler {
         Ok(())
     }
 
+    /// Displays the indices of the columns in the ruler row, lined up with the columns of each
+    /// editor.
+    fn render_ruler(frame: &mut Frame, comp_layouts: &ComponentLayouts, diff: bool) {
+        let ruler = |editor: Rect| {
+            Rect::new(editor.x + 1, comp_layouts.ruler.y, editor.width.saturating_sub(2), 1)
+        };
+        let (hex_ruler, ascii_ruler) =
+            generate_ruler(comp_layouts.bytes_per_line, comp_layouts.group_size);
+        frame.render_widget(Paragraph::new(hex_ruler.clone()), ruler(comp_layouts.hex));
+        if diff {
+            frame.render_widget(Paragraph::new(hex_ruler), ruler(comp_layouts.diff));
+        }
+        frame.render_widget(Paragraph::new(ascii_ruler), ruler(comp_layouts.ascii));
+    }
+
     /// Display the addresses, editors, labels, and popups based off of the specifications of
     /// [`ComponentLayouts`], defined by
     /// [`calculate_dimensions`](Self::calculate_dimensions).
@@ -252,8 +483,11 @@ impl Handler {
         window: &dyn KeyHandler,
         comp_layouts: &ComponentLayouts,
     ) {
-        // Check if terminal is large enough
-        if area.width < 50 || area.height < 15 {
+        // Check if terminal is large enough for even the smallest layout
+        // A wider address column leaves less room for the editors
+        let min_width = if app_info.diff.is_some() { MIN_DIFF_WIDTH } else { MIN_WIDTH }
+            + comp_layouts.line_numbers.width.saturating_sub(ADDRESS_WIDTH);
+        if area.width < min_width || area.height < MIN_HEIGHT {
             let dimension_notification = Paragraph::new("Terminal dimensions must be larger!")
                 .block(Block::default())
                 .alignment(Alignment::Center);
@@ -269,10 +503,21 @@ impl Handler {
             return;
         }
 
+        // Render the indices of the columns above the editors
+        if comp_layouts.ruler.height > 0 {
+            Self::render_ruler(frame, comp_layouts, app_info.diff.is_some());
+        }
+
+        // The hex editor names the annotation under the cursor if there is one.
+        let hex_title = match app_info.annotation_at(app_info.offset) {
+            Some(annotation) => format!("Hex ({})", annotation.label),
+            None => String::from("Hex"),
+        };
         let (address_text, hex_text, ascii_text) = Self::generate_text(
             app_info,
             comp_layouts.bytes_per_line,
             comp_layouts.lines_per_screen,
+            comp_layouts.group_size,
         );
 
         // Render Line Numbers
@@ -285,7 +530,7 @@ impl Handler {
         // Render Hex
         frame.render_widget(
             Paragraph::new(hex_text).block(
-                Block::default().borders(Borders::ALL).title("Hex").style(
+                Block::default().borders(Borders::ALL).title(hex_title).style(
                     if window.is_focusing(Window::Hex) {
                         Style::default().fg(Color::Yellow)
                     } else {
@@ -310,31 +555,143 @@ impl Handler {
             comp_layouts.ascii,
         );
 
+        // Render the file being compared with
+        if app_info.diff.is_some() {
+            let diff_text = generate_diff(
+                app_info,
+                comp_layouts.bytes_per_line,
+                comp_layouts.lines_per_screen,
+                comp_layouts.group_size,
+            );
+            frame.render_widget(
+                Paragraph::new(diff_text)
+                    .block(Block::default().borders(Borders::ALL).title("Diff")),
+                comp_layouts.diff,
+            );
+        }
+
+        // Render the overview of the file
+        frame.render_widget(
+            Paragraph::new(generate_overview(
+                app_info,
+                comp_layouts.bytes_per_line,
+                comp_layouts.lines_per_screen,
+                comp_layouts.overview.height,
+            )),
+            comp_layouts.overview,
+        );
+
         // Render Info
-        for (i, label) in comp_layouts.labels.iter().enumerate() {
+        for (&title, label) in labels.titles.iter().zip(comp_layouts.labels.iter()) {
             frame.render_widget(
-                Paragraph::new(labels[LABEL_TITLES[i]].clone())
-                    .block(Block::default().borders(Borders::ALL).title(LABEL_TITLES[i])),
+                Paragraph::new(fit_label(&labels[title], label.width.saturating_sub(2)))
+                    .block(Block::default().borders(Borders::ALL).title(title)),
                 *label,
             );
         }
 
+        // Render Status Line
+        frame.render_widget(
+            Paragraph::new(status_line(app_info, labels))
+                .style(Style::default().fg(Color::Black).bg(Color::White)),
+            comp_layouts.status,
+        );
+
         // Render Popup
         if !window.is_focusing(Window::Hex) && !window.is_focusing(Window::Ascii) {
             frame.render_widget(Clear, comp_layouts.popup);
-            frame.render_widget(window.widget(), comp_layouts.popup);
+            window.render(frame, comp_layouts.popup);
         }
     }
 }
 
+/// Returns the offset at the start of the row of the overview bar, where the rows split the file
+/// evenly.
+pub(crate) fn overview_offset(row: u16, rows: u16, len: usize) -> usize {
+    // The product is too large for a usize in huge files
+    #[allow(clippy::cast_possible_truncation)]
+    let offset = (u128::from(row) * len as u128 / u128::from(rows.max(1))) as usize;
+    offset
+}
+
+/// Draws the overview bar, where each row stands for an equal part of the file. The rows of the
+/// visible part are highlighted, and rows with search matches are shaded by how many they have
+/// compared to the rest.
+fn generate_overview(
+    app_info: &Data,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+    rows: u16,
+) -> Text<'static> {
+    let len = app_info.contents.len();
+    let visible =
+        app_info.start_address..app_info.start_address + bytes_per_line * lines_per_screen;
+    let match_counts: Vec<usize> = (0..rows)
+        .map(|row| {
+            let start = overview_offset(row, rows, len);
+            let end = overview_offset(row + 1, rows, len).max(start + 1);
+            let first = app_info.search_offsets.partition_point(|&o| o < start);
+            let last = app_info.search_offsets.partition_point(|&o| o < end);
+            last - first
+        })
+        .collect();
+    let most = match_counts.iter().max().copied().unwrap_or_default();
+    match_counts
+        .iter()
+        .enumerate()
+        .map(|(row, &count)| {
+            #[allow(clippy::cast_possible_truncation)]
+            let row = row as u16;
+            let start = overview_offset(row, rows, len);
+            let end = overview_offset(row + 1, rows, len).max(start + 1);
+            let symbol = match count {
+                0 => " ",
+                _ if count * 3 <= most => "░",
+                _ if count * 3 <= most * 2 => "▒",
+                _ => "█",
+            };
+            let mut style = Style::default().fg(app_info.theme.search_match);
+            if start < visible.end && visible.start < end {
+                style = style.bg(Color::DarkGray);
+            }
+            Line::from(Span::styled(symbol, style))
+        })
+        .collect::<Vec<Line>>()
+        .into()
+}
+
+/// Describes the file and the cursor for the status line, which unlike notifications stays on
+/// the screen. This includes the endianness that the labels interpret multiple bytes in.
+fn status_line(app_info: &Data, labels: &LabelHandler) -> String {
+    let name = app_info.file_name();
+    let modified = if app_info.dirty { " [modified]" } else { "" };
+    let step = if app_info.step > 1 { format!(" | Step {}", app_info.step) } else { String::new() };
+    format!(
+        " {name}{modified} | {} bytes | {} | {} | {}{step} | Offset {}",
+        app_info.contents.len(),
+        app_info.encoding,
+        labels.endianness,
+        app_info.edit_mode,
+        app_info.address_radix.format_offset(app_info.address(app_info.offset)),
+    )
+}
+
 /// Display hex bytes with correct highlighting and colors by chunking the bytes into rows and
 /// formatting them into hex.
 ///
 /// NOTE: In UTF-8, a character takes up to 4 bytes and thus the encoding can break at the ends of a
 /// chunk. Increasing the chunk size by 3 bytes at both ends before decoding and cropping them of
 /// afterwards solves the issue for the visible parts.
-fn generate_hex(app_info: &Data, bytes_per_line: usize, lines_per_screen: usize) -> Vec<Line> {
+fn generate_hex(
+    app_info: &Data,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+    group_size: usize,
+) -> Vec<Line<'_>> {
+    let theme = &app_info.theme;
     let initial_offset = app_info.start_address.min(3);
+    let matches = visible_search_matches(app_info, bytes_per_line, lines_per_screen);
+    let in_match = |byte_pos: usize| matches.iter().any(|found| found.contains(&byte_pos));
     OverlappingChunks::new(
         &app_info.contents[(app_info.start_address - initial_offset)..],
         bytes_per_line,
@@ -350,66 +707,64 @@ fn generate_hex(app_info: &Data, bytes_per_line: usize, lines_per_screen: usize)
             .take(bytes_per_line)
             .enumerate()
             .flat_map(|(col, (&byte, character))| {
-                // We don't want an extra space at the end of each row.
-                if col < bytes_per_line - 1 {
-                    format!("{byte:02X?} ")
-                } else {
-                    format!("{byte:02X?}")
-                }
-                .chars()
-                .enumerate()
-                .map(|(nibble_pos, c)| {
-                    let byte_pos = app_info.start_address + (row * bytes_per_line) + col;
-                    let mut span =
-                        Span::styled(c.to_string(), Style::default().fg(*character.color()));
-                    let is_cursor = byte_pos == app_info.offset
-                        && ((nibble_pos == 0 && app_info.nibble == Nibble::Beginning)
-                            || (nibble_pos == 1 && app_info.nibble == Nibble::End));
-
-                    // Determine if the specified nibble (or space) should have a
-                    // lighter foreground because it is in the user's dragged range.
-                    // The logic is more complicated for hex because users can select
-                    // a single nibble from a byte.
-                    let mut in_drag = false;
-                    if let Some(drag) = app_info.last_drag {
-                        let drag_nibble = app_info.drag_nibble.unwrap_or(Nibble::End);
-                        if !(drag == app_info.offset && app_info.nibble == drag_nibble) {
-                            let mut start = drag;
-                            let mut end = app_info.offset;
-                            let mut start_nibble = drag_nibble;
-                            let mut end_nibble = app_info.nibble;
-
-                            if app_info.offset < drag {
-                                start = app_info.offset;
-                                end = drag;
-                                start_nibble = app_info.nibble;
-                                end_nibble = drag_nibble;
-                            }
+                format!("{byte:02X?}{}", hex_separator(col, bytes_per_line, group_size))
+                    .chars()
+                    .enumerate()
+                    .map(|(nibble_pos, c)| {
+                        // The extra space between groups is displayed like the space before it.
+                        let nibble_pos = nibble_pos.min(2);
+                        let byte_pos = app_info.start_address + (row * bytes_per_line) + col;
+                        // The first digit of a byte that is being typed is shown in its place.
+                        let pending = app_info
+                            .pending_nibble
+                            .filter(|_| byte_pos == app_info.offset && nibble_pos == 0);
+                        let mut span = Span::styled(
+                            pending.map_or_else(|| c.to_string(), |digit| format!("{digit:X}")),
+                            Style::default().fg(app_info.byte_color(byte, &character)),
+                        );
+                        let is_cursor = byte_pos == app_info.offset
+                            && ((nibble_pos == 0 && app_info.nibble == Nibble::Beginning)
+                                || (nibble_pos == 1 && app_info.nibble == Nibble::End));
 
-                            // The only time the starting byte would not entirely be in
-                            // drag range is when the first nibble is not highlighted.
-                            // Similarly, the last nibble is only partially highlighted
-                            // when the second (and last) nibble is not selected.
-                            if byte_pos == start {
-                                in_drag = !(nibble_pos == 0 && start_nibble == Nibble::End);
-                            }
-                            if byte_pos == end {
-                                in_drag |= !(nibble_pos == 1 && end_nibble == Nibble::Beginning)
-                                    && nibble_pos != 2;
-                            }
-                            if start == end && nibble_pos == 2 {
-                                in_drag = false;
-                            } else if end - start > 1 {
-                                in_drag |= (start + 1..end).contains(&byte_pos);
+                        // Mark where records start, underneath every other highlight.
+                        if nibble_pos < 2 && app_info.is_record_start(byte_pos) {
+                            span.style = span.style.bg(theme.record);
+                        }
+                        // Highlight search matches, including the spaces in between their bytes.
+                        if in_match(byte_pos) && (nibble_pos < 2 || in_match(byte_pos + 1)) {
+                            span.style = span.style.bg(theme.search_match);
+                        } else if let Some(annotation) = app_info.annotation_at(byte_pos) {
+                            // Like matches, annotations include the spaces between their own bytes.
+                            if nibble_pos < 2 || annotation.range.contains(&(byte_pos + 1)) {
+                                span.style = span.style.bg(annotation.color);
                             }
                         }
-                    }
-                    if is_cursor || in_drag {
-                        span.style = span.style.bg(COLOR_NULL);
-                    }
-                    span
-                })
-                .collect::<Vec<Span>>()
+                        if in_hex_drag(app_info, byte_pos, nibble_pos) {
+                            span.style = span.style.bg(theme.selection);
+                        }
+                        // Underline protected bytes, but not the spaces between them.
+                        if nibble_pos < 2 && app_info.is_protected(byte_pos) {
+                            span.style = span.style.add_modifier(Modifier::UNDERLINED);
+                        }
+                        if nibble_pos < 2 && app_info.differs(byte_pos) {
+                            span.style = span.style.fg(theme.diff);
+                        }
+                        if nibble_pos < 2 && app_info.bookmarks.binary_search(&byte_pos).is_ok() {
+                            span.style = span.style.fg(theme.bookmark).add_modifier(Modifier::BOLD);
+                        }
+                        if pending.is_some() {
+                            span.style = span
+                                .style
+                                .fg(theme.cursor)
+                                .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+                        }
+                        // The cursor is drawn last so that it always stands out.
+                        if is_cursor {
+                            span.style = theme.cursor_style(span.style);
+                        }
+                        span
+                    })
+                    .collect::<Vec<Span>>()
             })
             .collect::<Vec<Span>>();
         Line::from(spans)
@@ -417,13 +772,64 @@ fn generate_hex(app_info: &Data, bytes_per_line: usize, lines_per_screen: usize)
     .collect::<Vec<Line>>()
 }
 
+/// Display the hex of the file being compared with, on the same rows as the hex editor so that
+/// both move together. Bytes that differ from the contents are highlighted, and the byte at the
+/// cursor's offset is marked like in the hex editor.
+fn generate_diff(
+    app_info: &Data,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+    group_size: usize,
+) -> Vec<Line<'_>> {
+    let Some(diff) = &app_info.diff else {
+        return Vec::new();
+    };
+    let theme = &app_info.theme;
+    let content_lines = app_info.contents.len() / bytes_per_line + 1;
+    let start_row = app_info.start_address / bytes_per_line;
+    (0..cmp::min(lines_per_screen, content_lines - start_row))
+        .map(|row| {
+            let row_start = app_info.start_address + row * bytes_per_line;
+            let row_end = diff.len().min(row_start + bytes_per_line);
+            let row_bytes = diff.get(row_start..row_end).unwrap_or_default();
+            let spans = row_bytes
+                .iter()
+                .zip(ByteAlignedDecoder::new(row_bytes, app_info.encoding))
+                .enumerate()
+                .flat_map(|(col, (&byte, character))| {
+                    let byte_pos = row_start + col;
+                    let color = if app_info.differs(byte_pos) {
+                        theme.diff
+                    } else {
+                        app_info.byte_color(byte, &character)
+                    };
+                    let mut style = Style::default().fg(color);
+                    if byte_pos == app_info.offset {
+                        style = theme.cursor_style(style);
+                    }
+                    let separator = hex_separator(col, bytes_per_line, group_size);
+                    [Span::styled(format!("{byte:02X}"), style), Span::raw(separator)]
+                })
+                .collect::<Vec<Span>>();
+            Line::from(spans)
+        })
+        .collect()
+}
+
 /// Display decoded bytes with correct highlighting and colors.
 ///
 /// NOTE: In UTF-8, a character takes up to 4 bytes and thus the encoding can break at the ends of a
 /// chunk. Increasing the chunk size by 3 bytes at both ends before decoding and cropping them of
 /// afterwards solves the issue for the visible parts.
-fn generate_decoded(app_info: &Data, bytes_per_line: usize, lines_per_screen: usize) -> Vec<Line> {
+fn generate_decoded(
+    app_info: &Data,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+) -> Vec<Line<'_>> {
+    let theme = &app_info.theme;
     let initial_offset = app_info.start_address.min(3);
+    let matches = visible_search_matches(app_info, bytes_per_line, lines_per_screen);
+    let in_match = |byte_pos: usize| matches.iter().any(|found| found.contains(&byte_pos));
     OverlappingChunks::new(
         &app_info.contents[(app_info.start_address - initial_offset)..],
         bytes_per_line,
@@ -433,23 +839,37 @@ fn generate_decoded(app_info: &Data, bytes_per_line: usize, lines_per_screen: us
     .enumerate()
     .map(|(row, chunk)| {
         Line::from(
-            ByteAlignedDecoder::new(chunk, app_info.encoding)
+            chunk
+                .iter()
+                .zip(ByteAlignedDecoder::new(chunk, app_info.encoding))
                 .skip(initial_offset)
                 .take(bytes_per_line)
                 .enumerate()
-                .map(|(col, character)| {
+                .map(|(col, (&byte, character))| {
                     let byte_pos = app_info.start_address + (row * bytes_per_line) + col;
                     let mut span = Span::styled(
                         character.escape().to_string(),
-                        Style::default().fg(*character.color()),
+                        Style::default().fg(app_info.byte_color(byte, &character)),
                     );
-                    // Highlight the selected byte in the ASCII table
+                    if in_match(byte_pos) {
+                        span.style = span.style.bg(theme.search_match);
+                    }
+                    // Highlight the selected bytes in the ASCII table
                     let last_drag = app_info.last_drag.unwrap_or(app_info.offset);
-                    if byte_pos == app_info.offset
-                        || (app_info.offset..=last_drag).contains(&byte_pos)
+                    if (app_info.offset..=last_drag).contains(&byte_pos)
                         || (last_drag..=app_info.offset).contains(&byte_pos)
                     {
-                        span.style = span.style.bg(COLOR_NULL);
+                        span.style = span.style.bg(theme.selection);
+                    }
+                    if app_info.is_protected(byte_pos) {
+                        span.style = span.style.add_modifier(Modifier::UNDERLINED);
+                    }
+                    if app_info.differs(byte_pos) {
+                        span.style = span.style.fg(theme.diff);
+                    }
+                    // The cursor is drawn last so that it always stands out, like in the hex editor.
+                    if byte_pos == app_info.offset {
+                        span.style = theme.cursor_style(span.style);
                     }
                     span
                 })
@@ -459,6 +879,112 @@ fn generate_decoded(app_info: &Data, bytes_per_line: usize, lines_per_screen: us
     .collect::<Vec<Line>>()
 }
 
+/// Returns the ranges of the search matches that are at least partially on screen.
+fn visible_search_matches(
+    app_info: &Data,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+) -> Vec<Range<usize>> {
+    let start = app_info.start_address;
+    app_info.search_matches_in(start..start + bytes_per_line * lines_per_screen)
+}
+
+/// Generates the rows of column indices displayed above the hex and the ASCII editors. The hex
+/// editor shows the index of each column in hex, lined up with its bytes, and the ASCII editor
+/// shows the last digit of it.
+fn generate_ruler(bytes_per_line: usize, group_size: usize) -> (String, String) {
+    let mut hex = String::new();
+    let mut ascii = String::new();
+    for col in 0..bytes_per_line {
+        let _ =
+            write!(hex, "{:02X}{}", col % 0x100, hex_separator(col, bytes_per_line, group_size));
+        let _ = write!(ascii, "{:X}", col % 0x10);
+    }
+    (hex, ascii)
+}
+
+/// The spaces displayed after the hex of the byte in the given column of a row: one between
+/// bytes, and another after every group of `group_size` bytes, but none at the end of the row.
+fn hex_separator(col: usize, bytes_per_line: usize, group_size: usize) -> &'static str {
+    if col + 1 >= bytes_per_line {
+        ""
+    } else if group_size > 0 && (col + 1).is_multiple_of(group_size) {
+        "  "
+    } else {
+        " "
+    }
+}
+
+/// The number of columns that the hex of `bytes` bytes takes, including the space after each of
+/// them and the extra spaces between their groups of `group_size` bytes.
+pub(crate) fn hex_row_width(bytes: usize, group_size: usize) -> usize {
+    let gaps = bytes.saturating_sub(1).checked_div(group_size).unwrap_or(0);
+    bytes * 3 + gaps
+}
+
+/// Converts a column of a row in the hex editor, counted from the start of the row, to the
+/// column it would be at if the bytes weren't grouped. The extra space after a group is treated
+/// like the space before it, which belongs to the first byte of the next group.
+pub(crate) fn ungrouped_hex_column(column: usize, group_size: usize) -> usize {
+    if group_size == 0 {
+        return column;
+    }
+    let group_width = group_size * 3 + 1;
+    let in_group = (column % group_width).min(group_size * 3 - 1);
+    column / group_width * group_size * 3 + in_group
+}
+
+/// Shortens the text of a label to fit in `width` columns, ending it with an ellipsis when it's
+/// cut off, like a long bit stream of the binary label.
+fn fit_label(text: &str, width: u16) -> String {
+    let width = usize::from(width);
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut fitted: String = text.chars().take(width.saturating_sub(1)).collect();
+    fitted.push('…');
+    fitted
+}
+
+/// Determines if the specified nibble (or space) of the hex editor should have a lighter
+/// foreground because it is in the user's dragged range. The logic is more complicated for hex
+/// because users can select a single nibble from a byte.
+fn in_hex_drag(app_info: &Data, byte_pos: usize, nibble_pos: usize) -> bool {
+    let mut in_drag = false;
+    if let Some(drag) = app_info.last_drag {
+        let drag_nibble = app_info.drag_nibble.unwrap_or(Nibble::End);
+        if !(drag == app_info.offset && app_info.nibble == drag_nibble) {
+            let mut start = drag;
+            let mut end = app_info.offset;
+            let mut start_nibble = drag_nibble;
+            let mut end_nibble = app_info.nibble;
+
+            if app_info.offset < drag {
+                start = app_info.offset;
+                end = drag;
+                start_nibble = app_info.nibble;
+                end_nibble = drag_nibble;
+            }
+
+            // The only time the starting byte would not entirely be in drag range is when the
+            // first nibble is not highlighted. Similarly, the last nibble is only partially
+            // highlighted when the second (and last) nibble is not selected.
+            if byte_pos == start {
+                in_drag = !(nibble_pos == 0 && start_nibble == Nibble::End);
+            }
+            if byte_pos == end {
+                in_drag |= !(nibble_pos == 1 && end_nibble == Nibble::Beginning) && nibble_pos != 2;
+            }
+            if start == end && nibble_pos == 2 {
+                in_drag = false;
+            } else if end - start > 1 {
+                in_drag |= (start + 1..end).contains(&byte_pos);
+            }
+        }
+    }
+    in_drag
+}
+
 /// Generates the dimensions of an x by y popup that is centered in Rect r.
 fn popup_rect((x, y): (u16, u16), r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -489,6 +1015,7 @@ fn popup_rect((x, y): (u16, u16), r: Rect) -> Rect {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::label::LABEL_TITLES;
 
     #[test]
     fn test_calculate_dimensions_no_popup() {
@@ -497,31 +1024,172 @@ mod tests {
 
         // Given a terminal size of 100 x 100, when dimensions are calculated
         let key_handler: Box<dyn KeyHandler> = Box::from(Editor::Ascii);
-        let layout = Handler::calculate_dimensions(Rect::new(0, 0, width, height), &*key_handler);
+        let layout = Handler::calculate_dimensions(
+            Rect::new(0, 0, width, height),
+            &*key_handler,
+            None,
+            0,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
 
         // The "editors" section, which consists of the line number column, Hex input box, and
-        // ASCII input box should have a size of height - 12 (there are 4 labels per column and
-        // each label takes 3 lines; each takes the vertical space alongside these components).
-        assert_eq!(layout.line_numbers.height, height - 12);
-        assert_eq!(layout.hex.height, height - 12);
-        assert_eq!(layout.ascii.height, height - 12);
+        // ASCII input box should have a size of height minus the height of the labels (there are
+        // 4 labels per row and each label takes 3 lines; each takes the vertical space alongside
+        // these components) and of the status line below them.
+        let labels_height = 3 * u16::try_from(LABEL_TITLES.len().div_ceil(4)).unwrap();
+        assert_eq!(layout.line_numbers.height, height - labels_height - 1);
+        assert_eq!(layout.hex.height, height - labels_height - 1);
+        assert_eq!(layout.ascii.height, height - labels_height - 1);
 
-        // The width of the line numbers column is hard coded to 10,
+        // The width of the line numbers column is hard coded to 10, and the overview bar on the
+        // right takes 1 column between the top and bottom borders of the editors,
         assert_eq!(layout.line_numbers.width, 10);
+        assert_eq!(layout.overview, Rect::new(width - 1, 1, 1, height - labels_height - 3));
         // The Hex editor takes up 3/4ths of the remaining horizontal space (rounded down as to not
         // overflow)...
-        assert_eq!(layout.hex.width, (width - 10) * 3 / 4);
+        assert_eq!(layout.hex.width, (width - 11) * 3 / 4);
         // And the ASCII editor takes up the remaining 1/4th. In some instances, the dimensions
-        // are larger than the layout, so instead of asserting (width - 10) / 4 we assert the
+        // are larger than the layout, so instead of asserting (width - 11) / 4 we assert the
         // remaining space.
-        assert_eq!(layout.ascii.width, width - (10 + ((width - 10) * 3 / 4)));
+        assert_eq!(layout.ascii.width, width - (11 + ((width - 11) * 3 / 4)));
 
-        // The remaining space should consist of the labels in a 4 by 4 grid. Since the height
-        // of each label column is hard set to 12, 4 labels in a column should have a width of 3.
+        // The remaining space should consist of the labels in a grid, one for each title, with
+        // 4 labels per row that are each 3 lines tall.
+        assert_eq!(layout.labels.len(), LABEL_TITLES.len());
         for label in &*layout.labels {
             assert_eq!(label.width, width / 4);
             assert_eq!(label.height, 3);
         }
+
+        // The status line takes up the last row.
+        assert_eq!(layout.status, Rect::new(0, height - 1, width, 1));
+    }
+
+    #[test]
+    fn test_calculate_dimensions_requested_width() {
+        let frame = Rect::new(0, 0, 100, 100);
+        let key_handler: Box<dyn KeyHandler> = Box::from(Editor::Ascii);
+
+        // A requested width that fits in the terminal is used as the number of bytes per line...
+        let layout = Handler::calculate_dimensions(
+            frame,
+            &*key_handler,
+            Some(16),
+            0,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
+        assert_eq!(layout.bytes_per_line, 16);
+        assert_eq!(layout.hex.width, 16 * 3 + 2);
+        assert_eq!(layout.ascii.width, 16 + 2);
+
+        // ...but one that doesn't fit falls back to the computed value.
+        let computed = Handler::calculate_dimensions(
+            frame,
+            &*key_handler,
+            None,
+            0,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
+        let layout = Handler::calculate_dimensions(
+            frame,
+            &*key_handler,
+            Some(64),
+            0,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
+        assert_eq!(layout.bytes_per_line, computed.bytes_per_line);
+    }
+
+    #[test]
+    fn test_calculate_dimensions_small_terminal() {
+        let key_handler: Box<dyn KeyHandler> = Box::from(Editor::Ascii);
+
+        // The smallest terminal fits one byte per line in both editors, but no labels...
+        let layout = Handler::calculate_dimensions(
+            Rect::new(0, 0, MIN_WIDTH, MIN_HEIGHT),
+            &*key_handler,
+            None,
+            0,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
+        assert_eq!(layout.bytes_per_line, 1);
+        assert_eq!(layout.lines_per_screen, 1);
+        assert_eq!(layout.ascii.width, 3);
+        assert!(layout.labels.is_empty());
+        assert_eq!(layout.status, Rect::new(0, MIN_HEIGHT - 1, MIN_WIDTH, 1));
+
+        // ...and the label rows are added back as they fit.
+        let layout = Handler::calculate_dimensions(
+            Rect::new(0, 0, MIN_WIDTH, MIN_HEIGHT + 2 * LABEL_HEIGHT),
+            &*key_handler,
+            None,
+            0,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
+        assert_eq!(layout.labels.len(), 2 * LABEL_COLUMNS);
+        assert_eq!(layout.lines_per_screen, 1);
+    }
+
+    #[test]
+    fn test_calculate_dimensions_group_size() {
+        let frame = Rect::new(0, 0, 100, 100);
+        let key_handler: Box<dyn KeyHandler> = Box::from(Editor::Ascii);
+
+        // The extra space between groups of bytes takes a column, so fewer bytes fit per line...
+        let layout = Handler::calculate_dimensions(
+            frame,
+            &*key_handler,
+            None,
+            4,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
+        let hex_columns = usize::from(layout.hex.width - 2);
+        assert_eq!(layout.group_size, 4);
+        assert!(hex_row_width(layout.bytes_per_line, 4) <= hex_columns);
+        assert!(hex_row_width(layout.bytes_per_line + 1, 4) > hex_columns);
+
+        // ...and the hex editor is widened to fit them when a width is requested.
+        let layout = Handler::calculate_dimensions(
+            frame,
+            &*key_handler,
+            Some(16),
+            8,
+            LABEL_TITLES.len(),
+            false,
+            ADDRESS_DIGITS,
+        );
+        assert_eq!(layout.bytes_per_line, 16);
+        assert_eq!(layout.hex.width, 16 * 3 + 1 + 2);
+
+        // Columns in and after the extra space of "00 01  02" count as the first column of 02.
+        assert_eq!(hex_separator(1, 3, 2), "  ");
+        assert_eq!(hex_separator(2, 3, 2), "");
+        assert_eq!(ungrouped_hex_column(4, 2), 4);
+        assert_eq!(ungrouped_hex_column(5, 2), 5);
+        assert_eq!(ungrouped_hex_column(6, 2), 5);
+        assert_eq!(ungrouped_hex_column(7, 2), 6);
+        assert_eq!(ungrouped_hex_column(7, 0), 7);
+    }
+
+    #[test]
+    fn test_fit_label() {
+        assert_eq!(fit_label("0110", 4), "0110");
+        assert_eq!(fit_label("0110100001100101", 8), "0110100…");
+        assert_eq!(fit_label("01", 0), "…");
     }
 
     // TODO: Create a test for asserting the dimension of each popup
@@ -0,0 +1,200 @@
+//! Finds the matches of the search term on a background thread, so that searching a large file
+//! doesn't freeze the screen. The matches are sent back in order as the file is searched.
+
+use std::{cmp, mem, ops::Range, thread};
+
+use crossbeam::channel::{Receiver, Sender, TryRecvError};
+use regex::bytes::Regex;
+
+use crate::{buffer::Snapshot, pattern::Pattern};
+
+/// The number of bytes that are searched before the matches in them are sent back.
+const CHUNK_LEN: usize = 0x10_0000;
+
+/// The offset and length of each match.
+type Matches = Vec<(usize, usize)>;
+
+/// The matches found in part of the contents, and the offset before which every match was found.
+type Found = (Matches, usize);
+
+/// What the contents are searched for.
+pub(crate) enum Matcher {
+    /// Any of several patterns of a fixed length.
+    Patterns(Vec<Pattern>),
+    /// A regular expression, whose matches can be of any length.
+    Regex(Regex),
+}
+
+impl Matcher {
+    /// Searches the contents chunk by chunk, sending the matches of each chunk in order. This
+    /// stops early once nothing receives them anymore.
+    fn search(&self, contents: &[u8], tx: &Sender<Found>) {
+        match self {
+            Self::Patterns(patterns) => {
+                for start in (0..contents.len()).step_by(CHUNK_LEN) {
+                    let end = cmp::min(start + CHUNK_LEN, contents.len());
+                    let found = find_starting_in(patterns, contents, start..end);
+                    if tx.send((found, end)).is_err() {
+                        return;
+                    }
+                }
+            }
+            Self::Regex(regex) => {
+                // A match can span any number of chunks, so these are only sent whenever a match
+                // starts in a later chunk than the one before it.
+                let mut found = Vec::new();
+                let mut next_chunk = CHUNK_LEN;
+                for m in regex.find_iter(contents) {
+                    if m.start() >= next_chunk {
+                        if tx.send((mem::take(&mut found), m.start())).is_err() {
+                            return;
+                        }
+                        next_chunk = m.start() + CHUNK_LEN;
+                    }
+                    found.push((m.start(), m.len()));
+                }
+                let _ = tx.send((found, contents.len()));
+            }
+        }
+    }
+
+    /// Finds the matches that can have changed after the byte at the offset was changed, and
+    /// returns them along with the range of offsets that they can start at. Matches of regular
+    /// expressions can start and end anywhere, so this returns None for them.
+    pub(crate) fn find_around(
+        &self,
+        contents: &[u8],
+        offset: usize,
+    ) -> Option<(Range<usize>, Matches)> {
+        let Self::Patterns(patterns) = self else {
+            return None;
+        };
+        let longest = patterns.iter().map(Pattern::len).max().unwrap_or(1);
+        let starts = (offset + 1).saturating_sub(longest)..offset + 1;
+        Some((starts.clone(), find_starting_in(patterns, contents, starts)))
+    }
+}
+
+/// Finds the offset and length of every match of the patterns that starts in the range, in order.
+fn find_starting_in(patterns: &[Pattern], contents: &[u8], starts: Range<usize>) -> Matches {
+    let mut found: Matches = patterns
+        .iter()
+        .flat_map(|pattern| {
+            // Matches that start in the range can end after it
+            let end = cmp::min(starts.end + pattern.len() - 1, contents.len());
+            pattern.find_all(&contents[starts.start..end])
+        })
+        .map(|(offset, len)| (starts.start + offset, len))
+        .collect();
+    found.sort_unstable();
+    found
+}
+
+/// A search that is running on a background thread. It is cancelled by dropping it.
+pub(crate) struct SearchIndex {
+    /// Receives the matches as they are found.
+    rx: Receiver<Found>,
+    /// The offset before which every match was received.
+    pub(crate) searched: usize,
+    /// The length of the contents that are being searched.
+    len: usize,
+}
+
+impl SearchIndex {
+    /// Starts searching a snapshot of the contents, so that they can be edited in the meantime.
+    pub(crate) fn new(contents: Snapshot, matcher: Matcher) -> Self {
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let len = contents.len();
+        thread::spawn(move || matcher.search(&contents, &tx));
+        Self { rx, searched: 0, len }
+    }
+
+    /// Returns the next matches that were found, waiting for them if `wait` is set.
+    ///
+    /// # Errors
+    ///
+    /// This errors when no matches are ready without waiting, or once every match was returned.
+    pub(crate) fn receive(&mut self, wait: bool) -> Result<Matches, TryRecvError> {
+        let (found, searched) = if wait {
+            self.rx.recv().map_err(|_| TryRecvError::Disconnected)?
+        } else {
+            self.rx.try_recv()?
+        };
+        self.searched = searched;
+        Ok(found)
+    }
+
+    /// Returns how much of the contents was searched, in percent.
+    pub(crate) fn progress(&self) -> usize {
+        (self.searched * 100).checked_div(self.len).unwrap_or(100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::buffer::AsyncBuffer;
+
+    use super::*;
+
+    fn search_all(contents: &[u8], matcher: Matcher) -> Matches {
+        let contents = AsyncBuffer::from_bytes(contents).unwrap();
+        let mut index = SearchIndex::new(contents.snapshot(), matcher);
+        let mut all = Vec::new();
+        while let Ok(found) = index.receive(true) {
+            all.extend(found);
+        }
+        assert_eq!(index.progress(), 100);
+        all
+    }
+
+    #[test]
+    fn test_search_index() {
+        // Matches at the start, across the first chunk boundary, and at the end
+        let mut contents = vec![0; 3 * CHUNK_LEN];
+        for offset in [0, CHUNK_LEN - 1, contents.len() - 2] {
+            contents[offset..offset + 2].copy_from_slice(b"AB");
+        }
+        let patterns = vec![Pattern::exact(b"AB"), Pattern::exact(b"B")];
+        assert_eq!(
+            search_all(&contents, Matcher::Patterns(patterns)),
+            [
+                (0, 2),
+                (1, 1),
+                (CHUNK_LEN - 1, 2),
+                (CHUNK_LEN, 1),
+                (3 * CHUNK_LEN - 2, 2),
+                (3 * CHUNK_LEN - 1, 1)
+            ]
+        );
+
+        let regex = Regex::new("A+B").unwrap();
+        assert_eq!(
+            search_all(&contents, Matcher::Regex(regex)),
+            [(0, 2), (CHUNK_LEN - 1, 2), (3 * CHUNK_LEN - 2, 2)]
+        );
+
+        assert!(search_all(&[], Matcher::Patterns(vec![Pattern::exact(b"A")])).is_empty());
+    }
+
+    #[test]
+    fn test_search_in_background() {
+        let mut contents = AsyncBuffer::from_bytes(&vec![0; 64 * CHUNK_LEN]).unwrap();
+        contents[0] = 1;
+        let matcher = Matcher::Patterns(vec![Pattern::exact(&[1])]);
+        let mut index = SearchIndex::new(contents.snapshot(), matcher);
+
+        // Starting the search doesn't wait for the contents to be searched
+        let _ = index.receive(false);
+        assert!(index.progress() < 100);
+
+        // Editing the contents doesn't change what is being searched
+        contents[1] = 1;
+        contents.insert(0, 1);
+        let mut all = Vec::new();
+        while let Ok(found) = index.receive(true) {
+            all.extend(found);
+        }
+        assert_eq!(all, [(0, 1)]);
+        assert_eq!(&contents[..3], &[1, 1, 1]);
+    }
+}
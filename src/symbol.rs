@@ -0,0 +1,71 @@
+//! Named offsets, like the sections and symbols of an executable, that can be jumped to.
+//!
+//! Symbols are loaded from a JSON object that maps every name to its offset, like
+//! `{"main": 4096, ".text": "0x1000"}`. An offset is either a number, or a string holding a
+//! decimal number or a hexadecimal number preceded with "0x".
+
+use serde_json::Value;
+
+use crate::windows::parse_number;
+
+/// A name for an offset in the file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    pub(crate) name: String,
+    pub(crate) offset: usize,
+}
+
+/// Parses the contents of a symbols file into symbols sorted by their offset.
+///
+/// # Errors
+///
+/// This errors when the file isn't a JSON object, or when the offset of a symbol isn't a valid
+/// number.
+pub fn parse(text: &str) -> Result<Vec<Symbol>, String> {
+    let Value::Object(map) = serde_json::from_str(text).map_err(|e| e.to_string())? else {
+        return Err(String::from("expected an object mapping names to offsets"));
+    };
+    let mut symbols = map
+        .into_iter()
+        .map(|(name, value)| {
+            let offset = match &value {
+                Value::Number(number) => number.as_u64().and_then(|n| usize::try_from(n).ok()),
+                Value::String(string) => parse_number(string).ok(),
+                _ => None,
+            };
+            match offset {
+                Some(offset) => Ok(Symbol { name, offset }),
+                None => Err(format!("invalid offset of {name}: {value}")),
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    symbols.sort_by(|a, b| a.offset.cmp(&b.offset).then_with(|| a.name.cmp(&b.name)));
+    Ok(symbols)
+}
+
+/// Returns the offset of the symbol with the given name.
+#[must_use]
+pub fn lookup(symbols: &[Symbol], name: &str) -> Option<usize> {
+    symbols.iter().find(|symbol| symbol.name == name).map(|symbol| symbol.offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let symbols = parse(r#"{"main": 4096, ".text": "0x1000", "_start": "16"}"#).unwrap();
+        let names: Vec<&str> = symbols.iter().map(|symbol| symbol.name.as_str()).collect();
+        // Symbols at the same offset are sorted by name
+        assert_eq!(names, ["_start", ".text", "main"]);
+        assert_eq!(lookup(&symbols, "main"), Some(4096));
+        assert_eq!(lookup(&symbols, ".text"), Some(0x1000));
+        assert_eq!(lookup(&symbols, "missing"), None);
+
+        assert_eq!(parse(r#"{"main": -1}"#).unwrap_err(), "invalid offset of main: -1");
+        assert_eq!(parse(r#"{"main": "0xzz"}"#).unwrap_err(), "invalid offset of main: \"0xzz\"");
+        assert_eq!(parse("[4096]").unwrap_err(), "expected an object mapping names to offsets");
+        assert!(parse("{").is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! The colors used to display the file contents.
+//!
+//! A theme is loaded from a file where every line has the form `key = "color"`. The color is
+//! either a name like `red`, an RGB value like `#ff8000`, or an index into the terminal's 256
+//! colors. Keys that are left out keep their default color. Empty lines and lines starting with
+//! `#` are ignored.
+
+use ratatui::style::{Color, Style};
+
+use crate::character::Category;
+
+/// The colors of the characters in each [`Category`] and of the highlights in the editors.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    pub(crate) null: Color,
+    pub(crate) ascii: Color,
+    pub(crate) unicode: Color,
+    pub(crate) whitespace: Color,
+    pub(crate) control: Color,
+    pub(crate) fill: Color,
+    pub(crate) unknown: Color,
+    /// The background of the byte under the cursor.
+    pub(crate) cursor: Color,
+    /// The foreground of the byte under the cursor, which sets it apart from the selection.
+    pub(crate) cursor_text: Color,
+    /// The background of the selected bytes.
+    pub(crate) selection: Color,
+    /// The background of search matches.
+    pub(crate) search_match: Color,
+    /// The foreground of bookmarked bytes.
+    pub(crate) bookmark: Color,
+    /// The foreground of bytes that differ from the file opened with `--diff`.
+    pub(crate) diff: Color,
+    /// The background of the first byte of each record set with `--record-size`.
+    pub(crate) record: Color,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            null: Color::DarkGray,
+            ascii: Color::Cyan,
+            unicode: Color::LightCyan,
+            whitespace: Color::Green,
+            control: Color::Magenta,
+            fill: Color::LightCyan,
+            unknown: Color::Yellow,
+            cursor: Color::Gray,
+            cursor_text: Color::Black,
+            selection: Color::DarkGray,
+            search_match: Color::Blue,
+            bookmark: Color::LightMagenta,
+            diff: Color::LightRed,
+            record: Color::Indexed(236),
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the style of the byte under the cursor, based on the style it would otherwise have.
+    pub(crate) fn cursor_style(&self, style: Style) -> Style {
+        style.fg(self.cursor_text).bg(self.cursor)
+    }
+
+    /// Returns the color of a byte in the heat map mode, a shade of gray from dark for 0x00 to
+    /// bright for 0xFF.
+    pub(crate) fn heat_color(byte: u8) -> Color {
+        // The last 20 of the terminal's 256 colors are grays from dark to bright
+        Color::Indexed(236 + byte / 13)
+    }
+
+    /// Returns the color that characters of the category are displayed in.
+    pub(crate) fn color(&self, category: &Category) -> Color {
+        match category {
+            Category::Null => self.null,
+            Category::Ascii => self.ascii,
+            Category::Unicode => self.unicode,
+            Category::Whitespace => self.whitespace,
+            Category::Control => self.control,
+            Category::Fill => self.fill,
+            Category::Unknown => self.unknown,
+        }
+    }
+}
+
+/// Parses the contents of a theme file, starting from the default colors.
+///
+/// # Errors
+///
+/// This errors with the offending line number when a line cannot be parsed or sets an unknown
+/// key.
+pub fn parse(text: &str) -> Result<Theme, String> {
+    let mut theme = Theme::default();
+    for (idx, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        parse_line(&mut theme, line).map_err(|e| format!("Line {}: {e}", idx + 1))?;
+    }
+    Ok(theme)
+}
+
+fn parse_line(theme: &mut Theme, line: &str) -> Result<(), String> {
+    let (key, value) = line.split_once('=').ok_or("expected `key = \"color\"`")?;
+    let value = value.trim();
+    let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).unwrap_or(value);
+    let color = value.parse::<Color>().map_err(|_| format!("unknown color: {value}"))?;
+    let slot = match key.trim() {
+        "null" => &mut theme.null,
+        "ascii" => &mut theme.ascii,
+        "unicode" => &mut theme.unicode,
+        "whitespace" => &mut theme.whitespace,
+        "control" => &mut theme.control,
+        "fill" => &mut theme.fill,
+        "unknown" => &mut theme.unknown,
+        "cursor" => &mut theme.cursor,
+        "cursor_text" => &mut theme.cursor_text,
+        "selection" => &mut theme.selection,
+        "match" => &mut theme.search_match,
+        "bookmark" => &mut theme.bookmark,
+        "diff" => &mut theme.diff,
+        "record" => &mut theme.record,
+        key => return Err(format!("unknown key: {key}")),
+    };
+    *slot = color;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let theme = parse("# light terminals\nascii = \"blue\"\n\ncursor = \"#c0c0c0\"\n").unwrap();
+        assert_eq!(
+            theme,
+            Theme { ascii: Color::Blue, cursor: Color::Rgb(0xc0, 0xc0, 0xc0), ..Theme::default() }
+        );
+
+        assert_eq!(parse("ascii = \"notacolor\"").unwrap_err(), "Line 1: unknown color: notacolor");
+        assert_eq!(parse("\nbackground = red").unwrap_err(), "Line 2: unknown key: background");
+        assert!(parse("ascii").is_err());
+    }
+
+    #[test]
+    fn test_heat_color() {
+        assert_eq!(Theme::heat_color(0x00), Color::Indexed(236));
+        assert_eq!(Theme::heat_color(0x80), Color::Indexed(245));
+        assert_eq!(Theme::heat_color(0xFF), Color::Indexed(255));
+    }
+}
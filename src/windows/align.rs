@@ -0,0 +1,99 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::{Data, Nibble},
+    label::Handler as LabelHandler,
+    screen::Handler as ScreenHandler,
+};
+
+use super::{adjust_offset, parse_number, KeyHandler, PopupOutput, Window};
+
+/// A window that accepts an alignment and moves the cursor to the next address that is a
+/// multiple of it, like the start of the next padded structure.
+///
+/// This can be opened by pressing `CNTRLa`.
+///
+/// The input is either parsed as hexadecimal if it is preceded with "0x", or decimal if not.
+/// Input preceded with "-" moves the cursor back to the previous multiple instead. The cursor
+/// stays where it is if its address is already a multiple.
+#[derive(PartialEq, Eq)]
+pub(crate) struct AlignCursor {
+    pub(crate) input: String,
+}
+
+impl KeyHandler for AlignCursor {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::AlignCursor
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        self.input.push(c);
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.input)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.input.pop();
+    }
+    fn enter(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
+        match self.target(app) {
+            Ok(new_offset) => {
+                app.offset = new_offset;
+                app.nibble = Nibble::Beginning;
+                app.last_drag = None;
+                app.drag_nibble = None;
+                labels.update_all(&app.contents[app.offset..]);
+                adjust_offset(app, display, labels);
+                labels.notification = format!(
+                    "Aligned to {}",
+                    app.address_radix.format_offset(app.address(app.offset))
+                );
+            }
+            Err(e) => labels.notification = e,
+        }
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 3))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
+            Block::default()
+                .title("Align to a Multiple of (- for previous):")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl AlignCursor {
+    pub(crate) fn new() -> Self {
+        Self { input: String::new() }
+    }
+
+    /// Parses the input into the offset of the multiple that the cursor should move to.
+    fn target(&self, app: &Data) -> Result<usize, String> {
+        let (alignment, forward) = match self.input.strip_prefix('-') {
+            Some(alignment) => (alignment, false),
+            None => (self.input.as_str(), true),
+        };
+        let alignment = parse_number(alignment).map_err(|e| format!("Error: {e:?}"))?;
+        if alignment == 0 {
+            return Err(String::from("The alignment must be at least 1!"));
+        }
+        let past_multiple = app.address(app.offset) % alignment;
+        if !forward || past_multiple == 0 {
+            // With a base, the previous multiple can be before the start of the file
+            return app
+                .offset
+                .checked_sub(past_multiple)
+                .ok_or_else(|| format!("There is no multiple of {alignment} before the cursor!"));
+        }
+        app.offset
+            .checked_add(alignment - past_multiple)
+            .filter(|&offset| offset < app.contents.len())
+            .ok_or_else(|| format!("There is no multiple of {alignment} after the cursor!"))
+    }
+}
@@ -0,0 +1,131 @@
+use std::fs;
+
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::{Action, Data},
+    label::Handler as LabelHandler,
+    screen::Handler as ScreenHandler,
+};
+
+use super::{KeyHandler, PopupOutput, Window};
+
+/// Files larger than this (256 MiB) are not appended, since the whole buffer has to fit in
+/// memory.
+const APPEND_LIMIT: u64 = 0x1000_0000;
+
+/// A window that appends the contents of another file to the end of the buffer.
+///
+/// This can be opened by pressing `ALTa`.
+///
+/// The input is the path of the file to append. Appending can be undone in a single step.
+#[derive(PartialEq, Eq)]
+pub(crate) struct Append {
+    pub(crate) input: String,
+}
+
+impl KeyHandler for Append {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Append
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        self.input.push(c);
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.input)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.input.pop();
+    }
+    fn enter(&mut self, app: &mut Data, _: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let path = self.input.trim();
+        match fs::metadata(path) {
+            Ok(metadata) if metadata.len() > APPEND_LIMIT => {
+                labels.notification =
+                    format!("{path} is too large to append ({} bytes)", metadata.len());
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                labels.notification = format!("Can't read {path}: {e}");
+                return;
+            }
+        }
+        let bytes = match fs::read(path) {
+            Ok(bytes) if bytes.is_empty() => {
+                labels.notification = format!("{path} is empty");
+                return;
+            }
+            Ok(bytes) => bytes,
+            Err(e) => {
+                labels.notification = format!("Can't read {path}: {e}");
+                return;
+            }
+        };
+
+        let len = app.contents.len();
+        app.actions.push(Action::Append(len));
+        app.contents.extend(&bytes);
+        app.shift_marks(&(len..len), bytes.len());
+        app.dirty = true;
+        labels.update_all(&app.contents[app.offset..]);
+        labels.notification = format!("Appended {} bytes from {path}", bytes.len());
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 3))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
+            Block::default()
+                .title("Append File:")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Append {
+    pub(crate) fn new() -> Self {
+        Self { input: String::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, process};
+
+    use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::app::{tests::open, Options};
+
+    use super::*;
+
+    #[test]
+    fn test_append() {
+        let (mut app, _) = open(b"head", 0, &Options::default());
+        let path = env::temp_dir().join(format!("heh-append-test-{}", process::id()));
+        fs::write(&path, b" tail").unwrap();
+        let mut append = Append { input: format!(" {} ", path.display()) };
+        append.enter(&mut app.data, &mut app.display, &mut app.labels);
+        assert_eq!(app.labels.notification, format!("Appended 5 bytes from {}", path.display()));
+        assert_eq!(&app.data.contents[..], b"head tail");
+        assert!(app.data.dirty);
+
+        // The whole file is removed again in a single undo
+        let undo = KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL);
+        app.handle_input(&Event::Key(undo)).unwrap();
+        assert_eq!(&app.data.contents[..], b"head");
+
+        fs::write(&path, b"").unwrap();
+        append.enter(&mut app.data, &mut app.display, &mut app.labels);
+        assert_eq!(app.labels.notification, format!("{} is empty", path.display()));
+        fs::remove_file(&path).unwrap();
+        append.enter(&mut app.data, &mut app.display, &mut app.labels);
+        assert!(app.labels.notification.starts_with("Can't read "));
+        assert_eq!(&app.data.contents[..], b"head");
+    }
+}
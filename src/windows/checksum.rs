@@ -0,0 +1,104 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::{Action, Data},
+    checksum::Algorithm,
+    label::{Endianness, Handler as LabelHandler},
+    screen::Handler as ScreenHandler,
+};
+
+use super::{parse_number, KeyHandler, PopupOutput, Window};
+
+/// A window that recomputes a checksum and writes it into the file.
+///
+/// This can be opened by pressing `ALTc`.
+///
+/// The input is the algorithm (`sum8`, `crc16`, or `crc32`), the offset to write the checksum
+/// to, and optionally the width of the checksum in bytes, e.g. `crc32 0x1FC 4`. The checksum is
+/// computed over the selected bytes, or over every byte before the offset if nothing is selected,
+/// and is written with the endianness used by the labels.
+#[derive(PartialEq, Eq)]
+pub(crate) struct Checksum {
+    pub(crate) input: String,
+}
+
+impl KeyHandler for Checksum {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Checksum
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        self.input.push(c);
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.input)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.input.pop();
+    }
+    fn enter(&mut self, app: &mut Data, _: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let (algorithm, destination, width) = match self.parse() {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                labels.notification = e;
+                return;
+            }
+        };
+        if !(1..=8).contains(&width) {
+            labels.notification = String::from("Width must be between 1 and 8");
+            return;
+        }
+        let Some(end) = destination.checked_add(width).filter(|&end| end <= app.contents.len())
+        else {
+            labels.notification = String::from("Invalid range!");
+            return;
+        };
+
+        let range = app.selection().unwrap_or(0..destination);
+        let checksum = algorithm.compute(&app.contents[range]);
+        let bytes = match labels.endianness {
+            Endianness::LittleEndian => checksum.to_le_bytes()[..width].to_vec(),
+            Endianness::BigEndian => checksum.to_be_bytes()[8 - width..].to_vec(),
+        };
+
+        app.actions.push(Action::Overwrite(destination, app.contents[destination..end].to_vec()));
+        app.contents[destination..end].copy_from_slice(&bytes);
+        app.dirty = true;
+        labels.update_all(&app.contents[app.offset..]);
+        labels.notification = format!("Wrote {algorithm} {checksum:#X} at {destination:#X}");
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 3))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
+            Block::default()
+                .title("Checksum (algorithm offset [width]):")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Checksum {
+    pub(crate) fn new() -> Self {
+        Self { input: String::new() }
+    }
+
+    /// Splits the input into the algorithm, destination offset, and width.
+    fn parse(&self) -> Result<(Algorithm, usize, usize), String> {
+        let mut args = self.input.split_whitespace();
+        let algorithm: Algorithm =
+            args.next().ok_or_else(|| String::from("Missing algorithm"))?.parse()?;
+        let destination = parse_number(args.next().ok_or_else(|| String::from("Missing offset"))?)
+            .map_err(|e| format!("Error: {e:?}"))?;
+        let width = args
+            .next()
+            .map_or(Ok(algorithm.width()), parse_number)
+            .map_err(|e| format!("Error: {e:?}"))?;
+        Ok((algorithm, destination, width))
+    }
+}
@@ -1,13 +1,14 @@
-use std::cmp;
+use std::{cmp, ops::Range};
 
 use crate::{
-    app::{Action, Data, Nibble},
+    app::{Action, Data, EditMode, FileEdges, HexEntry, MultibyteEdit, Nibble, PendingEdit},
+    decoder::{utf8_char_range, Encoding},
     label::Handler as LabelHandler,
     screen::Handler as ScreenHandler,
 };
 
 use super::{
-    adjust_offset,
+    adjust_offset, page_offsets,
     search::{perform_search, SearchDirection},
     KeyHandler, Window,
 };
@@ -29,15 +30,22 @@ impl KeyHandler for Editor {
     fn left(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
         app.last_drag = None;
         app.drag_nibble = None;
+        app.discard_pending_nibble();
+        // Stepping by several bytes lands on the first nibble of the hex editor
+        if app.step > 1 {
+            jump(app, display, labels, step_offset(app, app.step, false));
+            app.nibble = Nibble::Beginning;
+            return;
+        }
         match self {
             Self::Ascii => {
-                app.offset = app.offset.saturating_sub(1);
+                app.offset = step_offset(app, 1, false);
                 labels.update_all(&app.contents[app.offset..]);
                 adjust_offset(app, display, labels);
             }
             Self::Hex => {
                 if app.nibble == Nibble::Beginning {
-                    app.offset = app.offset.saturating_sub(1);
+                    app.offset = step_offset(app, 1, false);
                     labels.update_all(&app.contents[app.offset..]);
                     adjust_offset(app, display, labels);
                 }
@@ -48,15 +56,21 @@ impl KeyHandler for Editor {
     fn right(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
         app.last_drag = None;
         app.drag_nibble = None;
+        app.discard_pending_nibble();
+        if app.step > 1 {
+            jump(app, display, labels, step_offset(app, app.step, true));
+            app.nibble = Nibble::Beginning;
+            return;
+        }
         match self {
             Self::Ascii => {
-                app.offset = cmp::min(app.offset.saturating_add(1), app.contents.len() - 1);
+                app.offset = step_offset(app, 1, true);
                 labels.update_all(&app.contents[app.offset..]);
                 adjust_offset(app, display, labels);
             }
             Self::Hex => {
                 if app.nibble == Nibble::End {
-                    app.offset = cmp::min(app.offset.saturating_add(1), app.contents.len() - 1);
+                    app.offset = step_offset(app, 1, true);
                     labels.update_all(&app.contents[app.offset..]);
                     adjust_offset(app, display, labels);
                 }
@@ -67,6 +81,7 @@ impl KeyHandler for Editor {
     fn up(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
         app.last_drag = None;
         app.drag_nibble = None;
+        app.discard_pending_nibble();
         if let Some(new_offset) = app.offset.checked_sub(display.comp_layouts.bytes_per_line) {
             app.offset = new_offset;
             labels.update_all(&app.contents[app.offset..]);
@@ -76,6 +91,7 @@ impl KeyHandler for Editor {
     fn down(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
         app.last_drag = None;
         app.drag_nibble = None;
+        app.discard_pending_nibble();
         if let Some(new_offset) = app.offset.checked_add(display.comp_layouts.bytes_per_line) {
             if new_offset < app.contents.len() {
                 app.offset = new_offset;
@@ -87,6 +103,7 @@ impl KeyHandler for Editor {
     fn home(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
         app.last_drag = None;
         app.drag_nibble = None;
+        app.discard_pending_nibble();
         let bytes_per_line = display.comp_layouts.bytes_per_line;
         app.offset = app.offset / bytes_per_line * bytes_per_line;
         labels.update_all(&app.contents[app.offset..]);
@@ -99,6 +116,7 @@ impl KeyHandler for Editor {
     fn end(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
         app.last_drag = None;
         app.drag_nibble = None;
+        app.discard_pending_nibble();
         let bytes_per_line = display.comp_layouts.bytes_per_line;
         app.offset = cmp::min(
             app.offset + (bytes_per_line - 1 - app.offset % bytes_per_line),
@@ -111,14 +129,26 @@ impl KeyHandler for Editor {
             app.nibble = Nibble::End;
         }
     }
+    fn file_start(
+        &mut self,
+        app: &mut Data,
+        display: &mut ScreenHandler,
+        labels: &mut LabelHandler,
+    ) {
+        jump(app, display, labels, 0);
+        if self.is_focusing(Window::Hex) {
+            app.nibble = Nibble::Beginning;
+        }
+    }
+    fn file_end(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let last_offset = app.contents.len() - 1;
+        jump(app, display, labels, last_offset);
+        if self.is_focusing(Window::Hex) {
+            app.nibble = Nibble::End;
+        }
+    }
     fn page_up(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
-        app.last_drag = None;
-        app.drag_nibble = None;
-        app.offset = app.offset.saturating_sub(
-            display.comp_layouts.bytes_per_line * display.comp_layouts.lines_per_screen,
-        );
-        labels.update_all(&app.contents[app.offset..]);
-        adjust_offset(app, display, labels);
+        page(app, display, labels, false);
     }
     fn page_down(
         &mut self,
@@ -126,16 +156,7 @@ impl KeyHandler for Editor {
         display: &mut ScreenHandler,
         labels: &mut LabelHandler,
     ) {
-        app.last_drag = None;
-        app.drag_nibble = None;
-        app.offset = cmp::min(
-            app.offset.saturating_add(
-                display.comp_layouts.bytes_per_line * display.comp_layouts.lines_per_screen,
-            ),
-            app.contents.len() - 1,
-        );
-        labels.update_all(&app.contents[app.offset..]);
-        adjust_offset(app, display, labels);
+        page(app, display, labels, true);
     }
     fn backspace(
         &mut self,
@@ -143,20 +164,34 @@ impl KeyHandler for Editor {
         display: &mut ScreenHandler,
         labels: &mut LabelHandler,
     ) {
-        if app.offset > 0 {
+        if let Some(range) = app.selection() {
+            delete_selection(app, display, labels, range);
+        } else if app.offset > 0 {
+            if app.intercept_protected_edit(app.offset - 1..app.offset, PendingEdit::Backspace) {
+                return;
+            }
             app.actions.push(Action::Delete(
                 app.offset.saturating_sub(1),
                 app.contents.remove(app.offset - 1),
             ));
             app.offset = app.offset.saturating_sub(1);
+            app.shift_marks(&(app.offset..app.offset + 1), 0);
             labels.update_all(&app.contents[app.offset..]);
             adjust_offset(app, display, labels);
             app.dirty = true;
         }
     }
     fn delete(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
-        if app.contents.len() > 1 {
+        if let Some(range) = app.selection() {
+            delete_selection(app, display, labels, range);
+        } else if app.contents.len() == 1 {
+            labels.notification = String::from("Can't delete the last byte");
+        } else {
+            if app.intercept_protected_edit(app.offset..app.offset + 1, PendingEdit::Delete) {
+                return;
+            }
             app.actions.push(Action::Delete(app.offset, app.contents.remove(app.offset)));
+            app.shift_marks(&(app.offset..app.offset + 1), 0);
             labels.update_all(&app.contents[app.offset..]);
             adjust_offset(app, display, labels);
             app.dirty = true;
@@ -173,23 +208,66 @@ impl KeyHandler for Editor {
         app.drag_nibble = None;
         match *self {
             Self::Ascii => {
-                app.actions.push(Action::CharacterInput(
-                    app.offset,
-                    app.contents[app.offset],
-                    None,
-                ));
-                app.contents[app.offset] = c as u8;
-                app.dirty = true;
-                app.offset = cmp::min(app.offset.saturating_add(1), app.contents.len() - 1);
-                labels.update_all(&app.contents[app.offset..]);
-                adjust_offset(app, display, labels);
+                if let Some(escape) = &mut app.escape {
+                    escape.push(c);
+                    match parse_escape(escape) {
+                        Ok(Some(byte)) => {
+                            app.escape = None;
+                            type_byte(app, display, labels, byte);
+                        }
+                        Ok(None) => labels.notification = format!("\\{escape}"),
+                        Err(e) => {
+                            app.escape = None;
+                            labels.notification = e;
+                        }
+                    }
+                    return;
+                }
+                if c == '\\' {
+                    app.escape = Some(String::new());
+                    labels.notification = String::from("\\");
+                    return;
+                }
+                let multibyte = if app.edit_mode == EditMode::Overwrite
+                    && matches!(app.encoding, Encoding::Utf8)
+                {
+                    utf8_char_range(&app.contents, app.offset)
+                } else {
+                    None
+                };
+                // Every byte of a multibyte character is typed over
+                let edited = multibyte.clone().unwrap_or(app.offset..app.offset + 1);
+                if app.intercept_protected_edit(edited, PendingEdit::Char(c)) {
+                    return;
+                }
+                match multibyte {
+                    Some(range) => replace_multibyte_char(app, display, labels, range, c),
+                    None => write_byte(app, display, labels, c as u8),
+                }
             }
+            Self::Hex if app.hex_entry == HexEntry::Byte => type_hex_digit(app, display, labels, c),
             Self::Hex => {
-                app.actions.push(Action::CharacterInput(
-                    app.offset,
-                    app.contents[app.offset],
-                    Some(app.nibble),
-                ));
+                if c.is_ascii_hexdigit()
+                    && app
+                        .intercept_protected_edit(app.offset..app.offset + 1, PendingEdit::Char(c))
+                {
+                    return;
+                }
+                if app.edit_mode == EditMode::Insert
+                    && app.nibble == Nibble::Beginning
+                    && c.is_ascii_hexdigit()
+                {
+                    // The first nibble starts a new byte, which the second nibble then edits.
+                    app.contents.insert(app.offset, 0);
+                    app.shift_marks(&(app.offset..app.offset), 1);
+                    app.actions.push(Action::Insert(app.offset));
+                } else {
+                    app.actions.push(Action::CharacterInput(
+                        app.offset,
+                        app.contents[app.offset],
+                        Some(app.nibble),
+                    ));
+                }
                 if c.is_ascii_hexdigit() {
                     // This can probably be optimized...
                     match app.nibble {
@@ -227,6 +305,180 @@ impl KeyHandler for Editor {
     }
 
     fn enter(&mut self, data: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
+        // Enter finishes a byte with only its first digit typed, keeping its second nibble
+        if let Some(high) = data.pending_nibble.take().filter(|_| *self == Self::Hex) {
+            let low = match data.edit_mode {
+                EditMode::Overwrite => data.contents[data.offset] & 0x0F,
+                EditMode::Insert => 0,
+            };
+            data.nibble = Nibble::Beginning;
+            type_byte(data, display, labels, high << 4 | low);
+            return;
+        }
         perform_search(data, display, labels, &SearchDirection::Forward);
     }
 }
+
+/// Writes a byte typed with an escape in the ASCII editor, unless it is protected.
+pub(crate) fn type_byte(
+    app: &mut Data,
+    display: &mut ScreenHandler,
+    labels: &mut LabelHandler,
+    byte: u8,
+) {
+    if !app.intercept_protected_edit(app.offset..app.offset + 1, PendingEdit::Byte(byte)) {
+        write_byte(app, display, labels, byte);
+    }
+}
+
+/// Handles a digit typed in the hex editor when typing whole bytes. The first digit of a byte is
+/// held with the cursor on the second nibble, and the byte is written once the second is typed.
+fn type_hex_digit(app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler, c: char) {
+    let Some(digit) = c.to_digit(16).and_then(|digit| u8::try_from(digit).ok()) else {
+        labels.notification = format!("Invalid Hex: {c}");
+        return;
+    };
+    if let Some(high) = app.pending_nibble.take() {
+        app.nibble = Nibble::Beginning;
+        type_byte(app, display, labels, high << 4 | digit);
+    } else {
+        app.pending_nibble = Some(digit);
+        app.nibble = Nibble::End;
+        labels.notification = format!("{digit:X}_ (type the second digit or press Enter)");
+    }
+}
+
+/// Inserts or overwrites the byte at the cursor, depending on the edit mode, and moves past it.
+fn write_byte(app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler, byte: u8) {
+    if app.edit_mode == EditMode::Insert {
+        app.contents.insert(app.offset, byte);
+        app.shift_marks(&(app.offset..app.offset), 1);
+        app.actions.push(Action::Insert(app.offset));
+    } else {
+        app.actions.push(Action::CharacterInput(app.offset, app.contents[app.offset], None));
+        app.contents[app.offset] = byte;
+    }
+    app.dirty = true;
+    app.offset = cmp::min(app.offset.saturating_add(1), app.contents.len() - 1);
+    labels.update_all(&app.contents[app.offset..]);
+    adjust_offset(app, display, labels);
+}
+
+/// Returns the byte that the characters typed after a backslash stand for, or None while more of
+/// them are needed. These are `\\`, `\0`, `\t`, `\n`, `\r`, and `\x` followed by two hexadecimal
+/// digits.
+fn parse_escape(escape: &str) -> Result<Option<u8>, String> {
+    let invalid = || format!("Invalid escape: \\{escape}");
+    let mut chars = escape.chars();
+    match chars.next() {
+        None => Ok(None),
+        Some('\\') => Ok(Some(b'\\')),
+        Some('0') => Ok(Some(0)),
+        Some('t') => Ok(Some(b'\t')),
+        Some('n') => Ok(Some(b'\n')),
+        Some('r') => Ok(Some(b'\r')),
+        Some('x') => {
+            let digits = chars.as_str();
+            if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+                Err(invalid())
+            } else if digits.len() < 2 {
+                Ok(None)
+            } else {
+                u8::from_str_radix(digits, 16).map(Some).map_err(|_| invalid())
+            }
+        }
+        Some(_) => Err(invalid()),
+    }
+}
+
+/// The offset `distance` bytes after or before the cursor. This stops at the ends of the file, or
+/// wraps around them with `--wrap`.
+fn step_offset(app: &Data, distance: usize, forward: bool) -> usize {
+    let len = app.contents.len();
+    match (app.edges, forward) {
+        (FileEdges::Wrap, true) => (app.offset + distance % len) % len,
+        (FileEdges::Wrap, false) => (app.offset + len - distance % len) % len,
+        (FileEdges::Stop, true) => cmp::min(app.offset.saturating_add(distance), len - 1),
+        (FileEdges::Stop, false) => app.offset.saturating_sub(distance),
+    }
+}
+
+/// Moves the cursor to the offset, clearing the selection.
+fn jump(app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler, offset: usize) {
+    app.last_drag = None;
+    app.drag_nibble = None;
+    app.discard_pending_nibble();
+    app.offset = offset;
+    labels.update_all(&app.contents[app.offset..]);
+    adjust_offset(app, display, labels);
+}
+
+/// Removes the selected bytes as a single action and moves the cursor to where they started.
+fn delete_selection(
+    app: &mut Data,
+    display: &mut ScreenHandler,
+    labels: &mut LabelHandler,
+    range: Range<usize>,
+) {
+    if range.len() == app.contents.len() {
+        labels.notification = String::from("Can't delete the whole file");
+        return;
+    } else if range.clone().any(|offset| app.is_protected(offset)) {
+        labels.notification = String::from("Can't delete protected bytes");
+        return;
+    }
+    app.contents.block();
+    app.replace_bytes(range.clone(), &[]);
+    app.dirty = true;
+    app.last_drag = None;
+    app.drag_nibble = None;
+    app.offset = cmp::min(range.start, app.contents.len() - 1);
+    app.nibble = Nibble::Beginning;
+    labels.update_all(&app.contents[app.offset..]);
+    adjust_offset(app, display, labels);
+    labels.notification = format!("Deleted {} bytes", range.len());
+}
+
+/// Moves the cursor and the viewport up or down by a page.
+fn page(app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler, down: bool) {
+    app.last_drag = None;
+    app.drag_nibble = None;
+    app.discard_pending_nibble();
+    (app.offset, app.start_address) = page_offsets(
+        app.offset,
+        app.start_address,
+        app.contents.len(),
+        display.comp_layouts.bytes_per_line,
+        display.comp_layouts.lines_per_screen,
+        down,
+    );
+    labels.update_all(&app.contents[app.offset..]);
+    adjust_offset(app, display, labels);
+}
+
+/// Handles typing over part of a multibyte UTF-8 character, which is either replaced as a whole
+/// or left alone with a warning depending on [`MultibyteEdit`].
+fn replace_multibyte_char(
+    app: &mut Data,
+    display: &mut ScreenHandler,
+    labels: &mut LabelHandler,
+    range: Range<usize>,
+    c: char,
+) {
+    if app.multibyte_edit == MultibyteEdit::Warn {
+        labels.notification = String::from("This would corrupt a multibyte character!");
+        return;
+    }
+    let mut encoded = [0; 4];
+    let bytes = c.encode_utf8(&mut encoded).as_bytes();
+    // Overwriting doesn't move the rest of the file like a replacement of another length does
+    if bytes.len() == range.len() {
+        app.overwrite(range.start, bytes);
+    } else {
+        app.replace_bytes(range.clone(), bytes);
+    }
+    app.dirty = true;
+    app.offset = cmp::min(range.start + bytes.len(), app.contents.len() - 1);
+    labels.update_all(&app.contents[app.offset..]);
+    adjust_offset(app, display, labels);
+}
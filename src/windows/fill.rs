@@ -0,0 +1,99 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{app::Data, label::Handler as LabelHandler, screen::Handler as ScreenHandler};
+
+use super::{KeyHandler, PopupOutput, Window};
+
+/// A window that overwrites the selected bytes with a constant byte.
+///
+/// This can be opened by pressing `ALTo`.
+///
+/// The input is the byte in hexadecimal, e.g. `00` or `0xFF`. Without a selection, only the byte
+/// at the cursor is overwritten.
+#[derive(PartialEq, Eq)]
+pub(crate) struct Fill {
+    pub(crate) input: String,
+}
+
+impl KeyHandler for Fill {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Fill
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        self.input.push(c);
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.input)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.input.pop();
+    }
+    fn enter(&mut self, app: &mut Data, _: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let input = self.input.trim();
+        let digits = input.strip_prefix("0x").or_else(|| input.strip_prefix("0X")).unwrap_or(input);
+        let Ok(byte) = u8::from_str_radix(digits, 16) else {
+            labels.notification = String::from("Expected a byte in hex, e.g. FF");
+            return;
+        };
+
+        let range = app.selection().unwrap_or(app.offset..app.offset + 1);
+        if range.clone().any(|offset| app.is_protected(offset)) {
+            labels.notification = String::from("Can't fill protected bytes");
+            return;
+        }
+        labels.notification = format!("Filled {} bytes with {byte:02X}", range.len());
+        app.fill(range, byte);
+        labels.update_all(&app.contents[app.offset..]);
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 3))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
+            Block::default()
+                .title("Fill with byte (hex):")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Fill {
+    pub(crate) fn new() -> Self {
+        Self { input: String::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{tests::open, Application, Options};
+
+    use super::*;
+
+    #[test]
+    fn test_fill() {
+        let (mut app, _) = open(&[1; 6], 4, &Options::default());
+        let fill = |app: &mut Application, input: &str| {
+            let mut fill = Fill { input: String::from(input) };
+            fill.enter(&mut app.data, &mut app.display, &mut app.labels);
+            app.labels.notification.clone()
+        };
+
+        // The selection is filled, or only the byte at the cursor without one
+        app.data.last_drag = Some(1);
+        assert_eq!(fill(&mut app, "0xab"), "Filled 4 bytes with AB");
+        assert_eq!(&app.data.contents[..], [1, 0xAB, 0xAB, 0xAB, 0xAB, 1]);
+        app.data.last_drag = None;
+        assert_eq!(fill(&mut app, " 0 "), "Filled 1 bytes with 00");
+        assert_eq!(&app.data.contents[..], [1, 0xAB, 0xAB, 0xAB, 0, 1]);
+
+        assert_eq!(fill(&mut app, "100"), "Expected a byte in hex, e.g. FF");
+        app.data.toggle_protection(4..5);
+        assert_eq!(fill(&mut app, "FF"), "Can't fill protected bytes");
+        assert_eq!(&app.data.contents[..], [1, 0xAB, 0xAB, 0xAB, 0, 1]);
+    }
+}
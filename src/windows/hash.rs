@@ -0,0 +1,182 @@
+use std::ops::Range;
+
+use md5::Md5;
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    app::Data, checksum::crc32_update, label::Handler as LabelHandler,
+    screen::Handler as ScreenHandler,
+};
+
+use super::{KeyHandler, Window};
+
+/// The number of bytes hashed between renders, which keeps the UI responsive while large
+/// selections are hashed.
+const CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+/// The hash functions that can be computed.
+const ALGORITHMS: [&str; 3] = ["CRC32", "MD5", "SHA-256"];
+
+/// The state of a hash function that bytes are fed into.
+enum Hasher {
+    Crc32(u32),
+    Md5(Md5),
+    Sha256(Sha256),
+}
+
+impl Hasher {
+    fn new(algorithm: usize) -> Self {
+        match algorithm {
+            0 => Self::Crc32(u32::MAX),
+            1 => Self::Md5(Md5::new()),
+            _ => Self::Sha256(Sha256::new()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Crc32(crc) => *crc = crc32_update(*crc, bytes),
+            Self::Md5(md5) => md5.update(bytes),
+            Self::Sha256(sha256) => sha256.update(bytes),
+        }
+    }
+
+    /// Returns the hash in hexadecimal.
+    fn finish(self) -> String {
+        match self {
+            Self::Crc32(crc) => format!("{:08X}", !crc),
+            Self::Md5(md5) => hex::encode_upper(md5.finalize()),
+            Self::Sha256(sha256) => hex::encode_upper(sha256.finalize()),
+        }
+    }
+}
+
+/// A window that hashes the selected bytes.
+///
+/// This can be opened by pressing `ALTd`.
+///
+/// The left and right arrow keys choose between CRC32, MD5, and SHA-256, and pressing enter
+/// hashes the selection, or the whole file if nothing is selected. Large selections are hashed a
+/// piece at a time so that the window can show the progress.
+pub(crate) struct Hash {
+    algorithm: usize,
+    range: Range<usize>,
+    /// The hash function and the offset of the next byte to feed it, while hashing.
+    hashing: Option<(Hasher, usize)>,
+    result: Option<String>,
+}
+
+impl KeyHandler for Hash {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Hash
+    }
+    fn left(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select((self.algorithm + ALGORITHMS.len() - 1) % ALGORITHMS.len());
+    }
+    fn right(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select((self.algorithm + 1) % ALGORITHMS.len());
+    }
+    fn enter(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.result = None;
+        self.hashing = Some((Hasher::new(self.algorithm), self.range.start));
+    }
+    fn work(&mut self, app: &Data) -> bool {
+        let Some((hasher, next)) = &mut self.hashing else {
+            return false;
+        };
+        // Edits may still be moving bytes around outside of the window.
+        app.contents.block();
+        let end = self.range.end.min(app.contents.len()).min(next.saturating_add(CHUNK_LEN));
+        if *next < end {
+            hasher.update(&app.contents[*next..end]);
+            *next = end;
+        }
+        if *next < self.range.end.min(app.contents.len()) {
+            return true;
+        }
+        if let Some((hasher, _)) = self.hashing.take() {
+            self.result = Some(hasher.finish());
+        }
+        false
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((72, 5))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let result = match (&self.hashing, &self.result) {
+            (Some((_, next)), _) => {
+                let done = (next - self.range.start) * 100 / self.range.len().max(1);
+                format!("Hashing... {done}%")
+            }
+            (None, Some(result)) => result.clone(),
+            (None, None) => String::from("Press enter to hash"),
+        };
+        let lines = vec![
+            Line::from(Span::styled(
+                format!("Algorithm: < {} >", ALGORITHMS[self.algorithm]),
+                Style::default().fg(Color::White),
+            )),
+            Line::from(Span::styled(
+                format!(
+                    "Range:     {:#X}-{:#X} ({} bytes)",
+                    self.range.start,
+                    self.range.end - 1,
+                    self.range.len()
+                ),
+                Style::default().fg(Color::White),
+            )),
+            Line::from(Span::styled(result, Style::default().fg(Color::White))),
+        ];
+        Paragraph::new(lines).block(
+            Block::default()
+                .title("Hash:")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Hash {
+    pub(crate) fn new(app: &Data) -> Self {
+        Self {
+            algorithm: 0,
+            range: app.selection().unwrap_or(0..app.contents.len()),
+            hashing: None,
+            result: None,
+        }
+    }
+
+    /// Chooses another algorithm, discarding the hash of the previous one.
+    fn select(&mut self, algorithm: usize) {
+        self.algorithm = algorithm;
+        self.hashing = None;
+        self.result = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hasher() {
+        let hash = |algorithm, pieces: &[&[u8]]| {
+            let mut hasher = Hasher::new(algorithm);
+            for piece in pieces {
+                hasher.update(piece);
+            }
+            hasher.finish()
+        };
+        assert_eq!(hash(0, &[b"12345", b"6789"]), "CBF43926");
+        assert_eq!(hash(1, &[b"a", b"bc"]), "900150983CD24FB0D6963F7D28E17F72");
+        assert_eq!(
+            hash(2, &[b"ab", b"c"]),
+            "BA7816BF8F01CFEA414140DE5DAE2223B00361A396177A9CB410FF61F20015AD"
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use std::ops::Range;
+
+use ratatui::{
+    layout::Rect,
+    style::{Color, Style},
+    text::Line,
+    widgets::{Bar, BarChart, BarGroup, Block, Borders, Paragraph},
+    Frame,
+};
+
+use crate::app::Data;
+
+use super::{KeyHandler, Window};
+
+/// The number of bytes counted between renders, which keeps the UI responsive while large
+/// selections are counted.
+const CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+/// The number of the most common byte values that are shown as bars.
+const BARS: usize = 16;
+
+/// A window that shows how often each byte value occurs in the selected bytes, as a bar chart of
+/// the most common values and their share of the bytes.
+///
+/// This can be opened by pressing `ALTq`. It counts the selection, or the whole file if nothing
+/// is selected, a piece at a time so that large files don't block input.
+pub(crate) struct Histogram {
+    range: Range<usize>,
+    counts: [u64; 256],
+    /// The offset of the next byte to count, which is the end of the range once counting is done.
+    next: usize,
+}
+
+impl KeyHandler for Histogram {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Histogram
+    }
+    fn work(&mut self, app: &Data) -> bool {
+        // Edits may still be moving bytes around outside of the window.
+        app.contents.block();
+        let range_end = self.range.end.min(app.contents.len());
+        let end = range_end.min(self.next.saturating_add(CHUNK_LEN));
+        if self.next < end {
+            for &byte in &app.contents[self.next..end] {
+                self.counts[usize::from(byte)] += 1;
+            }
+            self.next = end;
+        }
+        self.next < range_end
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        #[allow(clippy::cast_possible_truncation)]
+        Some((BARS as u16 * 4 + 3, 16))
+    }
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::default()
+            .title(self.title())
+            .borders(Borders::ALL)
+            .style(Style::default().fg(Color::Yellow));
+        let counted = self.next - self.range.start;
+        if counted == 0 {
+            frame.render_widget(
+                Paragraph::new(Line::from("No bytes counted yet")).block(block),
+                area,
+            );
+            return;
+        }
+        let bars: Vec<Bar> = self
+            .most_common()
+            .into_iter()
+            .map(|(byte, count)| {
+                #[allow(clippy::cast_possible_truncation)]
+                let percent = (u128::from(count) * 100 / counted as u128) as u64;
+                Bar::default()
+                    .value(count)
+                    .label(Line::from(format!("{byte:02X}")))
+                    .text_value(format!("{percent}%"))
+            })
+            .collect();
+        let chart = BarChart::default()
+            .block(block)
+            .data(BarGroup::default().bars(&bars))
+            .bar_width(3)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(Color::White))
+            .value_style(Style::default().fg(Color::Black).bg(Color::White))
+            .label_style(Style::default().fg(Color::White));
+        frame.render_widget(chart, area);
+    }
+}
+
+impl Histogram {
+    pub(crate) fn new(app: &Data) -> Self {
+        let range = app.selection().unwrap_or(0..app.contents.len());
+        Self { next: range.start, range, counts: [0; 256] }
+    }
+
+    /// Returns the title with the range that is counted, and the progress while it is counted.
+    fn title(&self) -> String {
+        let range = format!("{:#X}-{:#X}", self.range.start, self.range.end.saturating_sub(1));
+        if self.next < self.range.end {
+            let done = (self.next - self.range.start) * 100 / self.range.len().max(1);
+            format!("Histogram of {range}: counting... {done}% (Esc to close)")
+        } else {
+            format!("Histogram of {range} (Esc to close)")
+        }
+    }
+
+    /// Returns the byte values that occur, with their counts, from the most common. Values that
+    /// are as common are ordered by value.
+    fn most_common(&self) -> Vec<(u8, u64)> {
+        let mut counts: Vec<(u8, u64)> =
+            (0..=u8::MAX).zip(self.counts).filter(|&(_, count)| count > 0).collect();
+        counts.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        counts.truncate(BARS);
+        counts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_most_common() {
+        let mut histogram = Histogram { range: 0..0, counts: [0; 256], next: 0 };
+        assert!(histogram.most_common().is_empty());
+
+        histogram.counts[0xFF] = 3;
+        histogram.counts[0x00] = 7;
+        histogram.counts[0x41] = 3;
+        assert_eq!(histogram.most_common(), vec![(0x00, 7), (0x41, 3), (0xFF, 3)]);
+
+        // Only the most common values are kept
+        histogram.counts = [1; 256];
+        histogram.counts[0x80] = 2;
+        let most_common = histogram.most_common();
+        assert_eq!(most_common.len(), BARS);
+        assert_eq!(most_common[..2], [(0x80, 2), (0x00, 1)]);
+    }
+}
@@ -0,0 +1,81 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{app::Data, screen::Handler as ScreenHandler};
+
+use super::{KeyHandler, Window};
+
+/// A window that shows the version of heh and the internal state of the display and buffer, which
+/// is useful to include in bug reports about rendering or scrolling.
+///
+/// This can be opened by pressing `CNTRL.`, and the values are captured when it is opened.
+pub(crate) struct Info {
+    lines: Vec<String>,
+}
+
+impl KeyHandler for Info {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Info
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        #[allow(clippy::cast_possible_truncation)]
+        Some((50, self.lines.len() as u16 + 2))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let lines: Vec<Line> = self.lines.iter().map(|line| Line::from(line.as_str())).collect();
+        Paragraph::new(lines).style(Style::default().fg(Color::White)).block(
+            Block::default()
+                .title("Info (Esc to close)")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Info {
+    pub(crate) fn new(app: &Data, display: &ScreenHandler) -> Self {
+        let size = display.terminal_size;
+        let layouts = &display.comp_layouts;
+        let lines = vec![
+            format!("heh {}", env!("CARGO_PKG_VERSION")),
+            format!("Terminal size: {}x{}", size.width, size.height),
+            format!("Bytes per line: {}", layouts.bytes_per_line),
+            format!("Lines per screen: {}", layouts.lines_per_screen),
+            format!("Start address: {:#X}", app.start_address),
+            format!("Cursor offset: {:#X}", app.offset),
+            format!("Buffer length: {:#X}", app.contents.len()),
+            format!("Window end: {:#X}", app.contents.window_end()),
+            format!("Pending work: {}", app.contents.has_pending_work()),
+        ];
+        Self { lines }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{tests::open, Options};
+
+    use super::*;
+
+    #[test]
+    fn test_info() {
+        let (app, _) = open(&[0; 0x40], 0x21, &Options::default());
+        app.data.contents.block();
+        let info = Info::new(&app.data, &app.display);
+        assert_eq!(info.lines[0], format!("heh {}", env!("CARGO_PKG_VERSION")));
+        assert_eq!(info.lines[1], "Terminal size: 100x40");
+        assert_eq!(
+            info.lines[5..],
+            [
+                "Cursor offset: 0x21",
+                "Buffer length: 0x40",
+                "Window end: 0x40",
+                "Pending work: false"
+            ]
+        );
+        assert_eq!(info.dimensions(), Some((50, 11)));
+    }
+}
@@ -0,0 +1,124 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::{Data, Nibble},
+    label::Handler as LabelHandler,
+    screen::Handler as ScreenHandler,
+};
+
+use super::{adjust_offset, parse_number, KeyHandler, PopupOutput, Window};
+
+/// More bytes than this (256 MiB) are not inserted at once, since the whole buffer has to fit in
+/// memory.
+const INSERT_LIMIT: usize = 0x1000_0000;
+
+/// A window that inserts a number of bytes at the cursor, growing the file.
+///
+/// This can be opened by pressing `ALTi`, or by pressing `ALTShift+a` to append the bytes to the
+/// end of the file instead, which moves the cursor to the first of them.
+///
+/// The input is the number of bytes and optionally the byte to insert in hexadecimal, which is
+/// `00` if left out, e.g. `0x100 FF`. The bytes after the cursor are moved in one go rather than
+/// once per inserted byte, and the insertion can be undone in a single step.
+#[derive(PartialEq, Eq)]
+pub(crate) struct InsertBytes {
+    pub(crate) input: String,
+    /// Whether the bytes are appended to the end of the file rather than inserted at the cursor.
+    append: bool,
+}
+
+impl KeyHandler for InsertBytes {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == if self.append { Window::AppendBytes } else { Window::InsertBytes }
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        self.input.push(c);
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.input)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.input.pop();
+    }
+    fn enter(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let (count, byte) = match self.parse() {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                labels.notification = e;
+                return;
+            }
+        };
+        if count == 0 || count > INSERT_LIMIT {
+            labels.notification = format!("Can insert between 1 and {INSERT_LIMIT:#X} bytes");
+            return;
+        }
+        if self.append {
+            let end = app.contents.len();
+            app.replace_bytes(end..end, &vec![byte; count]);
+            app.dirty = true;
+            app.offset = end;
+            app.nibble = Nibble::Beginning;
+            app.last_drag = None;
+            app.drag_nibble = None;
+            labels.update_all(&app.contents[app.offset..]);
+            adjust_offset(app, display, labels);
+            labels.notification = format!("Appended {count} bytes");
+            return;
+        }
+        if app.is_protected(app.offset) {
+            labels.notification = String::from("Can't insert into protected bytes");
+            return;
+        }
+
+        app.replace_bytes(app.offset..app.offset, &vec![byte; count]);
+        app.dirty = true;
+        labels.update_all(&app.contents[app.offset..]);
+        adjust_offset(app, display, labels);
+        labels.notification = format!("Inserted {count} bytes");
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 3))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
+            Block::default()
+                .title(if self.append {
+                    "Append bytes (count [byte]):"
+                } else {
+                    "Insert bytes (count [byte]):"
+                })
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl InsertBytes {
+    pub(crate) fn new() -> Self {
+        Self { input: String::new(), append: false }
+    }
+
+    /// Creates the window that appends the bytes to the end of the file.
+    pub(crate) fn appending() -> Self {
+        Self { input: String::new(), append: true }
+    }
+
+    /// Splits the input into the number of bytes and the byte to insert.
+    fn parse(&self) -> Result<(usize, u8), String> {
+        let mut args = self.input.split_whitespace();
+        let count = parse_number(args.next().ok_or_else(|| String::from("Missing count"))?)
+            .map_err(|e| format!("Error: {e:?}"))?;
+        let byte = match args.next() {
+            Some(byte) => {
+                let digits = byte.strip_prefix("0x").unwrap_or(byte);
+                u8::from_str_radix(digits, 16).map_err(|_| format!("Invalid byte: {byte}"))?
+            }
+            None => 0,
+        };
+        Ok((count, byte))
+    }
+}
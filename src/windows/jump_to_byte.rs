@@ -1,3 +1,5 @@
+use std::cmp;
+
 use ratatui::{
     style::{Color, Style},
     text::Span,
@@ -6,13 +8,16 @@ use ratatui::{
 
 use crate::{app::Data, label::Handler as LabelHandler, screen::Handler as ScreenHandler};
 
-use super::{adjust_offset, KeyHandler, PopupOutput, Window};
+use super::{adjust_offset, parse_number, KeyHandler, PopupOutput, Window};
 
 /// A window that can accept input and attempt to move the cursor to the inputted byte.
 ///
 /// This can be opened by pressing `CNTRLj`.
 ///
 /// The input is either parsed as hexadecimal if it is preceded with "0x", or decimal if not.
+/// Input ending in "%" is instead parsed as a percentage of the way through the file, and input
+/// preceded with "+" or "-" moves the cursor relative to its current position. Other input is an
+/// address, which is relative to `--base` if it is set.
 #[derive(PartialEq, Eq)]
 pub(crate) struct JumpToByte {
     pub(crate) input: String,
@@ -25,34 +30,26 @@ impl KeyHandler for JumpToByte {
     fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
         self.input.push(c);
     }
-    fn get_user_input(&self) -> PopupOutput {
+    fn get_user_input(&self) -> PopupOutput<'_> {
         PopupOutput::Str(&self.input)
     }
     fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
         self.input.pop();
     }
     fn enter(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
-        let new_offset = if self.input.starts_with("0x") {
-            usize::from_str_radix(&self.input[2..], 16)
-        } else {
-            self.input.parse()
-        };
-        if let Ok(new_offset) = new_offset {
-            if new_offset >= app.contents.len() {
-                labels.notification = String::from("Invalid range!");
-            } else {
+        match self.target(app) {
+            Ok(new_offset) => {
                 app.offset = new_offset;
                 labels.update_all(&app.contents[app.offset..]);
                 adjust_offset(app, display, labels);
             }
-        } else {
-            labels.notification = format!("Error: {:?}", new_offset.unwrap_err());
+            Err(e) => labels.notification = e,
         }
     }
     fn dimensions(&self) -> Option<(u16, u16)> {
         Some((50, 3))
     }
-    fn widget(&self) -> Paragraph {
+    fn widget(&self) -> Paragraph<'_> {
         Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
             Block::default()
                 .title("Jump to Byte:")
@@ -66,4 +63,68 @@ impl JumpToByte {
     pub(crate) fn new() -> Self {
         Self { input: String::new() }
     }
+
+    /// Parses the input into the offset that the cursor should jump to.
+    fn target(&self, app: &Data) -> Result<usize, String> {
+        let last_offset = app.contents.len() - 1;
+        if let Some(percentage) = self.input.strip_suffix('%') {
+            return match percentage.parse::<usize>() {
+                Ok(percentage) if percentage <= 100 => {
+                    Ok(cmp::min(app.contents.len().saturating_mul(percentage) / 100, last_offset))
+                }
+                _ => Err(String::from("Invalid percentage!")),
+            };
+        }
+        if let Some(delta) = self.input.strip_prefix('+') {
+            let delta = parse_number(delta).map_err(|e| format!("Error: {e:?}"))?;
+            return Ok(cmp::min(app.offset.saturating_add(delta), last_offset));
+        }
+        if let Some(delta) = self.input.strip_prefix('-') {
+            let delta = parse_number(delta).map_err(|e| format!("Error: {e:?}"))?;
+            return Ok(app.offset.saturating_sub(delta));
+        }
+
+        let address = parse_number(&self.input).map_err(|e| format!("Error: {e:?}"))?;
+        let new_offset = address.checked_sub(app.base).ok_or("Address is before the base!")?;
+        if new_offset > last_offset {
+            return Err(String::from("Invalid range!"));
+        }
+        Ok(new_offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::{tests::open, Options};
+
+    use super::*;
+
+    /// Returns where the input jumps to in a file of `len` bytes, starting at `offset`.
+    fn target(input: &str, len: usize, offset: usize) -> Result<usize, String> {
+        let (app, _) = open(&vec![0; len], offset, &Options::default());
+        JumpToByte { input: String::from(input) }.target(&app.data)
+    }
+
+    #[test]
+    fn test_percentage() {
+        assert_eq!(target("0%", 200, 50), Ok(0));
+        assert_eq!(target("50%", 200, 0), Ok(100));
+        assert_eq!(target("33%", 10, 0), Ok(3));
+
+        // The end of the file is the last byte rather than the offset past it
+        assert_eq!(target("100%", 200, 0), Ok(199));
+        assert_eq!(target("101%", 200, 0), Err(String::from("Invalid percentage!")));
+        assert_eq!(target("-5%", 200, 0), Err(String::from("Invalid percentage!")));
+    }
+
+    #[test]
+    fn test_relative_jump() {
+        assert_eq!(target("+0x10", 200, 50), Ok(66));
+        assert_eq!(target("-10", 200, 50), Ok(40));
+
+        // Jumps past either end of the file stop at it
+        assert_eq!(target("+500", 200, 50), Ok(199));
+        assert_eq!(target("-500", 200, 50), Ok(0));
+        assert!(target("+x", 200, 50).is_err());
+    }
 }
@@ -1,12 +1,30 @@
 //! The components that implement [`KeyHandler`], which allow them to uniquely react to user input.
 //! Example of a component include the Hex/ASCII editors and the Unsaved Changes warning.
 
+pub(crate) mod align;
+pub(crate) mod append;
+pub(crate) mod checksum;
 pub(crate) mod editor;
+pub(crate) mod fill;
+pub(crate) mod hash;
+pub(crate) mod histogram;
+pub(crate) mod info;
+pub(crate) mod insert_bytes;
 pub(crate) mod jump_to_byte;
+pub(crate) mod notifications;
+pub(crate) mod protected_edit;
+pub(crate) mod replace;
+pub(crate) mod restore_backup;
 pub(crate) mod search;
+pub(crate) mod split;
+pub(crate) mod strings;
+pub(crate) mod symbols;
 pub(crate) mod unsaved_changes;
+pub(crate) mod write_value;
 
-use ratatui::widgets::Paragraph;
+use std::{cmp, num::ParseIntError};
+
+use ratatui::{layout::Rect, widgets::Paragraph, Frame};
 
 use crate::{app::Data, label::Handler as LabelHandler, screen::Handler as ScreenHandler};
 
@@ -20,6 +38,24 @@ pub enum Window {
     JumpToByte,
     Search,
     UnsavedChanges,
+    ProtectedEdit,
+    Checksum,
+    Split,
+    Append,
+    Strings,
+    Info,
+    Replace,
+    Fill,
+    InsertBytes,
+    AppendBytes,
+    Symbols,
+    Hash,
+    RestoreBackup,
+    WriteValue,
+    Notifications,
+    Histogram,
+    AlignCursor,
+    Overview,
     Label(usize),
     Unhandled,
 }
@@ -48,6 +84,8 @@ pub trait KeyHandler {
     fn down(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
     fn home(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
     fn end(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
+    fn file_start(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
+    fn file_end(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
     fn page_up(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
     fn page_down(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
     fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {}
@@ -56,7 +94,7 @@ pub trait KeyHandler {
     fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, _: char) {}
 
     /// Returns user input. Is currently used to get information from popups.
-    fn get_user_input(&self) -> PopupOutput {
+    fn get_user_input(&self) -> PopupOutput<'_> {
         PopupOutput::NoOutput
     }
 
@@ -66,9 +104,22 @@ pub trait KeyHandler {
     }
 
     /// Returns the contents to display on the screen
-    fn widget(&self) -> Paragraph {
+    fn widget(&self) -> Paragraph<'_> {
         Paragraph::new("")
     }
+
+    /// Draws the popup in the area. This draws the [`Paragraph`] from [`KeyHandler::widget`],
+    /// unless a popup draws another kind of widget, like a chart.
+    fn render(&self, frame: &mut Frame, area: Rect) {
+        frame.render_widget(self.widget(), area);
+    }
+
+    /// Does a piece of the work started by a keypress, like hashing a large selection, and
+    /// returns whether there is work left. This is called before every render so that long work
+    /// can show its progress without blocking input.
+    fn work(&mut self, _: &Data) -> bool {
+        false
+    }
 }
 
 /// Moves the starting address of the editor viewports (Hex and ASCII) to include the cursor.
@@ -80,20 +131,193 @@ pub trait KeyHandler {
 ///
 /// If the cursor's location is past the end of the viewports, the viewports will move so that
 /// the cursor is included in the final row.
+///
+/// With `--scrolloff`, the viewports move before the cursor reaches their first or last row, so
+/// that there are that many rows around it. See [`scrolled_start_address`].
 pub(crate) fn adjust_offset(
     app: &mut Data,
     display: &mut ScreenHandler,
     labels: &mut LabelHandler,
 ) {
     let bytes_per_line = display.comp_layouts.bytes_per_line;
-    let bytes_per_screen = bytes_per_line * display.comp_layouts.lines_per_screen;
+    app.start_address = scrolled_start_address(
+        app.offset,
+        app.start_address,
+        app.contents.len(),
+        bytes_per_line,
+        display.comp_layouts.lines_per_screen,
+        app.scrolloff,
+    );
+
+    labels.update_offset(app.address(app.offset), app.address_radix);
+    labels.update_position(app.offset, bytes_per_line);
+}
+
+/// Returns the offset and start address after moving a page up or down. The cursor keeps its row
+/// and column in the viewport, except that the viewport stops at the first and last page and the
+/// cursor stops at the first and last byte of the file.
+pub(crate) fn page_offsets(
+    offset: usize,
+    start_address: usize,
+    len: usize,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+    down: bool,
+) -> (usize, usize) {
+    let bytes_per_screen = bytes_per_line * lines_per_screen;
+    if down {
+        let lines = len.saturating_sub(1) / bytes_per_line + 1;
+        let last_start = lines.saturating_sub(lines_per_screen) * bytes_per_line;
+        (
+            cmp::min(offset.saturating_add(bytes_per_screen), len.saturating_sub(1)),
+            cmp::max(start_address, cmp::min(start_address + bytes_per_screen, last_start)),
+        )
+    } else {
+        (offset.saturating_sub(bytes_per_screen), start_address.saturating_sub(bytes_per_screen))
+    }
+}
+
+/// Returns the start address of the viewport that keeps `scrolloff` rows above and below the
+/// offset's row, scrolling as little as possible. The margin is smaller near the start and the end
+/// of the file, and on screens too short for it. With no margin, the viewport only scrolls once
+/// the offset leaves it.
+pub(crate) fn scrolled_start_address(
+    offset: usize,
+    start_address: usize,
+    len: usize,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+    scrolloff: usize,
+) -> usize {
+    let row = offset / bytes_per_line;
+    let top = start_address / bytes_per_line;
+    let margin = cmp::min(scrolloff, lines_per_screen.saturating_sub(1) / 2);
+    let top = if row < top + margin {
+        row.saturating_sub(margin)
+    } else if row + margin >= top + lines_per_screen {
+        // The margin below stops at the last row of the file, but the offset stays in view
+        let lines = len.saturating_sub(1) / bytes_per_line + 1;
+        let last_top = lines.saturating_sub(lines_per_screen);
+        let lowest_top = (row + 1).saturating_sub(lines_per_screen);
+        cmp::max(
+            cmp::min((row + margin + 1).saturating_sub(lines_per_screen), last_top),
+            lowest_top,
+        )
+    } else {
+        top
+    };
+    top * bytes_per_line
+}
+
+/// Returns the start address that puts the offset's row in the middle of the viewport, or as close
+/// to it as the start and end of the file allow.
+pub(crate) fn centered_start_address(
+    offset: usize,
+    len: usize,
+    bytes_per_line: usize,
+    lines_per_screen: usize,
+) -> usize {
+    let lines = len.saturating_sub(1) / bytes_per_line + 1;
+    let last_top = lines.saturating_sub(lines_per_screen);
+    let top = (offset / bytes_per_line).saturating_sub(lines_per_screen / 2);
+    cmp::min(top, last_top) * bytes_per_line
+}
+
+/// Returns the offset and start address moved back inside content of the given length, which
+/// they can end up past after bytes are removed from the end.
+pub(crate) fn clamp_offsets(
+    offset: usize,
+    start_address: usize,
+    len: usize,
+    bytes_per_line: usize,
+) -> (usize, usize) {
+    let last_offset = len.saturating_sub(1);
+    let last_start = last_offset / bytes_per_line * bytes_per_line;
+    (cmp::min(offset, last_offset), cmp::min(start_address, last_start))
+}
 
-    if app.offset < app.start_address {
-        app.start_address = (app.offset / bytes_per_line) * bytes_per_line;
-    } else if app.offset >= app.start_address + (bytes_per_screen) {
-        app.start_address =
-            (app.offset / bytes_per_line) * bytes_per_line - bytes_per_screen + bytes_per_line;
+/// Parses a number that is either hexadecimal if it is preceded with "0x", or decimal if not.
+pub(crate) fn parse_number(input: &str) -> Result<usize, ParseIntError> {
+    if let Some(stripped) = input.strip_prefix("0x") {
+        usize::from_str_radix(stripped, 16)
+    } else {
+        input.parse()
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{centered_start_address, clamp_offsets, page_offsets, scrolled_start_address};
+
+    #[test]
+    fn test_page_offsets() {
+        // 10 bytes per line, 3 lines per screen, and 10 lines in the file
+        let page =
+            |offset, start_address, down| page_offsets(offset, start_address, 100, 10, 3, down);
+
+        // the cursor keeps its row and column in the viewport
+        assert_eq!(page(15, 10, true), (45, 40));
+        assert_eq!(page(45, 40, false), (15, 10));
+
+        // the viewport stops at the last page and the cursor at the last byte
+        assert_eq!(page(55, 50, true), (85, 70));
+        assert_eq!(page(75, 70, true), (99, 70));
 
-    labels.offset = format!("{:#X}", app.offset);
+        // the viewport stops at the first page and the cursor at the first byte
+        assert_eq!(page(25, 10, false), (0, 0));
+        assert_eq!(page(5, 0, false), (0, 0));
+
+        // the file fits on a single page
+        assert_eq!(page_offsets(5, 0, 20, 10, 3, true), (19, 0));
+    }
+    #[test]
+    fn test_clamp_offsets() {
+        assert_eq!(clamp_offsets(5, 0, 10, 4), (5, 0));
+        assert_eq!(clamp_offsets(12, 8, 10, 4), (9, 8));
+        assert_eq!(clamp_offsets(12, 12, 10, 4), (9, 8));
+
+        // a single byte is left
+        assert_eq!(clamp_offsets(3, 4, 1, 4), (0, 0));
+        assert_eq!(clamp_offsets(0, 0, 1, 4), (0, 0));
+    }
+
+    #[test]
+    fn test_scrolled_start_address() {
+        // 10 bytes per line, 5 lines per screen, and 10 lines in the file
+        let scroll = |offset, start_address, scrolloff| {
+            scrolled_start_address(offset, start_address, 100, 10, 5, scrolloff)
+        };
+
+        // Without a margin, the viewport only moves once the offset leaves it
+        assert_eq!(scroll(45, 10, 0), 10);
+        assert_eq!(scroll(55, 10, 0), 10);
+        assert_eq!(scroll(65, 10, 0), 20);
+        assert_eq!(scroll(5, 10, 0), 0);
+
+        // With a margin, it moves before the offset reaches its first or last row
+        assert_eq!(scroll(55, 10, 1), 20);
+        assert_eq!(scroll(25, 20, 1), 10);
+        assert_eq!(scroll(35, 10, 1), 10);
+
+        // The margin stops at the start and the end of the file
+        assert_eq!(scroll(5, 10, 2), 0);
+        assert_eq!(scroll(15, 10, 2), 0);
+        assert_eq!(scroll(95, 50, 2), 50);
+        assert_eq!(scroll(85, 40, 2), 50);
+
+        // It is at most half of the screen, which keeps the offset in the middle row
+        assert_eq!(scroll(55, 10, 10), 30);
+        assert_eq!(scroll(45, 30, 10), 20);
+    }
+
+    #[test]
+    fn test_centered_start_address() {
+        // 10 bytes per line, 4 lines per screen, and 10 lines in the file
+        assert_eq!(centered_start_address(55, 100, 10, 4), 30);
+        // The viewport stops at the start and the end of the file
+        assert_eq!(centered_start_address(15, 100, 10, 4), 0);
+        assert_eq!(centered_start_address(95, 100, 10, 4), 60);
+        // Files shorter than the screen start at the top
+        assert_eq!(centered_start_address(25, 30, 10, 4), 0);
+    }
 }
@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+use ratatui::{
+    style::{Color, Style},
+    text::Line,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::label::Handler as LabelHandler;
+
+use super::{KeyHandler, Window};
+
+/// A window that lists the notifications that were shown most recently, newest first, with how
+/// long ago they were shown.
+///
+/// This can be opened by pressing `CNTRLl`, and the notifications are captured when it is opened.
+pub(crate) struct Notifications {
+    entries: Vec<(Instant, String)>,
+}
+
+impl KeyHandler for Notifications {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Notifications
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        #[allow(clippy::cast_possible_truncation)]
+        Some((70, self.entries.len().max(1) as u16 + 2))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let now = Instant::now();
+        let lines: Vec<Line> = if self.entries.is_empty() {
+            vec![Line::from("No notifications yet")]
+        } else {
+            self.entries
+                .iter()
+                .map(|(shown, notification)| {
+                    Line::from(format!("{:>8}  {notification}", format_age(now - *shown)))
+                })
+                .collect()
+        };
+        Paragraph::new(lines).style(Style::default().fg(Color::White)).block(
+            Block::default()
+                .title("Notifications (Esc to close)")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Notifications {
+    pub(crate) fn new(labels: &LabelHandler) -> Self {
+        Self { entries: labels.history.iter().rev().cloned().collect() }
+    }
+}
+
+/// Formats how long ago something happened, like `42s ago` or `3m ago`.
+fn format_age(age: Duration) -> String {
+    let seconds = age.as_secs();
+    match seconds {
+        0..60 => format!("{seconds}s ago"),
+        60..3600 => format!("{}m ago", seconds / 60),
+        _ => format!("{}h ago", seconds / 3600),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_millis(1500)), "1s ago");
+        assert_eq!(format_age(Duration::from_secs(59)), "59s ago");
+        assert_eq!(format_age(Duration::from_secs(61)), "1m ago");
+        assert_eq!(format_age(Duration::from_secs(7300)), "2h ago");
+    }
+}
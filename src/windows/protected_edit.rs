@@ -0,0 +1,82 @@
+use ratatui::{
+    layout::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{app::Data, label::Handler as LabelHandler, screen::Handler as ScreenHandler};
+
+use super::{KeyHandler, PopupOutput, Window};
+
+/// A window that asks the user to confirm an edit to a byte in a protected range.
+///
+/// Ranges can be protected or unprotected by pressing `ALTp`.
+pub(crate) struct ProtectedEdit {
+    pub(crate) should_edit: bool,
+}
+
+impl KeyHandler for ProtectedEdit {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::ProtectedEdit
+    }
+    fn left(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        if !self.should_edit {
+            self.should_edit = true;
+        }
+    }
+    fn right(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        if self.should_edit {
+            self.should_edit = false;
+        }
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Boolean(self.should_edit)
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 5))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let message = vec![
+            Line::from(Span::styled(
+                "Are you sure you want to edit this byte? (y/n)",
+                Style::default().fg(Color::White),
+            )),
+            Line::from(Span::from("")),
+            Line::from(vec![
+                Span::styled(
+                    "    Yes    ",
+                    if self.should_edit {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::White)
+                    },
+                ),
+                Span::styled(
+                    "    No    ",
+                    if self.should_edit {
+                        Style::default().fg(Color::White)
+                    } else {
+                        Style::default()
+                    },
+                ),
+            ]),
+        ];
+        Paragraph::new(message).alignment(Alignment::Center).block(
+            Block::default()
+                .title(Span::styled(
+                    "This Byte Is Protected.",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl ProtectedEdit {
+    pub(crate) fn new() -> Self {
+        ProtectedEdit { should_edit: false }
+    }
+}
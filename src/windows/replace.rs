@@ -0,0 +1,355 @@
+use std::ops::Range;
+
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::{Action, Data, ASCII_PREFIX, HEX_PREFIX},
+    label::Handler as LabelHandler,
+    screen::Handler as ScreenHandler,
+};
+
+use super::{
+    search::{perform_search, SearchDirection},
+    KeyHandler, PopupOutput, Window,
+};
+
+/// The rows of the replace window that can be selected.
+#[derive(PartialEq, Eq, Copy, Clone)]
+enum Field {
+    Find,
+    Replacement,
+    Scope,
+}
+
+/// A window that replaces occurrences of a search term with other bytes of the same length.
+///
+/// This can be opened by pressing `ALTf`.
+///
+/// The search term is parsed like in [`Search`](super::search::Search). The replacement is read
+/// the same way, as hexadecimal when prefixed with `hex:` and as ASCII otherwise, and has to be as
+/// long as the matches. The arrow keys move between the rows and toggle between replacing only
+/// the match at the cursor or every match, and pressing enter performs the replacement. Unlike
+/// other popups, the window stays open until escape is pressed.
+pub(crate) struct Replace {
+    find: String,
+    replacement: String,
+    field: Field,
+    all_matches: bool,
+}
+
+impl KeyHandler for Replace {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Replace
+    }
+    fn up(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.field = match self.field {
+            Field::Find | Field::Replacement => Field::Find,
+            Field::Scope => Field::Replacement,
+        };
+    }
+    fn down(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.field = match self.field {
+            Field::Find => Field::Replacement,
+            Field::Replacement | Field::Scope => Field::Scope,
+        };
+    }
+    fn left(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        if self.field == Field::Scope {
+            self.all_matches = false;
+        }
+    }
+    fn right(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        if self.field == Field::Scope {
+            self.all_matches = true;
+        }
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        match self.field {
+            Field::Find => self.find.push(c),
+            Field::Replacement => self.replacement.push(c),
+            Field::Scope => {}
+        }
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.find)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        match self.field {
+            Field::Find => self.find.pop(),
+            Field::Replacement => self.replacement.pop(),
+            Field::Scope => None,
+        };
+    }
+    fn enter(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
+        if self.field == Field::Find {
+            self.field = Field::Replacement;
+            return;
+        }
+        if self.find.is_empty() {
+            labels.notification = String::from("Empty search query");
+            return;
+        }
+        app.search_term.clone_from(&self.find);
+        if let Err(e) = app.reindex_search() {
+            app.search_offsets.clear();
+            app.search_lengths.clear();
+            labels.notification = e;
+            return;
+        }
+        // Replacing needs to know every match
+        app.receive_search_matches(true);
+        if app.search_offsets.is_empty() {
+            labels.notification = String::from("Query not found");
+            return;
+        }
+
+        if self.all_matches {
+            self.replace_every_match(app, labels);
+            labels.update_all(&app.contents[app.offset..]);
+            return;
+        }
+        let Some(found) = app.search_offsets.iter().position(|&offset| offset == app.offset) else {
+            // Like in other editors, the first enter moves to a match so that it can be seen
+            // before it is replaced.
+            perform_search(app, display, labels, &SearchDirection::Forward);
+            return;
+        };
+        let range = app.offset..app.offset + app.search_lengths[found];
+        if let Err(e) = self.replace_match(app, range) {
+            labels.notification = e;
+            return;
+        }
+        app.dirty = true;
+        perform_search(app, display, labels, &SearchDirection::Forward);
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 5))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let row_style = |field| {
+            if self.field == field {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            }
+        };
+        let scope = if self.all_matches { "< All matches >" } else { "< Match at cursor >" };
+        let lines = vec![
+            Line::from(Span::styled(format!("Find:    {}", self.find), row_style(Field::Find))),
+            Line::from(Span::styled(
+                format!("Replace: {}", self.replacement),
+                row_style(Field::Replacement),
+            )),
+            Line::from(Span::styled(format!("Scope:   {scope}"), row_style(Field::Scope))),
+        ];
+        Paragraph::new(lines).block(
+            Block::default()
+                .title("Replace:")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Replace {
+    pub(crate) fn new(app: &Data) -> Self {
+        Self {
+            find: app.search_term.clone(),
+            replacement: String::new(),
+            field: Field::Find,
+            all_matches: false,
+        }
+    }
+
+    /// Returns the bytes that replace a match of the given length, which are the bytes that the
+    /// replacement decodes to with the `hex:` prefix, or its text otherwise.
+    fn replacement_bytes(&self, len: usize) -> Result<Vec<u8>, String> {
+        let bytes = if let Some(hex) = self.replacement.strip_prefix(HEX_PREFIX) {
+            hex::decode(hex.replace(char::is_whitespace, ""))
+                .map_err(|e| format!("Invalid hex: {e}"))?
+        } else {
+            let text = self.replacement.strip_prefix(ASCII_PREFIX).unwrap_or(&self.replacement);
+            text.as_bytes().to_vec()
+        };
+        if bytes.len() != len {
+            return Err(format!("The replacement must be {len} bytes long"));
+        }
+        Ok(bytes)
+    }
+
+    /// Returns the bytes to overwrite the match in the range with, after checking that they fit
+    /// and that the match isn't protected.
+    fn checked_replacement(&self, app: &Data, range: &Range<usize>) -> Result<Vec<u8>, String> {
+        let bytes = self.replacement_bytes(range.len())?;
+        if range.clone().any(|offset| app.is_protected(offset)) {
+            return Err(String::from("Can't replace protected bytes"));
+        }
+        Ok(bytes)
+    }
+
+    /// Overwrites a match with the replacement, recording it as a single action.
+    fn replace_match(&self, app: &mut Data, range: Range<usize>) -> Result<(), String> {
+        let bytes = self.checked_replacement(app, &range)?;
+        app.overwrite(range.start, &bytes);
+        Ok(())
+    }
+
+    /// Replaces every match that doesn't overlap with an earlier one, recording it as a single
+    /// action. Nothing is replaced if the replacement can't replace one of the matches.
+    fn replace_every_match(&self, app: &mut Data, labels: &mut LabelHandler) {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for (&offset, &len) in app.search_offsets.iter().zip(&app.search_lengths) {
+            if ranges.last().is_none_or(|last| last.end <= offset) {
+                ranges.push(offset..offset + len);
+            }
+        }
+        let replacements = match ranges
+            .iter()
+            .map(|range| self.checked_replacement(app, range))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(replacements) => replacements,
+            Err(e) => {
+                labels.notification = e;
+                return;
+            }
+        };
+
+        // Only the matches are kept to undo it, rather than everything between them
+        let overwritten = ranges
+            .iter()
+            .map(|range| (range.start, app.contents[range.clone()].to_vec()))
+            .collect();
+        app.actions.push(Action::OverwriteMany(overwritten));
+        for (range, bytes) in ranges.iter().zip(&replacements) {
+            app.contents[range.clone()].copy_from_slice(bytes);
+        }
+        app.dirty = true;
+        labels.notification = match app.reindex_search() {
+            Ok(()) => format!("Replaced {} matches", ranges.len()),
+            Err(e) => e,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+    use crate::{
+        app::{tests, Application, Options},
+        windows::search::jump_to_next_match,
+    };
+
+    use super::*;
+
+    /// Opens a replace window on the contents that replaces `find` with `replacement`.
+    fn open(contents: &[u8], find: &str, replacement: &str) -> (Application, Replace) {
+        let (app, _) = tests::open(contents, 0, &Options::default());
+        let mut replace = Replace::new(&app.data);
+        replace.find = String::from(find);
+        replace.replacement = String::from(replacement);
+        replace.field = Field::Replacement;
+        (app, replace)
+    }
+
+    /// Presses enter in the window, waiting for the search it starts to find the next match.
+    fn enter(app: &mut Application, replace: &mut Replace) {
+        replace.enter(&mut app.data, &mut app.display, &mut app.labels);
+        if let Some(direction) = app.data.pending_search.take() {
+            app.data.receive_search_matches(true);
+            jump_to_next_match(&mut app.data, &mut app.display, &mut app.labels, &direction);
+        }
+    }
+
+    #[test]
+    fn test_replace_next() {
+        let (mut app, mut replace) = open(b".abc.abc", "abc", "xyz");
+
+        // The first enter only moves to the next match, and the next ones replace it
+        enter(&mut app, &mut replace);
+        assert_eq!(&app.data.contents[..], b".abc.abc");
+        assert_eq!(app.data.offset, 1);
+        enter(&mut app, &mut replace);
+        assert_eq!(&app.data.contents[..], b".xyz.abc");
+        assert_eq!(app.data.offset, 5);
+        assert!(app.data.dirty);
+
+        // Hexadecimal replaces a match with the bytes it decodes to
+        replace.replacement = String::from("hex:00 FF 00");
+        enter(&mut app, &mut replace);
+        assert_eq!(&app.data.contents[..], b".xyz.\x00\xFF\x00");
+    }
+
+    #[test]
+    fn test_replacement_prefix() {
+        // Without a prefix, the replacement is text even when it looks like hexadecimal
+        let (mut app, mut replace) = open(b"AB.A", "A", "41");
+        replace.all_matches = true;
+        enter(&mut app, &mut replace);
+        assert_eq!(app.labels.notification, "The replacement must be 1 bytes long");
+        replace.find = String::from("AB");
+        enter(&mut app, &mut replace);
+        assert_eq!(&app.data.contents[..], b"41.A");
+
+        replace.find = String::from("A");
+        replace.replacement = String::from("hex:42");
+        enter(&mut app, &mut replace);
+        assert_eq!(&app.data.contents[..], b"41.B");
+        replace.find = String::from("hex:34");
+        replace.replacement = String::from("ascii:hex:");
+        enter(&mut app, &mut replace);
+        assert_eq!(app.labels.notification, "The replacement must be 1 bytes long");
+        replace.replacement = String::from("hex:4G");
+        enter(&mut app, &mut replace);
+        assert!(app.labels.notification.starts_with("Invalid hex"));
+    }
+
+    #[test]
+    fn test_replace_all() {
+        let (mut app, mut replace) = open(b"abcabc.abc", "abc", "xyz");
+        replace.all_matches = true;
+        enter(&mut app, &mut replace);
+        assert_eq!(&app.data.contents[..], b"xyzxyz.xyz");
+        assert_eq!(app.labels.notification, "Replaced 3 matches");
+
+        // Every match is restored by a single undo
+        let undo = Event::Key(KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL));
+        app.handle_input(&undo).unwrap();
+        assert_eq!(&app.data.contents[..], b"abcabc.abc");
+
+        // Only the replaced bytes are kept to undo it, not the bytes between the matches
+        let mut contents = vec![0; 10000];
+        contents[..3].copy_from_slice(b"abc");
+        contents[9997..].copy_from_slice(b"abc");
+        let (mut app, mut replace) = open(&contents, "abc", "xyz");
+        replace.all_matches = true;
+        enter(&mut app, &mut replace);
+        let Some(Action::OverwriteMany(overwritten)) = app.data.actions.last() else {
+            panic!("Replacing every match should be a single action");
+        };
+        assert_eq!(overwritten, &[(0, b"abc".to_vec()), (9997, b"abc".to_vec())]);
+        app.handle_input(&undo).unwrap();
+        assert_eq!(app.data.contents[..], contents);
+    }
+
+    #[test]
+    fn test_replacement_length() {
+        // A replacement of a different length than the match is rejected
+        let (mut app, mut replace) = open(b"abcabc", "abc", "xy");
+        replace.all_matches = true;
+        enter(&mut app, &mut replace);
+        assert_eq!(app.labels.notification, "The replacement must be 3 bytes long");
+        assert_eq!(&app.data.contents[..], b"abcabc");
+        assert!(app.data.actions.is_empty());
+
+        replace.all_matches = false;
+        enter(&mut app, &mut replace);
+        assert_eq!(app.labels.notification, "The replacement must be 3 bytes long");
+        assert_eq!(&app.data.contents[..], b"abcabc");
+    }
+}
@@ -0,0 +1,111 @@
+use ratatui::{
+    layout::Alignment,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{app::Data, backup, label::Handler as LabelHandler, screen::Handler as ScreenHandler};
+
+use super::{KeyHandler, PopupOutput, Window};
+
+/// A window that offers to restore a backup written with `--autosave` that is newer than the
+/// file, which happens when heh was killed or crashed before the edits were saved.
+///
+/// This is opened when heh starts.
+pub(crate) struct RestoreBackup {
+    pub(crate) should_restore: bool,
+}
+
+impl KeyHandler for RestoreBackup {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::RestoreBackup
+    }
+    fn left(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        if !self.should_restore {
+            self.should_restore = true;
+        }
+    }
+    fn right(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        if self.should_restore {
+            self.should_restore = false;
+        }
+    }
+    fn enter(&mut self, app: &mut Data, _: &mut ScreenHandler, labels: &mut LabelHandler) {
+        if self.should_restore {
+            restore(app, labels);
+        }
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Boolean(self.should_restore)
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 5))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let message = vec![
+            Line::from(Span::styled(
+                "Do you want to restore the backup? (y/n)",
+                Style::default().fg(Color::White),
+            )),
+            Line::from(Span::from("")),
+            Line::from(vec![
+                Span::styled(
+                    "    Yes    ",
+                    if self.should_restore {
+                        Style::default()
+                    } else {
+                        Style::default().fg(Color::White)
+                    },
+                ),
+                Span::styled(
+                    "    No    ",
+                    if self.should_restore {
+                        Style::default().fg(Color::White)
+                    } else {
+                        Style::default()
+                    },
+                ),
+            ]),
+        ];
+        Paragraph::new(message).alignment(Alignment::Center).block(
+            Block::default()
+                .title(Span::styled(
+                    "A Newer Backup Was Found.",
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                ))
+                .title_alignment(Alignment::Center)
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl RestoreBackup {
+    pub(crate) fn new() -> Self {
+        RestoreBackup { should_restore: false }
+    }
+}
+
+/// Replaces the contents with the backup of the file as a single action, so that it can be undone.
+/// The file itself is only changed once the contents are saved.
+pub(crate) fn restore(app: &mut Data, labels: &mut LabelHandler) {
+    let Some(path) = &app.path else {
+        return;
+    };
+    let backup = backup::path_for(path);
+    match std::fs::read(&backup) {
+        Ok(bytes) if bytes.is_empty() => {
+            labels.notification = String::from("The backup is empty");
+        }
+        Ok(bytes) => {
+            app.contents.block();
+            app.replace_bytes(0..app.contents.len(), &bytes);
+            app.dirty = true;
+            app.offset = app.offset.min(bytes.len() - 1);
+            labels.update_all(&app.contents[app.offset..]);
+            labels.notification = String::from("Restored the backup");
+        }
+        Err(e) => labels.notification = format!("Can't read {}: {e}", backup.display()),
+    }
+}
@@ -13,11 +13,14 @@ use super::{adjust_offset, KeyHandler, PopupOutput, Window};
 ///
 /// This can be opened by pressing `CNTRLf`.
 ///
-/// Each symbol group is either parsed as hexadecimal if it is preceded with "0x", or decimal if
-/// not.
+/// Input is searched for as ASCII, unless it is preceded with one of these prefixes, which choose
+/// how it is searched for instead:
 ///
-/// Replace ASCII "0x", with "0x30x", (0x30 is hexadecimal for ascii 0) e.g. to search for "0xFF"
-/// in ASCII, search for "0x30xFF" instead.
+/// - "hex:" is hexadecimal, where "?" matches any nibble, e.g. "hex:DE ?D BE EF".
+/// - "re:" is a regular expression over the bytes of the file, e.g. "re:[a-z]+@[a-z]+\.com".
+/// - "bits:" is a pattern of bits where "?" matches either bit, e.g. "bits:1010????".
+/// - "ascii:" is ASCII, which is only needed to search for text starting with a prefix, e.g.
+///   "ascii:hex:".
 #[derive(PartialEq, Eq)]
 pub(crate) struct Search {
     pub(crate) input: String,
@@ -36,7 +39,7 @@ impl KeyHandler for Search {
     fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
         self.input.push(c);
     }
-    fn get_user_input(&self) -> PopupOutput {
+    fn get_user_input(&self) -> PopupOutput<'_> {
         PopupOutput::Str(&self.input)
     }
     fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
@@ -50,23 +53,29 @@ impl KeyHandler for Search {
         }
 
         app.search_term.clone_from(&self.input);
-        app.reindex_search();
+        if let Err(e) = app.reindex_search() {
+            app.search_offsets.clear();
+            app.search_lengths.clear();
+            labels.notification = e;
+            return;
+        }
 
         perform_search(app, display, labels, &SearchDirection::Forward);
     }
     fn dimensions(&self) -> Option<(u16, u16)> {
         Some((50, 3))
     }
-    fn widget(&self) -> Paragraph {
+    fn widget(&self) -> Paragraph<'_> {
         Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
             Block::default()
-                .title("Search:")
+                .title("Search text, or hex:, re:, bits:")
                 .borders(Borders::ALL)
                 .style(Style::default().fg(Color::Yellow)),
         )
     }
 }
 
+#[derive(Clone)]
 pub(crate) enum SearchDirection {
     Forward,
     Backward,
@@ -83,8 +92,38 @@ pub(crate) fn perform_search(
     }
 
     // Cached search data may be invalidated if contents have changed
-    if app.dirty {
-        app.reindex_search();
+    if let Err(e) = app.update_search() {
+        labels.notification = e;
+        return;
+    }
+
+    jump_to_next_match(app, display, labels, search_direction);
+}
+
+/// Moves the cursor to the next match in the direction. If the reindex is still running and
+/// hasn't found that match yet, this waits for it to be found by
+/// [`receive_search`](crate::app::Application).
+pub(crate) fn jump_to_next_match(
+    app: &mut Data,
+    display: &mut ScreenHandler,
+    labels: &mut LabelHandler,
+    search_direction: &SearchDirection,
+) {
+    app.receive_search_matches(false);
+    if let Some(index) = &app.search_index {
+        // Matches are found from the start of the file to the end
+        let found = match search_direction {
+            SearchDirection::Forward => app.search_offsets.last().is_some_and(|&o| o > app.offset),
+            SearchDirection::Backward => {
+                index.searched >= app.offset
+                    && app.search_offsets.first().is_some_and(|&o| o < app.offset)
+            }
+        };
+        if !found {
+            app.pending_search = Some(search_direction.clone());
+            labels.notification = format!("Searching: {}%", index.progress());
+            return;
+        }
     }
 
     // This check needs to happen after reindexing search
@@ -96,9 +135,12 @@ pub(crate) fn perform_search(
     let idx = get_next_match_index(&app.search_offsets, app.offset, search_direction);
     let found_position = *app.search_offsets.get(idx).expect("There should be at least one result");
 
+    // The number of matches is still growing while the reindex runs
+    let more = if app.search_index.is_some() { "+" } else { "" };
     labels.notification =
-        format!("Search: {} [{}/{}]", app.search_term, idx + 1, app.search_offsets.len());
+        format!("Search: {} [{}/{}{more}]", app.search_term, idx + 1, app.search_offsets.len());
 
+    app.discard_pending_nibble();
     app.offset = found_position;
     labels.update_all(&app.contents[app.offset..]);
     adjust_offset(app, display, labels);
@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{app::Data, export, label::Handler as LabelHandler, screen::Handler as ScreenHandler};
+
+use super::{KeyHandler, PopupOutput, Window};
+
+/// A window that writes the bytes before the cursor to one file and the remaining bytes to
+/// another.
+///
+/// This can be opened by pressing `ALTs`.
+///
+/// The input is the two paths to write to, separated by whitespace, e.g. `header.bin body.bin`.
+/// Neither path may be the file that is currently open.
+#[derive(PartialEq, Eq)]
+pub(crate) struct Split {
+    pub(crate) input: String,
+}
+
+impl KeyHandler for Split {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Split
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        self.input.push(c);
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.input)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.input.pop();
+    }
+    fn enter(&mut self, app: &mut Data, _: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let mut paths = self.input.split_whitespace().map(Path::new);
+        let (Some(first), Some(second), None) = (paths.next(), paths.next(), paths.next()) else {
+            labels.notification = String::from("Expected two paths");
+            return;
+        };
+        if first == second {
+            labels.notification = String::from("The paths must be different");
+            return;
+        }
+        if let Some(open_path) = &app.path {
+            if [first, second].iter().any(|path| export::is_same_file(path, open_path)) {
+                labels.notification = String::from("Can't overwrite the open file");
+                return;
+            }
+        }
+
+        app.contents.block();
+        let (head, tail) = app.contents.split_at(app.offset);
+        labels.notification = match export::split(head, tail, first, second) {
+            Ok(()) => format!(
+                "Wrote {} ({} bytes) and {} ({} bytes)",
+                first.display(),
+                head.len(),
+                second.display(),
+                tail.len()
+            ),
+            Err(e) => format!("Can't split the file: {e}"),
+        };
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 3))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
+            Block::default()
+                .title("Split At Cursor (first second):")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Split {
+    pub(crate) fn new() -> Self {
+        Self { input: String::new() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::app::{tests::open, Options};
+
+    use super::*;
+
+    /// Splits the contents at the offset into the paths, returning the notification.
+    fn split(contents: &[u8], offset: usize, input: &str) -> String {
+        let (mut app, _) = open(contents, offset, &Options::default());
+        let mut split = Split { input: String::from(input) };
+        split.enter(&mut app.data, &mut app.display, &mut app.labels);
+        app.labels.notification
+    }
+
+    #[test]
+    fn test_split() {
+        let first = env::temp_dir().join(format!("heh-split-head-{}", process::id()));
+        let second = env::temp_dir().join(format!("heh-split-tail-{}", process::id()));
+        let input = format!("{} {}", first.display(), second.display());
+        assert_eq!(
+            split(b"header body", 6, &input),
+            format!("Wrote {} (6 bytes) and {} (5 bytes)", first.display(), second.display())
+        );
+        assert_eq!(fs::read(&first).unwrap(), b"header");
+        assert_eq!(fs::read(&second).unwrap(), b" body");
+        fs::remove_file(&first).unwrap();
+        fs::remove_file(&second).unwrap();
+
+        assert_eq!(split(b"ab", 1, "one"), "Expected two paths");
+        assert_eq!(split(b"ab", 1, "one two three"), "Expected two paths");
+        assert_eq!(split(b"ab", 1, "one one"), "The paths must be different");
+    }
+}
@@ -0,0 +1,203 @@
+use std::ops::Range;
+
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::Data, character::Category, label::Handler as LabelHandler,
+    screen::Handler as ScreenHandler,
+};
+
+use super::{adjust_offset, KeyHandler, PopupOutput, Window};
+
+/// The minimum length of the strings listed when the window is opened.
+const DEFAULT_MIN_LENGTH: usize = 4;
+
+/// The number of strings visible at once.
+const VISIBLE_ENTRIES: usize = 16;
+
+/// The number of bytes scanned at a time, between renders.
+const CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+/// A window that lists the runs of printable ASCII in the file, like the `strings` utility.
+///
+/// This can be opened by pressing `CNTRLt`.
+///
+/// Typing a number changes the minimum length of the listed strings. The arrow keys select a
+/// string, and pressing enter moves the cursor to it. The file is scanned a piece at a time so
+/// that large files don't block input.
+pub(crate) struct Strings {
+    min_length: String,
+    entries: Vec<(usize, String)>,
+    selected: usize,
+    top: usize,
+    /// The offset of the next byte to scan and the offset where the run of printable characters
+    /// before it started, while scanning.
+    scanning: Option<(usize, usize)>,
+}
+
+impl KeyHandler for Strings {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Strings
+    }
+    fn up(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_sub(1));
+    }
+    fn down(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_add(1));
+    }
+    fn page_up(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_sub(VISIBLE_ENTRIES));
+    }
+    fn page_down(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_add(VISIBLE_ENTRIES));
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        if c.is_ascii_digit() {
+            self.min_length.push(c);
+            self.rescan();
+        }
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.min_length.pop();
+        self.rescan();
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.min_length)
+    }
+    fn enter(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
+        if let Some(&(offset, _)) = self.entries.get(self.selected) {
+            app.offset = offset;
+            labels.update_all(&app.contents[app.offset..]);
+            adjust_offset(app, display, labels);
+        }
+    }
+    fn work(&mut self, app: &Data) -> bool {
+        let Some((next, start)) = &mut self.scanning else {
+            return false;
+        };
+        // Edits may still be moving bytes around outside of the window.
+        app.contents.block();
+        // The offset past the last byte is scanned too, since it ends the last run
+        let end = (app.contents.len() + 1).min(next.saturating_add(CHUNK_LEN));
+        let min_length = self.min_length.parse().unwrap_or(1).max(1);
+        self.entries.extend(find_strings(&app.contents, *next..end, start, min_length));
+        *next = end;
+        self.scanning = self.scanning.filter(|_| end <= app.contents.len());
+        self.scanning.is_some()
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        #[allow(clippy::cast_possible_truncation)]
+        Some((60, VISIBLE_ENTRIES as u16 + 2))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let lines: Vec<Line> = self
+            .entries
+            .iter()
+            .enumerate()
+            .skip(self.top)
+            .take(VISIBLE_ENTRIES)
+            .map(|(i, (offset, string))| {
+                let style = if i == self.selected {
+                    Style::default().fg(Color::Black).bg(Color::White)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                Line::from(Span::styled(format!("{offset:08X}  {string}"), style))
+            })
+            .collect();
+        let scanning = if self.scanning.is_some() { ", scanning..." } else { "" };
+        let title = format!(
+            "Strings (min length {}): {} found{scanning}",
+            self.min_length,
+            self.entries.len()
+        );
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(title)
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Strings {
+    pub(crate) fn new() -> Self {
+        Self {
+            min_length: DEFAULT_MIN_LENGTH.to_string(),
+            entries: Vec::new(),
+            selected: 0,
+            top: 0,
+            scanning: Some((0, 0)),
+        }
+    }
+
+    /// Starts finding the strings again after the minimum length changes.
+    fn rescan(&mut self) {
+        self.entries.clear();
+        self.scanning = Some((0, 0));
+        self.selected = 0;
+        self.top = 0;
+    }
+
+    /// Selects the entry at the index (or the last one), scrolling it into view.
+    fn select(&mut self, index: usize) {
+        self.selected = index.min(self.entries.len().saturating_sub(1));
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + VISIBLE_ENTRIES {
+            self.top = self.selected + 1 - VISIBLE_ENTRIES;
+        }
+    }
+}
+
+/// Finds the runs of at least `min_length` printable ASCII characters that end in the range of
+/// offsets, returning the offset of each run alongside it. `start` is the offset where the run
+/// before the range started, and is moved past every run that ends. The end of the contents also
+/// ends a run, so the range includes the offset past the last byte to find the last one.
+fn find_strings(
+    contents: &[u8],
+    range: Range<usize>,
+    start: &mut usize,
+    min_length: usize,
+) -> Vec<(usize, String)> {
+    let mut strings = Vec::new();
+    for offset in range {
+        if contents.get(offset).is_some_and(|&byte| Category::is_printable(byte)) {
+            continue;
+        }
+        if offset - *start >= min_length {
+            let string = String::from_utf8_lossy(&contents[*start..offset]).into_owned();
+            strings.push((*start, string));
+        }
+        *start = offset + 1;
+    }
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_strings() {
+        let contents = b"\x00\x01hello\xFFhi\x00world wide";
+        assert_eq!(
+            find_strings(contents, 0..22, &mut 0, 4),
+            vec![(2, String::from("hello")), (11, String::from("world wide"))]
+        );
+        assert_eq!(find_strings(contents, 0..22, &mut 0, 2).len(), 3);
+
+        // A run that continues into the next range is found once it ends
+        let mut start = 0;
+        assert_eq!(find_strings(contents, 0..14, &mut start, 4), vec![(2, String::from("hello"))]);
+        assert_eq!(start, 11);
+        assert_eq!(
+            find_strings(contents, 14..22, &mut start, 4),
+            vec![(11, String::from("world wide"))]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::Data, label::Handler as LabelHandler, screen::Handler as ScreenHandler, symbol::Symbol,
+};
+
+use super::{adjust_offset, KeyHandler, Window};
+
+/// The number of symbols visible at once.
+const VISIBLE_ENTRIES: usize = 16;
+
+/// A window that lists the symbols loaded with `--symbols`.
+///
+/// This can be opened by pressing `ALTm`.
+///
+/// The arrow keys select a symbol, and pressing enter moves the cursor to it.
+pub(crate) struct Symbols {
+    entries: Vec<Symbol>,
+    selected: usize,
+    top: usize,
+}
+
+impl KeyHandler for Symbols {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::Symbols
+    }
+    fn up(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_sub(1));
+    }
+    fn down(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_add(1));
+    }
+    fn page_up(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_sub(VISIBLE_ENTRIES));
+    }
+    fn page_down(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.select(self.selected.saturating_add(VISIBLE_ENTRIES));
+    }
+    fn enter(&mut self, app: &mut Data, display: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let Some(symbol) = self.entries.get(self.selected) else {
+            return;
+        };
+        if symbol.offset >= app.contents.len() {
+            labels.notification = format!("{} is past the end of the file", symbol.name);
+            return;
+        }
+        app.offset = symbol.offset;
+        labels.update_all(&app.contents[app.offset..]);
+        adjust_offset(app, display, labels);
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        #[allow(clippy::cast_possible_truncation)]
+        Some((60, VISIBLE_ENTRIES as u16 + 2))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        let lines: Vec<Line> = if self.entries.is_empty() {
+            vec![Line::from(Span::styled(
+                "No symbols were loaded with --symbols",
+                Style::default().fg(Color::White),
+            ))]
+        } else {
+            self.entries
+                .iter()
+                .enumerate()
+                .skip(self.top)
+                .take(VISIBLE_ENTRIES)
+                .map(|(i, symbol)| {
+                    let style = if i == self.selected {
+                        Style::default().fg(Color::Black).bg(Color::White)
+                    } else {
+                        Style::default().fg(Color::White)
+                    };
+                    Line::from(Span::styled(
+                        format!("{:08X}  {}", symbol.offset, symbol.name),
+                        style,
+                    ))
+                })
+                .collect()
+        };
+        Paragraph::new(lines).block(
+            Block::default()
+                .title(format!("Symbols: {}", self.entries.len()))
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl Symbols {
+    pub(crate) fn new(app: &Data) -> Self {
+        Self { entries: app.symbols.clone(), selected: 0, top: 0 }
+    }
+
+    /// Selects the entry at the index (or the last one), scrolling it into view.
+    fn select(&mut self, index: usize) {
+        self.selected = index.min(self.entries.len().saturating_sub(1));
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + VISIBLE_ENTRIES {
+            self.top = self.selected + 1 - VISIBLE_ENTRIES;
+        }
+    }
+}
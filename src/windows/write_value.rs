@@ -0,0 +1,146 @@
+use ratatui::{
+    style::{Color, Style},
+    text::Span,
+    widgets::{Block, Borders, Paragraph},
+};
+
+use crate::{
+    app::Data,
+    label::{Endianness, Handler as LabelHandler},
+    screen::Handler as ScreenHandler,
+};
+
+use super::{KeyHandler, PopupOutput, Window};
+
+/// A window that writes a number over the bytes at the cursor, in the endianness of the labels.
+///
+/// This can be opened by pressing `ALTt`.
+///
+/// The input is the type followed by the value, e.g. `f32 1.5`, `i16 -2`, or `u32 0xDEADBEEF`.
+/// The types are the ones of the labels: `u8` to `u64`, `i8` to `i64`, `f32`, and `f64`.
+#[derive(PartialEq, Eq)]
+pub(crate) struct WriteValue {
+    pub(crate) input: String,
+}
+
+impl KeyHandler for WriteValue {
+    fn is_focusing(&self, window_type: Window) -> bool {
+        window_type == Window::WriteValue
+    }
+    fn char(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler, c: char) {
+        self.input.push(c);
+    }
+    fn get_user_input(&self) -> PopupOutput<'_> {
+        PopupOutput::Str(&self.input)
+    }
+    fn backspace(&mut self, _: &mut Data, _: &mut ScreenHandler, _: &mut LabelHandler) {
+        self.input.pop();
+    }
+    fn enter(&mut self, app: &mut Data, _: &mut ScreenHandler, labels: &mut LabelHandler) {
+        let bytes = match encode_value(&self.input, &labels.endianness) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                labels.notification = e;
+                return;
+            }
+        };
+        let range = app.offset..app.offset + bytes.len();
+        if range.end > app.contents.len() {
+            labels.notification = format!("The value needs {} bytes after the cursor", bytes.len());
+            return;
+        }
+        if range.clone().any(|offset| app.is_protected(offset)) {
+            labels.notification = String::from("Can't write over protected bytes");
+            return;
+        }
+        app.overwrite(range.start, &bytes);
+        labels.update_all(&app.contents[app.offset..]);
+        labels.notification = format!("Wrote {} bytes", bytes.len());
+    }
+    fn dimensions(&self) -> Option<(u16, u16)> {
+        Some((50, 3))
+    }
+    fn widget(&self) -> Paragraph<'_> {
+        Paragraph::new(Span::styled(&self.input, Style::default().fg(Color::White))).block(
+            Block::default()
+                .title("Write value (type value, e.g. f32 1.5):")
+                .borders(Borders::ALL)
+                .style(Style::default().fg(Color::Yellow)),
+        )
+    }
+}
+
+impl WriteValue {
+    pub(crate) fn new() -> Self {
+        Self { input: String::new() }
+    }
+}
+
+/// Converts input like `u16 0x1234` to the bytes of the value in the given endianness.
+fn encode_value(input: &str, endianness: &Endianness) -> Result<Vec<u8>, String> {
+    let (kind, value) = input
+        .trim()
+        .split_once(char::is_whitespace)
+        .ok_or_else(|| String::from("Expected a type and a value, e.g. f32 1.5"))?;
+    let value = value.trim();
+    let out_of_range = |_| format!("{value} is out of range for {kind}");
+    let invalid_float = |_| format!("Invalid number: {value}");
+    let mut bytes = match kind {
+        "u8" => u8::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "u16" => u16::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "u32" => u32::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "u64" => u64::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "i8" => i8::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "i16" => i16::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "i32" => i32::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "i64" => i64::try_from(parse_integer(value)?).map_err(out_of_range)?.to_be_bytes().to_vec(),
+        "f32" => value.parse::<f32>().map_err(invalid_float)?.to_be_bytes().to_vec(),
+        "f64" => value.parse::<f64>().map_err(invalid_float)?.to_be_bytes().to_vec(),
+        _ => return Err(format!("Unknown type {kind}, expected one like u8, i32, or f64")),
+    };
+    if let Endianness::LittleEndian = endianness {
+        bytes.reverse();
+    }
+    Ok(bytes)
+}
+
+/// Parses an integer that is hexadecimal if it is preceded with "0x", or decimal if not, and that
+/// can be negative.
+fn parse_integer(value: &str) -> Result<i128, String> {
+    let (negative, digits) =
+        value.strip_prefix('-').map_or((false, value), |digits| (true, digits));
+    let magnitude = match digits.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => digits.parse(),
+    }
+    .map_err(|_| format!("Invalid number: {value}"))?;
+    let magnitude = i128::from(magnitude);
+    Ok(if negative { -magnitude } else { magnitude })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_value() {
+        let little = Endianness::LittleEndian;
+        assert_eq!(encode_value("u16 0x1234", &little).unwrap(), [0x34, 0x12]);
+        assert_eq!(encode_value("u16 0x1234", &Endianness::BigEndian).unwrap(), [0x12, 0x34]);
+        assert_eq!(encode_value(" i8 -1 ", &little).unwrap(), [0xFF]);
+        assert_eq!(
+            encode_value("i64 -2", &little).unwrap(),
+            [0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+        assert_eq!(encode_value("f32 1.5", &little).unwrap(), 1.5_f32.to_le_bytes());
+        assert_eq!(encode_value("f64 -0.25", &little).unwrap(), (-0.25_f64).to_le_bytes());
+        assert_eq!(encode_value("u64 0xFFFFFFFFFFFFFFFF", &little).unwrap(), [0xFF; 8]);
+
+        assert!(encode_value("u8 256", &little).is_err());
+        assert!(encode_value("u8 -1", &little).is_err());
+        assert!(encode_value("i8 128", &little).is_err());
+        assert!(encode_value("f32 one", &little).is_err());
+        assert!(encode_value("u24 1", &little).is_err());
+        assert!(encode_value("42", &little).is_err());
+    }
+}